@@ -0,0 +1,478 @@
+use crate::route::{CrossoverOp, MutationKind};
+use crate::utils::random_unit_with;
+use rand::Rng;
+use std::sync::Arc;
+
+/// How quickly the credit of an operator follows the rewards it earned in the last generation.
+const ADAPTATION_RATE: f64 = 0.3;
+/// The lowest probability any operator is chosen with, so that an operator that did not improve
+/// the routes for a while can still prove itself useful later.
+const MIN_OPERATOR_PROBABILITY: f64 = 0.05;
+
+/// Learn which mutation and crossover operators improve the routes of a given instance with
+/// probability matching: every operator has a credit that follows the improvements it achieved,
+/// and the operators are chosen with probabilities proportional to their credits. Every operator
+/// keeps a small minimal probability, so that no operator is discarded altogether. The mutations
+/// and the crossovers are chosen and credited independently of each other.
+///
+/// The improvement of a mutation is the relative decrease of the length of the route it was
+/// applied to. The improvement of a crossover is the relative decrease of the length of the child
+/// compared to the shorter of its parents. Operators that make a route longer earn no reward.
+#[derive(Debug, Clone)]
+pub struct AdaptiveOperators {
+    /// The mutation operators that are chosen from.
+    operators: Vec<MutationKind>,
+    /// The credits of the mutation operators.
+    mutation_credits: Credits,
+    /// The crossover operators that are chosen from, if any.
+    crossovers: Vec<Arc<dyn CrossoverOp>>,
+    /// The credits of the crossover operators.
+    crossover_credits: Credits,
+}
+
+// Two adaptive selections are the same if they choose from the same mutations and share the same
+// crossover operators with the same credits, as crossover operators cannot be compared by value.
+impl PartialEq for AdaptiveOperators {
+    fn eq(&self, other: &Self) -> bool {
+        self.operators == other.operators
+            && self.mutation_credits == other.mutation_credits
+            && self.crossovers.len() == other.crossovers.len()
+            && self
+                .crossovers
+                .iter()
+                .zip(other.crossovers.iter())
+                .all(|(crossover, other_crossover)| Arc::ptr_eq(crossover, other_crossover))
+            && self.crossover_credits == other.crossover_credits
+    }
+}
+
+impl AdaptiveOperators {
+    /// Create the adaptive selection for the given mutation operators, without any crossover
+    /// operators, see `with_crossovers`.
+    ///
+    /// # Arguments
+    ///
+    /// * `operators` - The mutation operators that are chosen from.
+    ///
+    /// # Panics
+    ///
+    /// If no operators are given.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::adaptive::AdaptiveOperators;
+    /// use genetic_algo::route::MutationKind;
+    ///
+    /// let operators = AdaptiveOperators::new(vec![MutationKind::Swap, MutationKind::Inversion]);
+    /// assert_eq!(operators.probabilities(), vec![0.5, 0.5]);
+    /// ```
+    pub fn new(operators: Vec<MutationKind>) -> Self {
+        assert!(
+            !operators.is_empty(),
+            "At least one mutation operator is needed."
+        );
+        AdaptiveOperators {
+            mutation_credits: Credits::new(operators.len()),
+            operators,
+            crossovers: Vec::new(),
+            crossover_credits: Credits::new(0),
+        }
+    }
+    /// Choose the children of every pair of parents from the given crossover operators, instead
+    /// of creating them with `GaConfig::crossover`. All crossover operators start without credit.
+    ///
+    /// # Arguments
+    ///
+    /// * `crossovers` - The crossover operators that are chosen from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::adaptive::AdaptiveOperators;
+    /// use genetic_algo::route::{FixedDepotCrossover, OrderedCrossover};
+    /// use std::sync::Arc;
+    ///
+    /// let operators = AdaptiveOperators::default()
+    ///     .with_crossovers(vec![Arc::new(OrderedCrossover), Arc::new(FixedDepotCrossover { depot: 0 })]);
+    /// assert_eq!(operators.crossover_probabilities(), vec![0.5, 0.5]);
+    /// ```
+    pub fn with_crossovers(mut self, crossovers: Vec<Arc<dyn CrossoverOp>>) -> Self {
+        self.crossover_credits = Credits::new(crossovers.len());
+        self.crossovers = crossovers;
+        self
+    }
+    /// Get the mutation operators that are chosen from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::adaptive::AdaptiveOperators;
+    /// use genetic_algo::route::MutationKind;
+    ///
+    /// let operators = AdaptiveOperators::new(vec![MutationKind::Swap]);
+    /// assert_eq!(operators.operators(), &[MutationKind::Swap]);
+    /// ```
+    pub fn operators(&self) -> &[MutationKind] {
+        &self.operators
+    }
+    /// Get the crossover operators that are chosen from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::adaptive::AdaptiveOperators;
+    ///
+    /// assert!(AdaptiveOperators::default().crossovers().is_empty());
+    /// ```
+    pub fn crossovers(&self) -> &[Arc<dyn CrossoverOp>] {
+        &self.crossovers
+    }
+    /// Get the probability with which every mutation operator is chosen, in the order of
+    /// `operators`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::adaptive::AdaptiveOperators;
+    ///
+    /// let operators = AdaptiveOperators::default();
+    /// assert_eq!(operators.probabilities(), vec![0.25; 4]);
+    /// ```
+    pub fn probabilities(&self) -> Vec<f64> {
+        self.mutation_credits.probabilities()
+    }
+    /// Get the probability with which every crossover operator is chosen, in the order of
+    /// `crossovers`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::adaptive::AdaptiveOperators;
+    /// use genetic_algo::route::OrderedCrossover;
+    /// use std::sync::Arc;
+    ///
+    /// let operators = AdaptiveOperators::default().with_crossovers(vec![Arc::new(OrderedCrossover)]);
+    /// assert_eq!(operators.crossover_probabilities(), vec![1.0]);
+    /// ```
+    pub fn crossover_probabilities(&self) -> Vec<f64> {
+        self.crossover_credits.probabilities()
+    }
+    /// Choose the position of a mutation operator in `operators` according to `probabilities`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator used to choose the operator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::adaptive::AdaptiveOperators;
+    ///
+    /// let operators = AdaptiveOperators::default();
+    /// assert!(operators.choose(&mut rand::thread_rng()) < 4);
+    /// ```
+    pub fn choose(&self, rng: &mut impl Rng) -> usize {
+        self.mutation_credits.choose(rng)
+    }
+    /// Choose the position of a crossover operator in `crossovers` according to
+    /// `crossover_probabilities`. Without any crossover operators nothing is chosen.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator used to choose the operator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::adaptive::AdaptiveOperators;
+    /// use genetic_algo::route::OrderedCrossover;
+    /// use std::sync::Arc;
+    ///
+    /// let operators = AdaptiveOperators::default();
+    /// assert_eq!(operators.choose_crossover(&mut rand::thread_rng()), None);
+    /// let operators = operators.with_crossovers(vec![Arc::new(OrderedCrossover)]);
+    /// assert_eq!(operators.choose_crossover(&mut rand::thread_rng()), Some(0));
+    /// ```
+    pub fn choose_crossover(&self, rng: &mut impl Rng) -> Option<usize> {
+        if self.crossovers.is_empty() {
+            None
+        } else {
+            Some(self.crossover_credits.choose(rng))
+        }
+    }
+    /// Remember that the mutation operator at position `operator` changed the length of a route
+    /// from `length_before` to `length_after`. The credits only change with `end_generation`.
+    ///
+    /// # Arguments
+    ///
+    /// * `operator` - The position of the operator in `operators`.
+    /// * `length_before` - The length of the route before the mutation.
+    /// * `length_after` - The length of the route after the mutation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::adaptive::AdaptiveOperators;
+    ///
+    /// let mut operators = AdaptiveOperators::default();
+    /// operators.record(0, 10.0, 8.0);
+    /// operators.end_generation();
+    /// assert!(operators.probabilities()[0] > 0.25);
+    /// ```
+    pub fn record(&mut self, operator: usize, length_before: f64, length_after: f64) {
+        self.mutation_credits
+            .record(operator, length_before, length_after);
+    }
+    /// Remember that the crossover operator at position `crossover` created a child of length
+    /// `child_length` from parents whose shorter one has the length `parent_length`. The credits
+    /// only change with `end_generation`.
+    ///
+    /// # Arguments
+    ///
+    /// * `crossover` - The position of the operator in `crossovers`.
+    /// * `parent_length` - The length of the shorter parent.
+    /// * `child_length` - The length of the child.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::adaptive::AdaptiveOperators;
+    /// use genetic_algo::route::OrderedCrossover;
+    /// use std::sync::Arc;
+    ///
+    /// let mut operators = AdaptiveOperators::default()
+    ///     .with_crossovers(vec![Arc::new(OrderedCrossover), Arc::new(OrderedCrossover)]);
+    /// operators.record_crossover(0, 10.0, 8.0);
+    /// operators.end_generation();
+    /// assert!(operators.crossover_probabilities()[0] > 0.5);
+    /// ```
+    pub fn record_crossover(&mut self, crossover: usize, parent_length: f64, child_length: f64) {
+        self.crossover_credits
+            .record(crossover, parent_length, child_length);
+    }
+    /// Move the credit of every operator that was used since the last call towards the mean
+    /// reward it earned. Operators that were not used keep their credit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::adaptive::AdaptiveOperators;
+    ///
+    /// let mut operators = AdaptiveOperators::default();
+    /// operators.record(0, 10.0, 8.0);
+    /// operators.record(1, 10.0, 12.0);
+    /// operators.end_generation();
+    /// assert!(operators.probabilities()[1] < 0.25);
+    /// ```
+    pub fn end_generation(&mut self) {
+        self.mutation_credits.end_generation();
+        self.crossover_credits.end_generation();
+    }
+}
+
+impl Default for AdaptiveOperators {
+    /// Choose from all kinds of mutations: `Insertion`, `Swap`, `Inversion` and `DoubleBridge`,
+    /// without any crossover operators.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::adaptive::AdaptiveOperators;
+    ///
+    /// assert_eq!(AdaptiveOperators::default().operators().len(), 4);
+    /// ```
+    fn default() -> Self {
+        AdaptiveOperators::new(vec![
+            MutationKind::Insertion,
+            MutationKind::Swap,
+            MutationKind::Inversion,
+            MutationKind::DoubleBridge,
+        ])
+    }
+}
+
+/// The credits of a group of operators that are chosen from with probability matching.
+#[derive(Debug, Clone, PartialEq)]
+struct Credits {
+    /// The credit of every operator. All operators start without credit, which makes them equally
+    /// likely.
+    credits: Vec<f64>,
+    /// The rewards earned by every operator since the last call of `end_generation`.
+    pending_rewards: Vec<Vec<f64>>,
+}
+
+impl Credits {
+    /// Create the credits of `n_operators` operators, none of which earned any credit yet.
+    fn new(n_operators: usize) -> Self {
+        Credits {
+            credits: vec![0.0; n_operators],
+            pending_rewards: vec![Vec::new(); n_operators],
+        }
+    }
+    /// Get the probability with which every operator is chosen.
+    fn probabilities(&self) -> Vec<f64> {
+        let n_operators = self.credits.len() as f64;
+        let total_credit = self.credits.iter().sum::<f64>();
+        if total_credit <= 0.0 {
+            return vec![1.0 / n_operators; self.credits.len()];
+        }
+        let min_probability = MIN_OPERATOR_PROBABILITY.min(1.0 / n_operators);
+        self.credits
+            .iter()
+            .map(|credit| {
+                min_probability + (1.0 - n_operators * min_probability) * credit / total_credit
+            })
+            .collect()
+    }
+    /// Choose the position of an operator according to `probabilities`.
+    fn choose(&self, rng: &mut impl Rng) -> usize {
+        let mut remaining = random_unit_with(rng);
+        let probabilities = self.probabilities();
+        for (idx, probability) in probabilities.iter().enumerate() {
+            if remaining < *probability {
+                return idx;
+            }
+            remaining -= probability;
+        }
+        // Rounding errors can leave a tiny remainder, which belongs to the last operator.
+        probabilities.len() - 1
+    }
+    /// Remember the relative improvement from `length_before` to `length_after` as a reward of
+    /// the operator at position `operator`.
+    fn record(&mut self, operator: usize, length_before: f64, length_after: f64) {
+        let reward = if length_before > 0.0 {
+            ((length_before - length_after) / length_before).max(0.0)
+        } else {
+            0.0
+        };
+        self.pending_rewards[operator].push(reward);
+    }
+    /// Move the credit of every operator that was used since the last call towards the mean
+    /// reward it earned.
+    fn end_generation(&mut self) {
+        for (credit, rewards) in self.credits.iter_mut().zip(self.pending_rewards.iter_mut()) {
+            if !rewards.is_empty() {
+                let mean_reward = rewards.iter().sum::<f64>() / rewards.len() as f64;
+                *credit += ADAPTATION_RATE * (mean_reward - *credit);
+                rewards.clear();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    mod test_probabilities {
+        use super::*;
+        #[test]
+        fn sum_to_one() {
+            let mut operators = AdaptiveOperators::default();
+            operators.record(0, 10.0, 9.0);
+            operators.record(2, 10.0, 5.0);
+            operators.record(3, 10.0, 11.0);
+            operators.end_generation();
+            assert!((operators.probabilities().iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        }
+        #[test]
+        fn never_improving_operator_decays() {
+            let mut operators = AdaptiveOperators::new(vec![
+                MutationKind::Swap,
+                MutationKind::Inversion,
+                MutationKind::DoubleBridge,
+            ]);
+            let initial_probability = operators.probabilities()[0];
+            let mut probability = initial_probability;
+            for _ in 0..50 {
+                operators.record(0, 10.0, 10.5);
+                operators.record(1, 10.0, 9.0);
+                operators.record(2, 10.0, 9.5);
+                operators.end_generation();
+                let next_probability = operators.probabilities()[0];
+                assert!(next_probability <= probability);
+                probability = next_probability;
+            }
+            assert!(probability < initial_probability);
+            assert!((probability - MIN_OPERATOR_PROBABILITY).abs() < 1e-12);
+        }
+        #[test]
+        fn unused_operators_keep_credit() {
+            let mut operators = AdaptiveOperators::new(vec![MutationKind::Swap; 2]);
+            operators.end_generation();
+            assert_eq!(operators.probabilities(), vec![0.5, 0.5]);
+        }
+        #[test]
+        fn credit_decays_without_improvements() {
+            let mut operators = AdaptiveOperators::new(vec![MutationKind::Swap; 2]);
+            operators.record(0, 10.0, 5.0);
+            operators.record(1, 10.0, 9.0);
+            operators.end_generation();
+            let first_probability = operators.probabilities()[0];
+            for _ in 0..20 {
+                operators.record(0, 10.0, 11.0);
+                operators.record(1, 10.0, 9.0);
+                operators.end_generation();
+            }
+            assert!(operators.probabilities()[0] < first_probability);
+            assert!(operators.probabilities()[0] < 0.5);
+        }
+    }
+    mod test_crossovers {
+        use super::*;
+        use crate::route::OrderedCrossover;
+        #[test]
+        fn none_without_registration() {
+            let operators = AdaptiveOperators::default();
+            assert!(operators.crossover_probabilities().is_empty());
+            assert_eq!(operators.choose_crossover(&mut rand::thread_rng()), None);
+        }
+        #[test]
+        fn never_improving_crossover_decays() {
+            let mut operators = AdaptiveOperators::default()
+                .with_crossovers(vec![Arc::new(OrderedCrossover), Arc::new(OrderedCrossover)]);
+            for _ in 0..20 {
+                operators.record_crossover(0, 10.0, 10.0);
+                operators.record_crossover(1, 10.0, 9.0);
+                operators.end_generation();
+            }
+            assert!(
+                (operators.crossover_probabilities()[0] - MIN_OPERATOR_PROBABILITY).abs() < 1e-12
+            );
+            // The mutations are credited independently.
+            assert_eq!(operators.probabilities(), vec![0.25; 4]);
+        }
+        #[test]
+        fn same_crossovers_are_equal() {
+            let crossover: Arc<dyn CrossoverOp> = Arc::new(OrderedCrossover);
+            let operators = AdaptiveOperators::default().with_crossovers(vec![crossover.clone()]);
+            assert_eq!(
+                operators,
+                AdaptiveOperators::default().with_crossovers(vec![crossover])
+            );
+            assert_ne!(
+                operators,
+                AdaptiveOperators::default().with_crossovers(vec![Arc::new(OrderedCrossover)])
+            );
+        }
+    }
+    mod test_choose {
+        use super::*;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn follows_probabilities() {
+            let mut operators = AdaptiveOperators::new(vec![MutationKind::Swap; 2]);
+            for _ in 0..50 {
+                operators.record(0, 10.0, 11.0);
+                operators.record(1, 10.0, 9.0);
+                operators.end_generation();
+            }
+            let mut rng = StdRng::seed_from_u64(3);
+            let n_first = (0..1000)
+                .filter(|_| operators.choose(&mut rng) == 0)
+                .count();
+            assert!(n_first < 150);
+        }
+    }
+}
@@ -0,0 +1,457 @@
+use crate::route::CrossoverOp;
+use crate::routes::ReplacementStrategy;
+use std::sync::Arc;
+
+/// How the parents of the next generation are chosen.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Selection {
+    /// Every pair of routes in the population is used as parents.
+    AllPairs,
+    /// Pairs of parents are drawn with a probability proportional to their fitness after it has
+    /// been transformed with the `FitnessScaling` of the `GaConfig`. As many pairs are drawn as
+    /// are needed to create `population_size` children.
+    Proportionate,
+    /// Every parent is the fittest of `size` routes that are drawn uniformly at random, with
+    /// replacement. As many pairs are drawn as are needed to create `population_size` children.
+    Tournament {
+        /// How many routes compete to become a parent.
+        size: usize,
+    },
+}
+
+/// The bounds of the adaptive tournament selection. The tournament size follows the diversity
+/// of the population (see `Routes::edge_diversity`): diverse populations are selected from with
+/// large tournaments to exploit the best routes, populations whose diversity collapsed with
+/// small tournaments to explore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdaptiveSelectionParams {
+    /// The tournament size of a population without diversity.
+    pub min_size: usize,
+    /// The tournament size of a population with maximal diversity.
+    pub max_size: usize,
+}
+
+impl AdaptiveSelectionParams {
+    /// Get the tournament size for a population with the given diversity, interpolated linearly
+    /// between `min_size` and `max_size`.
+    ///
+    /// # Arguments
+    ///
+    /// * `diversity` - The diversity of the population between 0 and 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::config::AdaptiveSelectionParams;
+    ///
+    /// let params = AdaptiveSelectionParams { min_size: 2, max_size: 6 };
+    /// assert_eq!(params.tournament_size(0.0), 2);
+    /// assert_eq!(params.tournament_size(0.5), 4);
+    /// assert_eq!(params.tournament_size(1.0), 6);
+    /// ```
+    pub fn tournament_size(&self, diversity: f64) -> usize {
+        let spread = self.max_size.saturating_sub(self.min_size) as f64;
+        self.min_size + (spread * diversity.clamp(0.0, 1.0)).round() as usize
+    }
+}
+
+/// The local search every child is refined with before it enters the next generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalSearchKind {
+    /// Improve the children with `Route::two_opt`.
+    TwoOpt,
+    /// Improve the children with `Route::or_opt`.
+    OrOpt,
+}
+
+/// How the raw fitnesses of the routes are transformed before they are used in proportionate
+/// selection. The raw fitness of a route is `1 / distance`, so shorter routes are fitter. Scaled
+/// fitnesses below 0 are set to 0, e.g. these routes are never selected.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FitnessScaling {
+    /// Use the raw fitnesses.
+    None,
+    /// Use `a * fitness + b`.
+    Linear {
+        /// The factor the fitness is multiplied with.
+        a: f64,
+        /// The offset that is added to the fitness.
+        b: f64,
+    },
+    /// Use `fitness - (mean - c * std)`, where `mean` and `std` are the mean and standard deviation
+    /// of the fitnesses in the population. Routes that are more than `c` standard deviations less
+    /// fit than the mean are clipped to 0.
+    SigmaTruncation {
+        /// How many standard deviations below the mean a fitness is clipped.
+        c: f64,
+    },
+    /// Use `exp(fitness / temp)`. High temperatures flatten the differences between routes, low
+    /// temperatures increase them.
+    Boltzmann {
+        /// The temperature the fitnesses are divided by.
+        temp: f64,
+    },
+}
+
+impl FitnessScaling {
+    /// Transform raw fitnesses into the weights used by proportionate selection.
+    ///
+    /// # Arguments
+    ///
+    /// * `fitnesses` - The raw fitnesses of the routes, higher is better.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::config::FitnessScaling;
+    ///
+    /// let scaling = FitnessScaling::Linear { a: 2.0, b: -1.0 };
+    /// assert_eq!(scaling.scale(&[0.0, 1.0, 2.0]), vec![0.0, 1.0, 3.0]);
+    /// ```
+    pub fn scale(&self, fitnesses: &[f64]) -> Vec<f64> {
+        let scaled_fitnesses = match self {
+            FitnessScaling::None => fitnesses.to_vec(),
+            FitnessScaling::Linear { a, b } => {
+                fitnesses.iter().map(|fitness| a * fitness + b).collect()
+            }
+            FitnessScaling::SigmaTruncation { c } => {
+                let n_fitnesses = fitnesses.len() as f64;
+                let mean = fitnesses.iter().sum::<f64>() / n_fitnesses;
+                let std = (fitnesses
+                    .iter()
+                    .map(|fitness| (fitness - mean).powi(2))
+                    .sum::<f64>()
+                    / n_fitnesses)
+                    .sqrt();
+                fitnesses
+                    .iter()
+                    .map(|fitness| fitness - (mean - c * std))
+                    .collect()
+            }
+            FitnessScaling::Boltzmann { temp } => fitnesses
+                .iter()
+                .map(|fitness| (fitness / temp).exp())
+                .collect(),
+        };
+        scaled_fitnesses
+            .into_iter()
+            .map(|fitness| fitness.max(0.0))
+            .collect()
+    }
+}
+
+/// The parameters of a genetic algorithm run, e.g. how large the population is and how the
+/// individuals are changed and selected from generation to generation.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algo::config::GaConfig;
+///
+/// let config = GaConfig {
+///     population_size: 50,
+///     ..GaConfig::default()
+/// };
+/// ```
+#[derive(Debug, Clone)]
+pub struct GaConfig {
+    /// How many routes are kept after each generation.
+    pub population_size: usize,
+    /// The probability with which a new route is mutated.
+    pub mutation_rate: f32,
+    /// The probability with which two parents are `crossover`ed. Otherwise the children are
+    /// copies of their parents.
+    pub crossover_rate: f32,
+    /// How the parents of the offspring are chosen.
+    pub selection: Selection,
+    /// How the fitnesses are transformed before `Selection::Proportionate`.
+    pub fitness_scaling: FitnessScaling,
+    /// How the routes that survive into the next generation are chosen.
+    pub replacement: ReplacementStrategy,
+    /// How many of the fittest routes survive into the next generation regardless of the
+    /// `replacement`.
+    pub elitism: usize,
+    /// The local search the children are refined with, if any. This makes every generation
+    /// considerably more expensive, but also finds much shorter routes per generation.
+    pub local_search: Option<LocalSearchKind>,
+    /// If set, the parents are chosen with tournaments whose size adapts to the diversity of the
+    /// population, whatever `selection` is.
+    pub adaptive_selection: Option<AdaptiveSelectionParams>,
+    /// The crossover operator that creates each child from a pair of parents, e.g. a custom
+    /// implementation of `CrossoverOp`. If not set, both children of a pair are created with
+    /// `Route::crossover_pair` from the same subsequence. The operator is shared with an `Arc`,
+    /// so the config stays cheap to clone.
+    pub crossover: Option<Arc<dyn CrossoverOp>>,
+    /// If set, every child is mutated with one of the `MutationKind`s, chosen with probabilities
+    /// that follow how much each kind improved the routes in the previous generations, see
+    /// `AdaptiveOperators`. Otherwise the children are mutated with `Route::mutate_with`. If
+    /// crossover operators are registered with `AdaptiveOperators::with_crossovers`, the children
+    /// are also created with one of them, chosen the same way, instead of with `crossover`.
+    pub adaptive_operators: bool,
+}
+
+// Two configs are the same if all parameters are equal and they share the same crossover
+// operator, as operators cannot be compared by value.
+impl PartialEq for GaConfig {
+    fn eq(&self, other: &Self) -> bool {
+        self.population_size == other.population_size
+            && self.mutation_rate == other.mutation_rate
+            && self.crossover_rate == other.crossover_rate
+            && self.selection == other.selection
+            && self.fitness_scaling == other.fitness_scaling
+            && self.replacement == other.replacement
+            && self.elitism == other.elitism
+            && self.local_search == other.local_search
+            && self.adaptive_selection == other.adaptive_selection
+            && self.adaptive_operators == other.adaptive_operators
+            && match (&self.crossover, &other.crossover) {
+                (Some(crossover), Some(other_crossover)) => Arc::ptr_eq(crossover, other_crossover),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+}
+
+impl Default for GaConfig {
+    /// The default parameters keep 20 routes per generation, mutate new routes with a probability
+    /// of 0.5, crossover all pairs of parents with a probability of 0.9 and only keep the fittest
+    /// routes, without any elitism or local search. Children are created with
+    /// `Route::crossover_pair`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::config::GaConfig;
+    ///
+    /// let config = GaConfig::default();
+    /// ```
+    fn default() -> Self {
+        GaConfig {
+            population_size: 20,
+            mutation_rate: 0.5,
+            crossover_rate: 0.9,
+            selection: Selection::AllPairs,
+            fitness_scaling: FitnessScaling::None,
+            replacement: ReplacementStrategy::Fittest,
+            elitism: 0,
+            local_search: None,
+            adaptive_selection: None,
+            crossover: None,
+            adaptive_operators: false,
+        }
+    }
+}
+
+/// How a `Schedule` moves from its start to its end parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Change the parameters a little every generation.
+    Linear,
+    /// Keep the parameters constant for a while and change them in `steps` equal jumps.
+    Stepwise {
+        /// In how many jumps the end parameters are reached.
+        steps: usize,
+    },
+}
+
+/// A schedule that changes the `mutation_rate` and the tournament size of the selection from
+/// generation to generation, e.g. to explore with much mutation and little selection pressure
+/// first and to exploit with the reverse later. All other parameters are taken from `start`.
+/// The tournament size is only interpolated if both configs use `Selection::Tournament`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    /// The parameters of the first generation.
+    pub start: GaConfig,
+    /// The parameters of the last generation.
+    pub end: GaConfig,
+    /// How the parameters move from `start` to `end`.
+    pub interpolation: Interpolation,
+}
+
+impl Schedule {
+    /// Get the parameters for `generation`, counted from 0, of a run with `generations`
+    /// generations. The first generation uses the parameters of `start`, the last generation
+    /// those of `end`.
+    ///
+    /// # Arguments
+    ///
+    /// * `generation` - The generation the parameters are used for, starting at 0.
+    /// * `generations` - How many generations the whole run has.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::config::{GaConfig, Interpolation, Schedule, Selection};
+    ///
+    /// let schedule = Schedule {
+    ///     start: GaConfig { mutation_rate: 0.8, selection: Selection::Tournament { size: 2 }, ..GaConfig::default() },
+    ///     end: GaConfig { mutation_rate: 0.2, selection: Selection::Tournament { size: 6 }, ..GaConfig::default() },
+    ///     interpolation: Interpolation::Linear,
+    /// };
+    /// let config = schedule.config_at(2, 5);
+    /// assert!((config.mutation_rate - 0.5).abs() < 1e-6);
+    /// assert_eq!(config.selection, Selection::Tournament { size: 4 });
+    /// ```
+    pub fn config_at(&self, generation: usize, generations: usize) -> GaConfig {
+        let progress = if generations > 1 {
+            (generation.min(generations - 1) as f64) / ((generations - 1) as f64)
+        } else {
+            0.0
+        };
+        let progress = match self.interpolation {
+            Interpolation::Linear => progress,
+            Interpolation::Stepwise { steps } if steps > 0 => {
+                (progress * steps as f64).floor() / steps as f64
+            }
+            Interpolation::Stepwise { .. } => 0.0,
+        };
+        let mut config = self.start.clone();
+        config.mutation_rate = self.start.mutation_rate
+            + (progress as f32) * (self.end.mutation_rate - self.start.mutation_rate);
+        if let (
+            Selection::Tournament { size: start_size },
+            Selection::Tournament { size: end_size },
+        ) = (&self.start.selection, &self.end.selection)
+        {
+            let size = *start_size as f64 + progress * (*end_size as f64 - *start_size as f64);
+            config.selection = Selection::Tournament {
+                size: size.round() as usize,
+            };
+        }
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    mod test_schedule {
+        use super::*;
+        fn schedule(interpolation: Interpolation) -> Schedule {
+            Schedule {
+                start: GaConfig {
+                    mutation_rate: 0.9,
+                    selection: Selection::Tournament { size: 2 },
+                    elitism: 1,
+                    ..GaConfig::default()
+                },
+                end: GaConfig {
+                    mutation_rate: 0.1,
+                    selection: Selection::Tournament { size: 8 },
+                    elitism: 3,
+                    ..GaConfig::default()
+                },
+                interpolation,
+            }
+        }
+        #[test]
+        fn endpoints_match() {
+            for interpolation in [Interpolation::Linear, Interpolation::Stepwise { steps: 3 }] {
+                let schedule = schedule(interpolation);
+                let first = schedule.config_at(0, 10);
+                assert_eq!(first, schedule.start);
+                let last = schedule.config_at(9, 10);
+                assert!((last.mutation_rate - 0.1).abs() < 1e-6);
+                assert_eq!(last.selection, Selection::Tournament { size: 8 });
+                // Everything else is taken from the start.
+                assert_eq!(last.elitism, 1);
+            }
+        }
+        #[test]
+        fn linear_changes_every_generation() {
+            let sizes = (0..7)
+                .map(|generation| {
+                    match schedule(Interpolation::Linear)
+                        .config_at(generation, 7)
+                        .selection
+                    {
+                        Selection::Tournament { size } => size,
+                        _ => panic!("The selection should stay a tournament."),
+                    }
+                })
+                .collect::<Vec<usize>>();
+            assert_eq!(sizes, vec![2, 3, 4, 5, 6, 7, 8]);
+        }
+        #[test]
+        fn stepwise_jumps() {
+            let rates = (0..5)
+                .map(|generation| {
+                    schedule(Interpolation::Stepwise { steps: 2 })
+                        .config_at(generation, 5)
+                        .mutation_rate
+                })
+                .collect::<Vec<f32>>();
+            assert_eq!(rates[0], rates[1]);
+            assert!((rates[2] - 0.5).abs() < 1e-6 && rates[2] == rates[3]);
+            assert!((rates[4] - 0.1).abs() < 1e-6);
+        }
+        #[test]
+        fn other_selections_are_kept() {
+            let schedule = Schedule {
+                start: GaConfig::default(),
+                end: GaConfig {
+                    selection: Selection::Tournament { size: 4 },
+                    ..GaConfig::default()
+                },
+                interpolation: Interpolation::Linear,
+            };
+            assert_eq!(schedule.config_at(3, 4).selection, Selection::AllPairs);
+            assert_eq!(schedule.config_at(0, 1), GaConfig::default());
+        }
+    }
+    mod test_partial_eq {
+        use super::*;
+        use crate::route::OrderedCrossover;
+        #[test]
+        fn same_crossover_operator() {
+            let config = GaConfig {
+                crossover: Some(Arc::new(OrderedCrossover)),
+                ..GaConfig::default()
+            };
+            assert_eq!(config, config.clone());
+            assert_ne!(config, GaConfig::default());
+            assert_ne!(
+                config,
+                GaConfig {
+                    crossover: Some(Arc::new(OrderedCrossover)),
+                    ..GaConfig::default()
+                }
+            );
+        }
+    }
+    mod test_fitness_scaling {
+        use super::*;
+        #[test]
+        fn no_scaling() {
+            assert_eq!(FitnessScaling::None.scale(&[0.5, 1.0]), vec![0.5, 1.0]);
+        }
+        #[test]
+        fn linear() {
+            assert_eq!(
+                FitnessScaling::Linear { a: 2.0, b: -1.0 }.scale(&[0.0, 1.0, 2.0]),
+                vec![0.0, 1.0, 3.0]
+            );
+        }
+        #[test]
+        fn sigma_truncation_clips_outlier() {
+            // The mean is 8 and the standard deviation 4, so fitnesses are shifted by 8 - 4 = 4
+            // and the unfit outlier is clipped to 0.
+            assert_eq!(
+                FitnessScaling::SigmaTruncation { c: 1.0 }.scale(&[10.0, 10.0, 10.0, 10.0, 0.0]),
+                vec![6.0, 6.0, 6.0, 6.0, 0.0]
+            );
+        }
+        #[test]
+        fn sigma_truncation_keeps_close_fitnesses() {
+            let scaled = FitnessScaling::SigmaTruncation { c: 2.0 }.scale(&[1.0, 2.0, 3.0]);
+            assert!(scaled.iter().all(|fitness| *fitness > 0.0));
+            assert!(scaled[0] < scaled[1] && scaled[1] < scaled[2]);
+        }
+        #[test]
+        fn boltzmann() {
+            let scaled = FitnessScaling::Boltzmann { temp: 0.5 }.scale(&[0.0, 1.0]);
+            assert_eq!(scaled[0], 1.0);
+            assert!((scaled[1] - 2.0_f64.exp()).abs() < 1e-12);
+        }
+    }
+}
@@ -1,7 +1,253 @@
+use crate::config::GaConfig;
+use crate::gen_traits::{MultiObjective, Objective};
+use crate::route::Route;
+use crate::routes::Routes;
+use crate::utils::random_index_with;
+use crossbeam_utils::thread;
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// For how many generations the population of a cluster is evolved in `cluster_solve`.
+const CLUSTER_GENERATIONS: usize = 100;
+/// After how many iterations the clustering in `cluster_solve` stops, even if the clusters still
+/// change.
+const MAX_CLUSTERING_ITERATIONS: usize = 50;
+/// Up to how many nodes `brute_force_optimal` enumerates the routes.
+const BRUTE_FORCE_MAX_NODES: usize = 10;
+/// After how many iterations the power iteration in `embed_2d` stops, even if it did not converge.
+const MAX_POWER_ITERATIONS: usize = 10_000;
+
+/// The errors that can occur while constructing a `DistanceMat` or using routes with it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DistanceMatError {
+    /// A point has a different number of dimensions than the first point.
+    DimensionMismatch {
+        /// The index of the offending point.
+        point: usize,
+        /// The number of dimensions of the first point.
+        expected: usize,
+        /// The number of dimensions of the offending point.
+        found: usize,
+    },
+    /// A row of the distances does not have one distance for every row.
+    NotSquare {
+        /// The index of the offending row.
+        row: usize,
+        /// The number of rows, e.g. the expected length of every row.
+        expected: usize,
+        /// The length of the offending row.
+        found: usize,
+    },
+    /// A route does not visit every node of the matrix exactly once.
+    IncompatibleRoute {
+        /// The nodes of the offending route.
+        route: Vec<usize>,
+        /// The number of nodes of the matrix.
+        n_units: usize,
+    },
+}
+
+impl std::fmt::Display for DistanceMatError {
+    /// Describe the error, including the offending point.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMatError;
+    ///
+    /// let error = DistanceMatError::DimensionMismatch { point: 2, expected: 3, found: 2 };
+    /// assert_eq!(error.to_string(), "point 2 has 2 dimensions, but the first point has 3");
+    /// ```
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DistanceMatError::DimensionMismatch {
+                point,
+                expected,
+                found,
+            } => write!(
+                f,
+                "point {} has {} dimensions, but the first point has {}",
+                point, found, expected
+            ),
+            DistanceMatError::NotSquare {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {} has {} distances, but the matrix has {} rows",
+                row, found, expected
+            ),
+            DistanceMatError::IncompatibleRoute { route, n_units } => write!(
+                f,
+                "route {:?} does not visit the {} nodes of the matrix exactly once",
+                route, n_units
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DistanceMatError {}
+
 /// A representation of a f64 based distance matrix.
 #[derive(Debug)]
 pub struct DistanceMat {
     distances: Vec<Vec<f64>>,
+    /// The points the distances were computed from, if the matrix was created from coordinates.
+    coordinates: Option<Vec<(f64, f64)>>,
+}
+
+/// Cache the objective of routes, so that evaluating a route again is a single lookup, e.g. for
+/// populations with many duplicate routes. Routes are cached by their canonical form: all
+/// rotations of a route share one entry and, if the wrapped objective `is_symmetric`, so do both
+/// of its directions.
+///
+/// Every evaluated canonical route stays in the cache until `clear_cache` is called, which costs
+/// one copy of the route and its objective per entry. For long runs with many different routes
+/// the cache can grow large, so clear it from time to time, e.g. every few generations. The
+/// cache is guarded by a `Mutex`, so it can be shared between threads.
+#[derive(Debug)]
+pub struct CachedObjective<O> {
+    /// The objective whose values are cached.
+    objective: O,
+    /// Whether `objective` is symmetric. It is only checked once, for a `DistanceMat` this needs
+    /// to compare every pair of distances.
+    symmetric: bool,
+    /// The objective of every evaluated canonical route.
+    cache: Mutex<HashMap<Route, f64>>,
+}
+
+impl<O: Objective<Individual = Route>> CachedObjective<O> {
+    /// Wrap an objective, e.g. a `DistanceMat`, into an empty cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `objective` - The objective whose values should be cached.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::{CachedObjective, DistanceMat};
+    /// use genetic_algo::gen_traits::Objective;
+    /// use genetic_algo::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let cached = CachedObjective::new(distance_matrix);
+    /// assert_eq!(cached.objective(&Route::new(vec![0, 1, 2])), 6.0);
+    /// assert_eq!(cached.objective(&Route::new(vec![2, 1, 0])), 6.0);
+    /// assert_eq!(cached.cache_len(), 1);
+    /// ```
+    pub fn new(objective: O) -> Self {
+        CachedObjective {
+            symmetric: objective.is_symmetric(),
+            objective,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+    /// Get the wrapped objective.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::{CachedObjective, DistanceMat};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0], vec![1.0,0.0]]);
+    /// assert_eq!(CachedObjective::new(distance_matrix).inner().n_units(), 2);
+    /// ```
+    pub fn inner(&self) -> &O {
+        &self.objective
+    }
+    /// Get how many canonical routes are cached.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::{CachedObjective, DistanceMat};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0], vec![1.0,0.0]]);
+    /// assert_eq!(CachedObjective::new(distance_matrix).cache_len(), 0);
+    /// ```
+    pub fn cache_len(&self) -> usize {
+        self.cache.lock().unwrap().len()
+    }
+    /// Remove all routes from the cache to free its memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::{CachedObjective, DistanceMat};
+    /// use genetic_algo::gen_traits::Objective;
+    /// use genetic_algo::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0], vec![1.0,0.0]]);
+    /// let cached = CachedObjective::new(distance_matrix);
+    /// cached.objective(&Route::new(vec![0, 1]));
+    /// cached.clear_cache();
+    /// assert_eq!(cached.cache_len(), 0);
+    /// ```
+    pub fn clear_cache(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+    /// Get the canonical form of a route: it starts at its smallest node and, for symmetric
+    /// objectives, continues towards the smaller of its two neighbors.
+    fn canonical(&self, route: &Route) -> Route {
+        let mut indexes = route.indexes.clone();
+        if let Some(start) = (0..indexes.len()).min_by_key(|position| indexes[*position]) {
+            indexes.rotate_left(start);
+        }
+        if self.symmetric && indexes.len() > 2 && indexes[indexes.len() - 1] < indexes[1] {
+            indexes[1..].reverse();
+        }
+        Route::new(indexes)
+    }
+}
+
+// The cached objective is the objective it wraps, looked up by canonical route.
+impl<O: Objective<Individual = Route>> Objective for CachedObjective<O> {
+    type Individual = Route;
+    /// Get the objective of the route from the cache, and compute and cache it on a miss.
+    ///
+    /// # Arguments
+    ///
+    /// * `individual` - The route whose objective is looked up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::{CachedObjective, DistanceMat};
+    /// use genetic_algo::gen_traits::Objective;
+    /// use genetic_algo::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let cached = CachedObjective::new(distance_matrix);
+    /// assert_eq!(cached.objective(&Route::new(vec![1, 2, 0])), 6.0);
+    /// ```
+    fn objective(&self, individual: &Route) -> f64 {
+        let canonical = self.canonical(individual);
+        if let Some(objective) = self.cache.lock().unwrap().get(&canonical) {
+            return *objective;
+        }
+        // The lock is not held while the objective is computed, so that other threads can use the
+        // cache in the meantime.
+        let objective = self.objective.objective(&canonical);
+        self.cache.lock().unwrap().insert(canonical, objective);
+        objective
+    }
+    /// The cached objective is as symmetric as the objective it wraps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::{CachedObjective, DistanceMat};
+    /// use genetic_algo::gen_traits::Objective;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0], vec![1.0,0.0]]);
+    /// assert!(CachedObjective::new(distance_matrix).is_symmetric());
+    /// ```
+    fn is_symmetric(&self) -> bool {
+        self.symmetric
+    }
 }
 
 impl DistanceMat {
@@ -10,9 +256,491 @@ impl DistanceMat {
     ///
     /// # Arguments
     ///
-    /// * `distances` - The distances between all indexes 0..n. The matrix
-    /// is assumed to be symmetrical and the distance between an object and itself
-    /// (the diagonal) should be only 0.
+    /// * `distances` - The distances between all indexes 0..n. The matrix
+    ///   is assumed to be symmetrical and the distance between an object and itself
+    ///   (the diagonal) should be only 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// ```
+    pub fn new(distances: Vec<Vec<f64>>) -> Self {
+        DistanceMat {
+            distances,
+            coordinates: None,
+        }
+    }
+    /// Create a new distance mat based on existing distances like `new`, but check that the
+    /// distances are a square matrix first.
+    ///
+    /// # Arguments
+    ///
+    /// * `distances` - The distances between all indexes 0..n.
+    ///
+    /// # Errors
+    ///
+    /// `DistanceMatError::NotSquare` with the first row that does not have `n` distances.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// assert!(DistanceMat::try_new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]).is_ok());
+    /// assert!(DistanceMat::try_new(vec![vec![0.0, 1.0], vec![1.0]]).is_err());
+    /// ```
+    pub fn try_new(distances: Vec<Vec<f64>>) -> Result<DistanceMat, DistanceMatError> {
+        check_square(&distances)?;
+        Ok(DistanceMat::new(distances))
+    }
+    /// Create a new distance mat from borrowed rows of distances. The rows are only copied once
+    /// they are known to be a square matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The distances between all indexes 0..n.
+    ///
+    /// # Errors
+    ///
+    /// `DistanceMatError::NotSquare` with the first row that does not have `n` distances.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let rows = vec![vec![0.0, 1.0], vec![1.0, 0.0]];
+    /// let distance_matrix = DistanceMat::try_from_rows(&rows).unwrap();
+    /// assert_eq!(distance_matrix.distance_between(0, 1), 1.0);
+    /// ```
+    pub fn try_from_rows(rows: &[Vec<f64>]) -> Result<DistanceMat, DistanceMatError> {
+        check_square(rows)?;
+        Ok(DistanceMat::new(rows.to_vec()))
+    }
+    /// Create a new distance mat from the euclidean distances between points. The points are kept
+    /// and can be accessed with `coordinates`.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The `(x, y)` coordinates of the nodes 0..n.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (3.0, 4.0), (0.0, 1.0)]);
+    /// assert_eq!(distance_matrix.get_distance(&[0, 1]), 10.0);
+    /// ```
+    pub fn from_coordinates(points: &[(f64, f64)]) -> Self {
+        DistanceMat::from_coordinates_scaled(points, 1.0, 1.0)
+    }
+    /// Create a new distance mat from the euclidean distances between points whose axes are
+    /// weighted differently, e.g. `sqrt((weight_x * dx)^2 + (weight_y * dy)^2)`. This is useful if
+    /// the axes are measured in different units. The (unweighted) points are kept and can be
+    /// accessed with `coordinates`.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The `(x, y)` coordinates of the nodes 0..n.
+    /// * `weight_x` - The factor the distances along the x-axis are multiplied with.
+    /// * `weight_y` - The factor the distances along the y-axis are multiplied with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates_scaled(&[(0.0, 0.0), (1.0, 2.0)], 3.0, 2.0);
+    /// assert_eq!(distance_matrix.get_distance(&[0, 1]), 10.0);
+    /// ```
+    pub fn from_coordinates_scaled(points: &[(f64, f64)], weight_x: f64, weight_y: f64) -> Self {
+        DistanceMat {
+            distances: points
+                .iter()
+                .map(|from| scaled_distance_row(*from, points, weight_x, weight_y))
+                .collect(),
+            coordinates: Some(points.to_vec()),
+        }
+    }
+    /// Create a new distance mat from the euclidean distances between points like
+    /// `from_coordinates`, but compute the rows on all available cores. Every row is independent
+    /// of the others, so this scales well and pays off for thousands of points.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The `(x, y)` coordinates of the nodes 0..n.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let points = [(0.0, 0.0), (3.0, 4.0), (0.0, 1.0)];
+    /// let distance_matrix = DistanceMat::from_coordinates_par(&points);
+    /// assert!(distance_matrix.distances_approx_eq(&DistanceMat::from_coordinates(&points), 0.0));
+    /// ```
+    pub fn from_coordinates_par(points: &[(f64, f64)]) -> Self {
+        let n_threads = std::thread::available_parallelism()
+            .map(|n_threads| n_threads.get())
+            .unwrap_or(1);
+        let rows_per_thread = points.len().div_ceil(n_threads).max(1);
+        let mut distances = vec![Vec::new(); points.len()];
+        thread::scope(|s| {
+            for (chunk_idx, rows) in distances.chunks_mut(rows_per_thread).enumerate() {
+                s.spawn(move |_| {
+                    for (row_idx, row) in rows.iter_mut().enumerate() {
+                        let from = points[chunk_idx * rows_per_thread + row_idx];
+                        *row = scaled_distance_row(from, points, 1.0, 1.0);
+                    }
+                });
+            }
+        })
+        .unwrap();
+        DistanceMat {
+            distances,
+            coordinates: Some(points.to_vec()),
+        }
+    }
+    /// Create a new distance mat from the squared euclidean distances between points, which saves
+    /// the square roots. Note that this is a different objective, not a faster way to compute the
+    /// euclidean one: the lengths of tours are not comparable to euclidean tour lengths, and even
+    /// the ranking of two tours can differ, as long edges weigh much more in the squared metric.
+    /// Only use it if the squared distances are what should be minimized. The points are kept and
+    /// can be accessed with `coordinates`.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The `(x, y)` coordinates of the nodes 0..n.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates_squared(&[(0.0, 0.0), (3.0, 4.0)]);
+    /// assert_eq!(distance_matrix.distance_between(0, 1), 25.0);
+    /// ```
+    pub fn from_coordinates_squared(points: &[(f64, f64)]) -> Self {
+        DistanceMat {
+            distances: points
+                .iter()
+                .map(|(x_from, y_from)| {
+                    points
+                        .iter()
+                        .map(|(x_to, y_to)| (x_from - x_to).powi(2) + (y_from - y_to).powi(2))
+                        .collect()
+                })
+                .collect(),
+            coordinates: Some(points.to_vec()),
+        }
+    }
+    /// Create a new distance mat from the euclidean distances between points with an arbitrary
+    /// number of dimensions. As the points are not two-dimensional, `coordinates` returns `None`
+    /// for the created matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The coordinates of the nodes 0..n, all with the same number of dimensions.
+    ///
+    /// # Errors
+    ///
+    /// `DistanceMatError::DimensionMismatch` with the first point whose number of dimensions
+    /// differs from the first point.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates_nd(&[vec![0.0, 0.0, 0.0], vec![1.0, 2.0, 2.0]]).unwrap();
+    /// assert_eq!(distance_matrix.distance_between(0, 1), 3.0);
+    /// assert!(DistanceMat::from_coordinates_nd(&[vec![0.0, 0.0], vec![1.0]]).is_err());
+    /// ```
+    pub fn from_coordinates_nd(points: &[Vec<f64>]) -> Result<DistanceMat, DistanceMatError> {
+        if let Some(first_point) = points.first() {
+            if let Some((point, mismatch)) = points
+                .iter()
+                .enumerate()
+                .find(|(_, point)| point.len() != first_point.len())
+            {
+                return Err(DistanceMatError::DimensionMismatch {
+                    point,
+                    expected: first_point.len(),
+                    found: mismatch.len(),
+                });
+            }
+        }
+        Ok(DistanceMat::new(
+            points
+                .iter()
+                .map(|from| {
+                    points
+                        .iter()
+                        .map(|to| {
+                            from.iter()
+                                .zip(to.iter())
+                                .map(|(from, to)| (from - to).powi(2))
+                                .sum::<f64>()
+                                .sqrt()
+                        })
+                        .collect()
+                })
+                .collect(),
+        ))
+    }
+    /// Get the points the distance matrix was created from. Returns `None` if the matrix was
+    /// created from explicit distances.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (3.0, 4.0)]);
+    /// assert_eq!(distance_matrix.coordinates(), Some(&[(0.0, 0.0), (3.0, 4.0)][..]));
+    /// ```
+    pub fn coordinates(&self) -> Option<&[(f64, f64)]> {
+        self.coordinates.as_deref()
+    }
+    /// Get the number of nodes in the distance matrix, e.g. one of its dimensions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// println!("{}", distance_matrix.n_units());
+    /// ```
+    #[inline]
+    pub fn n_units(&self) -> usize {
+        self.distances.len()
+    }
+    /// Get the distance between two individual nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The node the edge starts at.
+    /// * `to` - The node the edge ends at.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(distance_matrix.distance_between(1, 2), 3.0);
+    /// ```
+    pub fn distance_between(&self, from: usize, to: usize) -> f64 {
+        self.distances[from][to]
+    }
+    /// Check whether two distance matrices have the same shape and all their distances differ by
+    /// at most `tol`. This is useful to compare matrices computed from coordinates, that may
+    /// differ in the last digits.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The distance matrix to compare with.
+    /// * `tol` - The largest absolute difference two distances may have.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0, 0.3], vec![0.3, 0.0]]);
+    /// let other = DistanceMat::new(vec![vec![0.0, 0.1 + 0.2], vec![0.1 + 0.2, 0.0]]);
+    /// assert!(distance_matrix.distances_approx_eq(&other, 1e-12));
+    /// ```
+    pub fn distances_approx_eq(&self, other: &DistanceMat, tol: f64) -> bool {
+        self.n_units() == other.n_units()
+            && self
+                .distances
+                .iter()
+                .zip(other.distances.iter())
+                .all(|(row, other_row)| {
+                    row.len() == other_row.len()
+                        && row.iter().zip(other_row.iter()).all(|(distance, other)| {
+                            // Equal infinite distances have no finite difference.
+                            distance == other || (distance - other).abs() <= tol
+                        })
+                })
+    }
+    /// Check whether a sequence of nodes is a valid route for this distance matrix, e.g. whether
+    /// it visits every node 0..n exactly once.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes that should be checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert!(distance_matrix.route_matches(&[1,0,2]));
+    /// assert!(!distance_matrix.route_matches(&[1,0]));
+    /// ```
+    pub fn route_matches(&self, route: &[usize]) -> bool {
+        if route.len() != self.n_units() {
+            return false;
+        }
+        let mut visited = vec![false; self.n_units()];
+        for node in route {
+            if *node >= self.n_units() || visited[*node] {
+                return false;
+            }
+            visited[*node] = true;
+        }
+        true
+    }
+    /// Check whether a route can be used with this distance matrix, e.g. whether it is a
+    /// permutation of the nodes 0..n. Routes created for a matrix of another size are not.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route that should be checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    /// use genetic_algo::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert!(distance_matrix.is_compatible(&Route::new(vec![2,0,1])));
+    /// assert!(!distance_matrix.is_compatible(&Route::new(vec![3,0,1,2])));
+    /// ```
+    pub fn is_compatible(&self, route: &Route) -> bool {
+        self.route_matches(&route.indexes)
+    }
+    /// Given a sequence of nodes (in a `Route`-object) compute the distance for the round-
+    /// trip between node 0..0
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes that is visited and for which the round-trip-lenght
+    ///   should be computed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    /// use genetic_algo::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// println!("{}", distance_matrix.get_distance(&vec![1,0,2]));
+    /// ```
+    #[inline]
+    pub fn get_distance(&self, route: &[usize]) -> f64 {
+        let (first, last) = (route[0], route[route.len() - 1]);
+        // The windows of the route are the edges 1-2, 2-3, ... , (n-1)-n, so only the closing
+        // edge n-1 is missing. It is added first, which keeps the order of the summation stable.
+        let closing_edge = self.distances[last][first];
+        route
+            .windows(2)
+            .map(|edge| self.distances[edge[0]][edge[1]])
+            .fold(closing_edge, |distance, edge| distance + edge)
+    }
+    /// Compute the distance of the round-trip of a route, like `get_distance` of its nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route for which the round-trip-length should be computed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    /// use genetic_algo::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(distance_matrix.route_distance(&Route::new(vec![1,0,2])), 6.0);
+    /// ```
+    pub fn route_distance(&self, route: &Route) -> f64 {
+        self.get_distance(route.nodes())
+    }
+    /// Compute the length of a path along any sequence of nodes, e.g. a sub-tour over some of the
+    /// nodes, without building a submatrix for them. In contrast to `get_distance` the nodes do not
+    /// have to be a permutation of all nodes. A closed path also returns from the last to the first
+    /// node, an open path ends at the last node. Paths with a single node or no nodes at all have a
+    /// length of 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes that is visited.
+    /// * `closed` - Whether the path returns to its first node.
+    ///
+    /// # Panics
+    ///
+    /// If a node of `route` is not part of the distance matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(distance_matrix.get_distance_subset(&[2, 0], false), 2.0);
+    /// assert_eq!(distance_matrix.get_distance_subset(&[2, 0], true), 4.0);
+    /// ```
+    pub fn get_distance_subset(&self, route: &[usize], closed: bool) -> f64 {
+        let path = route
+            .windows(2)
+            .map(|edge| self.distances[edge[0]][edge[1]])
+            .sum::<f64>();
+        match (closed, route.first(), route.last()) {
+            (true, Some(first), Some(last)) if route.len() > 1 => {
+                path + self.distances[*last][*first]
+            }
+            _ => path,
+        }
+    }
+    /// Compute the distance of the round-trip like `get_distance`, but stop as soon as an edge is
+    /// not finite, e.g. because two nodes are not connected in a sparse matrix. The edges are
+    /// visited in the order of the route and the edge back to the first node is visited last.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes that is visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, f64::INFINITY],
+    ///     vec![1.0, 0.0, 2.0],
+    ///     vec![f64::INFINITY, 2.0, 0.0],
+    /// ]);
+    /// assert_eq!(distance_matrix.try_get_finite_distance(&[0, 1, 2]), None);
+    /// assert_eq!(distance_matrix.try_get_finite_distance(&[1, 2]), Some(4.0));
+    /// ```
+    pub fn try_get_finite_distance(&self, route: &[usize]) -> Option<f64> {
+        route
+            .iter()
+            .zip(route.iter().cycle().skip(1))
+            .try_fold(0.0, |distance, (from, to)| {
+                let edge = self.distances[*from][*to];
+                if edge.is_finite() {
+                    Some(distance + edge)
+                } else {
+                    None
+                }
+            })
+    }
+    /// Compute the length of every edge of the round-trip, e.g. the distances between 1-2,
+    /// 2-3, ... , (n-1)-n and finally n-1. The edges sum up to `get_distance`.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes whose edges should be computed.
     ///
     /// # Examples
     ///
@@ -20,11 +748,84 @@ impl DistanceMat {
     /// use genetic_algo::distance_mat::DistanceMat;
     ///
     /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(distance_matrix.edge_distances(&[1,0,2]), vec![1.0, 2.0, 3.0]);
     /// ```
-    pub fn new(distances: Vec<Vec<f64>>) -> Self {
-        DistanceMat { distances }
+    pub fn edge_distances(&self, route: &[usize]) -> Vec<f64> {
+        route
+            .iter()
+            .zip(route.iter().cycle().skip(1))
+            .map(|(from, to)| self.distances[*from][*to])
+            .collect()
     }
-    /// Get the number of nodes in the distance matrix, e.g. one of its dimensions.
+    /// Compute how much longer the round-trip becomes if the nodes at the positions `pos_a` and
+    /// `pos_b` of the route are swapped, without swapping them. Only the at most four edges that
+    /// touch the two positions are looked at, so this is constant time. Adjacent positions share
+    /// an edge, which is only counted once. A negative delta means the swap shortens the route.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes that is visited.
+    /// * `pos_a` - The position of the first node that is swapped.
+    /// * `pos_b` - The position of the second node that is swapped.
+    ///
+    /// # Panics
+    ///
+    /// If `pos_a` or `pos_b` is not a position of the route.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+    /// let delta = distance_matrix.swap_delta(&[0, 2, 1, 3], 1, 2);
+    /// assert!((distance_matrix.get_distance(&[0, 2, 1, 3]) + delta - 4.0).abs() < 1e-12);
+    /// ```
+    pub fn swap_delta(&self, route: &[usize], pos_a: usize, pos_b: usize) -> f64 {
+        let n_nodes = route.len();
+        assert!(
+            pos_a < n_nodes && pos_b < n_nodes,
+            "Cannot swap the positions {} and {} of a route with {} nodes",
+            pos_a,
+            pos_b,
+            n_nodes
+        );
+        if pos_a == pos_b {
+            return 0.0;
+        }
+        let swapped_node = |pos: usize| {
+            if pos == pos_a {
+                route[pos_b]
+            } else if pos == pos_b {
+                route[pos_a]
+            } else {
+                route[pos]
+            }
+        };
+        // The edges are identified by the position they start at, so an edge that is shared by
+        // adjacent positions appears only once.
+        let mut edge_starts = vec![
+            (pos_a + n_nodes - 1) % n_nodes,
+            pos_a,
+            (pos_b + n_nodes - 1) % n_nodes,
+            pos_b,
+        ];
+        edge_starts.sort_unstable();
+        edge_starts.dedup();
+        edge_starts
+            .into_iter()
+            .map(|start| {
+                let end = (start + 1) % n_nodes;
+                self.distances[swapped_node(start)][swapped_node(end)]
+                    - self.distances[route[start]][route[end]]
+            })
+            .sum()
+    }
+    /// Compute the round-trip distance of many routes at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `routes` - The routes for which the round-trip-length should be computed.
     ///
     /// # Examples
     ///
@@ -32,44 +833,829 @@ impl DistanceMat {
     /// use genetic_algo::distance_mat::DistanceMat;
     ///
     /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
-    /// println!("{}", distance_matrix.n_units());
+    /// assert_eq!(distance_matrix.get_distances(&[vec![1,0,2], vec![0,1]]), vec![6.0, 2.0]);
     /// ```
-    pub fn n_units(&self) -> usize {
-        self.distances.len()
+    pub fn get_distances(&self, routes: &[Vec<usize>]) -> Vec<f64> {
+        routes
+            .iter()
+            .map(|route| self.get_distance(route))
+            .collect()
     }
-    /// Given a sequence of nodes (in a `Route`-object) compute the distance for the round-
-    /// trip between node 0..0
+    /// Given a sequence of nodes as an iterator compute the distance for the round-trip. This
+    /// does the same as `get_distance` but computes the distance while iterating, so the nodes don't
+    /// have to be collected into a slice first. An empty sequence has a distance of 0.
     ///
     /// # Arguments
     ///
     /// * `route` - The sequence of nodes that is visited and for which the round-trip-lenght
-    /// should be computed.
+    ///   should be computed.
     ///
     /// # Examples
     ///
     /// ```
     /// use genetic_algo::distance_mat::DistanceMat;
-    /// use genetic_algo::route::Route;
     ///
     /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
-    /// println!("{}", distance_matrix.get_distance(&vec![1,0,2]));
+    /// println!("{}", distance_matrix.get_distance_iter((0..3).rev()));
     /// ```
-    pub fn get_distance(&self, route: &[usize]) -> f64 {
-        route
+    pub fn get_distance_iter<I: IntoIterator<Item = usize>>(&self, route: I) -> f64 {
+        let mut nodes = route.into_iter();
+        let first_point = match nodes.next() {
+            Some(first_point) => first_point,
+            None => return 0.0,
+        };
+        let (loss, last_point) =
+            nodes.fold((0.0, first_point), |(loss, last_point), current_point| {
+                (
+                    loss + self.distances[last_point][current_point],
+                    current_point,
+                )
+            });
+        // Close the round-trip by going back to the first node.
+        loss + self.distances[last_point][first_point]
+    }
+    /// Approximate two-dimensional coordinates for the nodes with classical multidimensional
+    /// scaling, e.g. to plot a matrix that was not created from points. The squared distances are
+    /// double-centered and the nodes are projected on the two eigenvectors with the largest
+    /// eigenvalues. This is only an approximation: the distances between the returned points
+    /// match the matrix exactly only if it holds euclidean distances of points in a plane, and
+    /// the result is only meaningful for matrices that are close to metric. Asymmetric matrices
+    /// are symmetrized first. The points are unique up to rotation, reflection and translation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0, 3.0, 5.0], vec![3.0, 0.0, 4.0], vec![5.0, 4.0, 0.0]]);
+    /// let points = distance_matrix.embed_2d();
+    /// let (x_0, y_0) = points[0];
+    /// let (x_2, y_2) = points[2];
+    /// assert!(((x_0 - x_2).hypot(y_0 - y_2) - 5.0).abs() < 1e-6);
+    /// ```
+    pub fn embed_2d(&self) -> Vec<(f64, f64)> {
+        let n_nodes = self.n_units();
+        let squared = (0..n_nodes)
+            .map(|from| {
+                (0..n_nodes)
+                    .map(|to| ((self.distances[from][to] + self.distances[to][from]) / 2.0).powi(2))
+                    .collect::<Vec<f64>>()
+            })
+            .collect::<Vec<Vec<f64>>>();
+        let row_means = squared
             .iter()
-            .fold(
-                // By folding the indexes we get the distances between 1-2, 2-3, ... , (n-1)-n.
-                // Then we are missing n-0, therefore that's the initial value we choose in the `fold`-
-                // operator.
-                (self.distances[route[route.len() - 1]][route[0]], None),
-                |(mut loss, last_point): (f64, Option<usize>), current_point| {
-                    if let Some(last_point) = last_point {
-                        loss += &self.distances[last_point][*current_point];
+            .map(|row| row.iter().sum::<f64>() / n_nodes as f64)
+            .collect::<Vec<f64>>();
+        let total_mean = row_means.iter().sum::<f64>() / n_nodes as f64;
+        let centered = (0..n_nodes)
+            .map(|from| {
+                (0..n_nodes)
+                    .map(|to| {
+                        -0.5 * (squared[from][to] - row_means[from] - row_means[to] + total_mean)
+                    })
+                    .collect::<Vec<f64>>()
+            })
+            .collect::<Vec<Vec<f64>>>();
+        // Shifting all eigenvalues by the largest absolute row sum makes them non-negative, so the
+        // power iteration finds the largest eigenvalues instead of the largest absolute ones.
+        let shift = centered
+            .iter()
+            .map(|row| row.iter().map(|value| value.abs()).sum::<f64>())
+            .fold(0.0, f64::max);
+        let mut eigenvectors: Vec<Vec<f64>> = Vec::new();
+        let mut axes = Vec::new();
+        for _ in 0..2 {
+            let (eigenvalue, eigenvector) = dominant_eigenpair(&centered, shift, &eigenvectors);
+            let scale = eigenvalue.max(0.0).sqrt();
+            axes.push(
+                eigenvector
+                    .iter()
+                    .map(|component| scale * component)
+                    .collect::<Vec<f64>>(),
+            );
+            eigenvectors.push(eigenvector);
+        }
+        axes[0]
+            .iter()
+            .cloned()
+            .zip(axes[1].iter().cloned())
+            .collect()
+    }
+    /// Compute the metric closure of the distance matrix, e.g. replace the distance between every
+    /// pair of nodes by the length of the shortest path between them, using the Floyd–Warshall
+    /// algorithm. Missing edges can be given as `f64::INFINITY` and are filled in if the nodes are
+    /// connected via other nodes. The closure satisfies the triangle inequality, which many TSP
+    /// heuristics rely on. Note that this takes O(n^3) time for n nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, f64::INFINITY],
+    ///     vec![1.0, 0.0, 2.0],
+    ///     vec![f64::INFINITY, 2.0, 0.0],
+    /// ]);
+    /// assert_eq!(distance_matrix.metric_closure().distance_between(0, 2), 3.0);
+    /// ```
+    pub fn metric_closure(&self) -> DistanceMat {
+        let mut distances = self.distances.clone();
+        for via in 0..self.n_units() {
+            for from in 0..self.n_units() {
+                for to in 0..self.n_units() {
+                    let distance_via = distances[from][via] + distances[via][to];
+                    if distance_via < distances[from][to] {
+                        distances[from][to] = distance_via;
                     }
-                    (loss, Some(*current_point))
+                }
+            }
+        }
+        DistanceMat {
+            distances,
+            coordinates: self.coordinates.clone(),
+        }
+    }
+    /// Construct a route with the nearest neighbor heuristic: starting at `start`, the closest
+    /// node that was not visited yet is always visited next. If several nodes are the closest,
+    /// the one with the lowest index is visited.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The node the route starts with.
+    ///
+    /// # Panics
+    ///
+    /// If `start` is not a node of the distance matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    /// use genetic_algo::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(distance_matrix.nearest_neighbor_route(2), Route::new(vec![2,0,1]));
+    /// ```
+    pub fn nearest_neighbor_route(&self, start: usize) -> Route {
+        assert!(
+            start < self.n_units(),
+            "The start {} is not one of the {} nodes.",
+            start,
+            self.n_units()
+        );
+        Route::complete_greedily(&[start], self)
+    }
+    /// Run `nearest_neighbor_route` from the nodes `0..max_starts` and return the shortest of the
+    /// routes. Each run takes O(n^2), so starting from all n nodes takes O(n^3); use `max_starts`
+    /// to limit the cost on large instances. If several routes are the shortest, the one with the
+    /// lowest start is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_starts` - From how many nodes at most the heuristic is started, at least from node 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (3.0, 0.0), (1.0, 1.0)]);
+    /// let route = distance_matrix.best_nearest_neighbor_route(usize::MAX);
+    /// assert!(distance_matrix.get_distance(&route.indexes) <= distance_matrix.get_distance(&distance_matrix.nearest_neighbor_route(0).indexes));
+    /// ```
+    pub fn best_nearest_neighbor_route(&self, max_starts: usize) -> Route {
+        (0..self.n_units().min(max_starts.max(1)))
+            .map(|start| {
+                let route = self.nearest_neighbor_route(start);
+                let distance = self.get_distance(&route.indexes);
+                (route, distance)
+            })
+            .fold(
+                None,
+                |best: Option<(Route, f64)>, (route, distance)| match best {
+                    Some(best) if best.1 <= distance => Some(best),
+                    _ => Some((route, distance)),
                 },
             )
-            .0
+            .map_or_else(|| Route::new(Vec::new()), |(route, _)| route)
+    }
+    /// Construct a route with the cheapest insertion heuristic. Starting from the sub-tour of
+    /// node 0 and its nearest node, the node that increases the length of the sub-tour the least
+    /// is inserted at its best position until all nodes are visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let route = distance_matrix.cheapest_insertion_route();
+    /// ```
+    pub fn cheapest_insertion_route(&self) -> Route {
+        if self.n_units() < 2 {
+            return Route::new((0..self.n_units()).collect());
+        }
+        let nearest_node = (1..self.n_units())
+            .min_by(|node_a, node_b| {
+                self.distances[0][*node_a]
+                    .partial_cmp(&self.distances[0][*node_b])
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap();
+        let mut sub_tour = vec![0, nearest_node];
+        let mut remaining_nodes = (1..self.n_units())
+            .filter(|node| *node != nearest_node)
+            .collect::<Vec<usize>>();
+        while !remaining_nodes.is_empty() {
+            // For every remaining node find the position at which it increases the length of the
+            // sub-tour the least, then insert the node for which that increase is smallest.
+            let current_tour = &sub_tour;
+            let (remaining_idx, insert_position, _) = remaining_nodes
+                .iter()
+                .enumerate()
+                .flat_map(|(remaining_idx, node)| {
+                    (0..current_tour.len()).map(move |position| {
+                        let from = current_tour[position];
+                        let to = current_tour[(position + 1) % current_tour.len()];
+                        (
+                            remaining_idx,
+                            position + 1,
+                            self.distances[from][*node] + self.distances[*node][to]
+                                - self.distances[from][to],
+                        )
+                    })
+                })
+                .fold(
+                    None,
+                    |cheapest: Option<(usize, usize, f64)>, insertion| match cheapest {
+                        Some(cheapest) if cheapest.2 <= insertion.2 => Some(cheapest),
+                        _ => Some(insertion),
+                    },
+                )
+                .unwrap();
+            sub_tour.insert(insert_position, remaining_nodes.swap_remove(remaining_idx));
+        }
+        Route::new(sub_tour)
+    }
+    /// Find the optimal route by trying all of them, e.g. to validate the result of the genetic
+    /// algorithm on tiny instances. The route starts at node 0 and each route is only tried in one
+    /// of its two directions, so the distances are assumed to be symmetric. Partial routes that
+    /// are already longer than the best route found so far are not continued.
+    ///
+    /// Returns the optimal route together with its length, or `None` if the matrix is empty or
+    /// has more than 10 nodes, as the (n-1)!/2 routes cannot be enumerated in reasonable time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 1.0), (1.0, 0.0), (0.0, 1.0)]);
+    /// let (route, distance) = distance_matrix.brute_force_optimal().unwrap();
+    /// assert_eq!(distance, 4.0);
+    /// ```
+    pub fn brute_force_optimal(&self) -> Option<(Route, f64)> {
+        if self.n_units() == 0 || self.n_units() > BRUTE_FORCE_MAX_NODES {
+            return None;
+        }
+        let mut route = vec![0];
+        let mut visited = vec![false; self.n_units()];
+        visited[0] = true;
+        let mut best = None;
+        self.extend_optimal(&mut route, &mut visited, 0.0, &mut best);
+        best.map(|(indexes, distance)| (Route::new(indexes), distance))
+    }
+    /// Try all ways to complete the partial `route` that is `distance` long and keep the shortest
+    /// round-trip in `best`.
+    fn extend_optimal(
+        &self,
+        route: &mut Vec<usize>,
+        visited: &mut Vec<bool>,
+        distance: f64,
+        best: &mut Option<(Vec<usize>, f64)>,
+    ) {
+        let last = route[route.len() - 1];
+        if route.len() == self.n_units() {
+            // Every route is also visited in the opposite direction, only keep one of both.
+            if route.len() > 2 && route[1] > last {
+                return;
+            }
+            let round_trip = distance + self.distances[last][route[0]];
+            if best
+                .as_ref()
+                .is_none_or(|(_, best_distance)| round_trip < *best_distance)
+            {
+                *best = Some((route.clone(), round_trip));
+            }
+            return;
+        }
+        for next in 0..self.n_units() {
+            let extended = distance + self.distances[last][next];
+            if visited[next]
+                || best
+                    .as_ref()
+                    .is_some_and(|(_, best_distance)| extended >= *best_distance)
+            {
+                continue;
+            }
+            visited[next] = true;
+            route.push(next);
+            self.extend_optimal(route, visited, extended, best);
+            route.pop();
+            visited[next] = false;
+        }
+    }
+    /// Compute the weight of the minimum spanning tree of the nodes. As removing an edge from the
+    /// optimal round-trip leaves a spanning tree, this is a lower bound for the length of the
+    /// optimal route. Use it to estimate an optimality gap with `(best - bound) / bound`. The
+    /// distances are assumed to be symmetric.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(distance_matrix.mst_lower_bound(), 3.0);
+    /// ```
+    pub fn mst_lower_bound(&self) -> f64 {
+        self.spanning_tree_weight(&(0..self.n_units()).collect::<Vec<usize>>())
+    }
+    /// Compute the weight of the minimum 1-tree: the minimum spanning tree of all nodes but node
+    /// 0, plus the two shortest edges of node 0. Every round-trip is a 1-tree, so this is a lower
+    /// bound for the length of the optimal route, and usually a tighter one than
+    /// `mst_lower_bound`. The distances are assumed to be symmetric.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(distance_matrix.one_tree_lower_bound(), 6.0);
+    /// ```
+    pub fn one_tree_lower_bound(&self) -> f64 {
+        if self.n_units() < 3 {
+            return self.mst_lower_bound();
+        }
+        let mut edges_of_first = self.distances[0][1..].to_vec();
+        edges_of_first.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        self.spanning_tree_weight(&(1..self.n_units()).collect::<Vec<usize>>())
+            + edges_of_first[0]
+            + edges_of_first[1]
+    }
+    /// Compute the weight of the minimum spanning tree of `nodes` with Prim's algorithm.
+    fn spanning_tree_weight(&self, nodes: &[usize]) -> f64 {
+        if nodes.is_empty() {
+            return 0.0;
+        }
+        // The shortest edge from every node outside of the tree into the tree.
+        let mut distances_to_tree = nodes[1..]
+            .iter()
+            .map(|node| (*node, self.distances[nodes[0]][*node]))
+            .collect::<Vec<(usize, f64)>>();
+        let mut weight = 0.0;
+        while !distances_to_tree.is_empty() {
+            let closest = (0..distances_to_tree.len())
+                .min_by(|a, b| {
+                    distances_to_tree[*a]
+                        .1
+                        .partial_cmp(&distances_to_tree[*b].1)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .unwrap();
+            let (added_node, distance) = distances_to_tree.swap_remove(closest);
+            weight += distance;
+            for (node, distance_to_tree) in distances_to_tree.iter_mut() {
+                *distance_to_tree = distance_to_tree.min(self.distances[added_node][*node]);
+            }
+        }
+        weight
+    }
+    /// Solve large instances by divide and conquer: the nodes are partitioned into `n_clusters`
+    /// clusters of close nodes, the genetic algorithm is run on every cluster on its own and the
+    /// tours of the clusters are stitched together. Starting with the cluster of node 0, the
+    /// tour is always continued with the closest node of the clusters that were not visited yet.
+    ///
+    /// The clusters are found with k-medoids on the distances, so the matrix does not need to
+    /// be created from coordinates. The population of each cluster is evolved with `config` for
+    /// a fixed number of generations.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_clusters` - Into how many clusters the nodes are partitioned, at most one per node.
+    /// * `config` - The parameters of the genetic algorithm run on every cluster.
+    /// * `rng` - The random number generator used for the clustering and the genetic algorithm.
+    ///
+    /// # Panics
+    ///
+    /// If `n_clusters` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    /// use genetic_algo::config::GaConfig;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[
+    ///     (0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0),
+    ///     (10.0, 0.0), (11.0, 0.0), (11.0, 1.0), (10.0, 1.0),
+    /// ]);
+    /// let route = distance_matrix.cluster_solve(2, &GaConfig::default(), &mut rand::thread_rng());
+    /// assert!(distance_matrix.route_matches(&route.indexes));
+    /// ```
+    pub fn cluster_solve(&self, n_clusters: usize, config: &GaConfig, rng: &mut impl Rng) -> Route {
+        assert!(n_clusters > 0, "At least one cluster is needed.");
+        let mut cluster_tours = self
+            .cluster_nodes(n_clusters, rng)
+            .into_iter()
+            .map(|cluster| self.solve_cluster(&cluster, config, rng))
+            .collect::<Vec<Vec<usize>>>();
+        let mut route: Vec<usize> = Vec::with_capacity(self.n_units());
+        while !cluster_tours.is_empty() {
+            // Enter the cluster at the node that is closest to the end of the route. The route
+            // starts with node 0.
+            let (cluster_idx, entry_idx) = match route.last() {
+                None => cluster_tours
+                    .iter()
+                    .enumerate()
+                    .find_map(|(cluster_idx, tour)| {
+                        tour.iter()
+                            .position(|node| *node == 0)
+                            .map(|entry_idx| (cluster_idx, entry_idx))
+                    })
+                    .unwrap(),
+                Some(last) => cluster_tours
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(cluster_idx, tour)| {
+                        (0..tour.len()).map(move |entry_idx| (cluster_idx, entry_idx))
+                    })
+                    .min_by(|(cluster_a, entry_a), (cluster_b, entry_b)| {
+                        let bridge_length = |cluster: usize, entry: usize| {
+                            self.distances[*last][cluster_tours[cluster][entry]]
+                        };
+                        bridge_length(*cluster_a, *entry_a)
+                            .partial_cmp(&bridge_length(*cluster_b, *entry_b))
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    })
+                    .unwrap(),
+            };
+            let mut tour = cluster_tours.swap_remove(cluster_idx);
+            tour.rotate_left(entry_idx);
+            // Leave the cluster over the shorter of the two edges of the entry node, so the
+            // longer one is the edge of the cluster tour that is dropped.
+            if tour.len() > 2
+                && self.distances[tour[0]][tour[1]] > self.distances[tour[tour.len() - 1]][tour[0]]
+            {
+                tour[1..].reverse();
+            }
+            route.extend(tour);
+        }
+        Route::new(route)
+    }
+    /// Partition the nodes into at most `n_clusters` clusters with k-medoids. The first medoid is
+    /// chosen at random, every further medoid is the node that is farthest away from all medoids
+    /// chosen so far.
+    fn cluster_nodes(&self, n_clusters: usize, rng: &mut impl Rng) -> Vec<Vec<usize>> {
+        if self.n_units() == 0 {
+            return Vec::new();
+        }
+        let distance_to_medoids = |node: usize, medoids: &[usize]| {
+            medoids
+                .iter()
+                .map(|medoid| self.distances[*medoid][node])
+                .fold(f64::INFINITY, f64::min)
+        };
+        let mut medoids = vec![random_index_with(0..self.n_units(), rng)];
+        while medoids.len() < n_clusters.min(self.n_units()) {
+            let farthest = (0..self.n_units())
+                .filter(|node| !medoids.contains(node))
+                .max_by(|a, b| {
+                    distance_to_medoids(*a, &medoids)
+                        .partial_cmp(&distance_to_medoids(*b, &medoids))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .unwrap();
+            medoids.push(farthest);
+        }
+        let mut clusters = Vec::new();
+        for _ in 0..MAX_CLUSTERING_ITERATIONS {
+            clusters = vec![Vec::new(); medoids.len()];
+            for node in 0..self.n_units() {
+                let closest = (0..medoids.len())
+                    .min_by(|a, b| {
+                        self.distances[medoids[*a]][node]
+                            .partial_cmp(&self.distances[medoids[*b]][node])
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    })
+                    .unwrap();
+                clusters[closest].push(node);
+            }
+            // The new medoid of a cluster is the node with the smallest distance to all others.
+            let new_medoids = clusters
+                .iter()
+                .zip(medoids.iter())
+                .map(|(cluster, medoid)| {
+                    cluster
+                        .iter()
+                        .cloned()
+                        .min_by(|a, b| {
+                            let spread = |node: usize| {
+                                cluster
+                                    .iter()
+                                    .map(|other| self.distances[node][*other])
+                                    .sum::<f64>()
+                            };
+                            spread(*a)
+                                .partial_cmp(&spread(*b))
+                                .unwrap_or(std::cmp::Ordering::Equal)
+                        })
+                        .unwrap_or(*medoid)
+                })
+                .collect::<Vec<usize>>();
+            if new_medoids == medoids {
+                break;
+            }
+            medoids = new_medoids;
+        }
+        clusters.retain(|cluster| !cluster.is_empty());
+        clusters
+    }
+    /// Run the genetic algorithm on the nodes of a cluster and return the nodes in the order of
+    /// the best route that was found.
+    fn solve_cluster(
+        &self,
+        cluster: &[usize],
+        config: &GaConfig,
+        rng: &mut impl Rng,
+    ) -> Vec<usize> {
+        // On three nodes or less all round-trips have the same length.
+        if cluster.len() <= 3 {
+            return cluster.to_vec();
+        }
+        let cluster_mat = DistanceMat::new(
+            cluster
+                .iter()
+                .map(|from| {
+                    cluster
+                        .iter()
+                        .map(|to| self.distances[*from][*to])
+                        .collect()
+                })
+                .collect(),
+        );
+        // A cluster has only `cluster.len()!` different routes, `Routes::random` would never
+        // find more of them.
+        let n_routes = (1..=cluster.len())
+            .try_fold(1_usize, |n_routes, n_nodes| n_routes.checked_mul(n_nodes))
+            .map_or(config.population_size, |n_permutations| {
+                n_permutations.min(config.population_size)
+            });
+        let mut routes = Routes::random_with(n_routes, cluster.len(), rng);
+        for _ in 0..CLUSTER_GENERATIONS {
+            routes = routes.step(&cluster_mat, config, rng);
+        }
+        routes
+            .best(&cluster_mat)
+            .unwrap()
+            .indexes
+            .iter()
+            .map(|idx| cluster[*idx])
+            .collect()
+    }
+}
+
+/// Compute the weighted euclidean distances from the point `from` to all `points`, see
+/// `DistanceMat::from_coordinates_scaled`.
+fn scaled_distance_row(
+    (x_from, y_from): (f64, f64),
+    points: &[(f64, f64)],
+    weight_x: f64,
+    weight_y: f64,
+) -> Vec<f64> {
+    points
+        .iter()
+        .map(|(x_to, y_to)| {
+            ((weight_x * (x_from - x_to)).powi(2) + (weight_y * (y_from - y_to)).powi(2)).sqrt()
+        })
+        .collect()
+}
+
+/// Find the largest eigenvalue of the symmetric `matrix` whose eigenvector is orthogonal to all
+/// `found` eigenvectors, with the power iteration on the matrix shifted by `shift`. Returns the
+/// eigenvalue and the normalized eigenvector.
+fn dominant_eigenpair(matrix: &[Vec<f64>], shift: f64, found: &[Vec<f64>]) -> (f64, Vec<f64>) {
+    let n_rows = matrix.len();
+    let orthogonalize = |vector: &mut Vec<f64>| {
+        for eigenvector in found {
+            let projection = vector
+                .iter()
+                .zip(eigenvector.iter())
+                .map(|(a, b)| a * b)
+                .sum::<f64>();
+            for (value, component) in vector.iter_mut().zip(eigenvector.iter()) {
+                *value -= projection * component;
+            }
+        }
+        let norm = vector.iter().map(|value| value * value).sum::<f64>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|value| *value /= norm);
+        }
+        norm
+    };
+    // A deterministic start that is not orthogonal to the eigenvectors of typical matrices.
+    let mut vector = (0..n_rows)
+        .map(|row| 1.0 + (row as f64).sqrt())
+        .collect::<Vec<f64>>();
+    if orthogonalize(&mut vector) == 0.0 {
+        return (0.0, vec![0.0; n_rows]);
+    }
+    for _ in 0..MAX_POWER_ITERATIONS {
+        let mut next = (0..n_rows)
+            .map(|row| {
+                matrix[row]
+                    .iter()
+                    .zip(vector.iter())
+                    .map(|(a, b)| a * b)
+                    .sum::<f64>()
+                    + shift * vector[row]
+            })
+            .collect::<Vec<f64>>();
+        if orthogonalize(&mut next) == 0.0 {
+            return (0.0, vec![0.0; n_rows]);
+        }
+        let change = next
+            .iter()
+            .zip(vector.iter())
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max);
+        vector = next;
+        if change < 1e-12 {
+            break;
+        }
+    }
+    let eigenvalue = (0..n_rows)
+        .map(|row| {
+            vector[row]
+                * matrix[row]
+                    .iter()
+                    .zip(vector.iter())
+                    .map(|(a, b)| a * b)
+                    .sum::<f64>()
+        })
+        .sum::<f64>();
+    (eigenvalue, vector)
+}
+
+/// Check that every row has one distance for every row.
+fn check_square(rows: &[Vec<f64>]) -> Result<(), DistanceMatError> {
+    match rows
+        .iter()
+        .enumerate()
+        .find(|(_, row)| row.len() != rows.len())
+    {
+        Some((row, distances)) => Err(DistanceMatError::NotSquare {
+            row,
+            expected: rows.len(),
+            found: distances.len(),
+        }),
+        None => Ok(()),
+    }
+}
+// Convert borrowed rows of distances into a distance matrix, if they are square.
+impl TryFrom<&[Vec<f64>]> for DistanceMat {
+    type Error = DistanceMatError;
+    /// Create a new distance mat from borrowed rows of distances via `DistanceMat::try_from_rows`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The distances between all indexes 0..n.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let rows = vec![vec![0.0, 1.0], vec![1.0, 0.0]];
+    /// let distance_matrix = DistanceMat::try_from(&rows[..]).unwrap();
+    /// assert_eq!(distance_matrix.n_units(), 2);
+    /// ```
+    fn try_from(rows: &[Vec<f64>]) -> Result<Self, Self::Error> {
+        DistanceMat::try_from_rows(rows)
+    }
+}
+// Convert points into the matrix of their euclidean distances.
+impl From<Vec<(f64, f64)>> for DistanceMat {
+    /// Create the distance matrix of the euclidean distances between the points, via
+    /// `DistanceMat::from_coordinates`.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The (x, y) coordinates of the nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix: DistanceMat = vec![(0.0, 0.0), (3.0, 4.0)].into();
+    /// assert_eq!(distance_matrix.distance_between(0, 1), 5.0);
+    /// ```
+    fn from(points: Vec<(f64, f64)>) -> Self {
+        DistanceMat::from_coordinates(&points)
+    }
+}
+// Convert points into the matrix of their euclidean distances.
+impl From<&[(f64, f64)]> for DistanceMat {
+    /// Create the distance matrix of the euclidean distances between the points, via
+    /// `DistanceMat::from_coordinates`.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The (x, y) coordinates of the nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let points = [(0.0, 0.0), (3.0, 4.0)];
+    /// let distance_matrix = DistanceMat::from(&points[..]);
+    /// assert_eq!(distance_matrix.distance_between(1, 0), 5.0);
+    /// ```
+    fn from(points: &[(f64, f64)]) -> Self {
+        DistanceMat::from_coordinates(points)
+    }
+}
+// A TSP: a route is judged on its round-trip length.
+impl Objective for DistanceMat {
+    type Individual = Route;
+    /// Compute the round-trip length of the route.
+    ///
+    /// # Arguments
+    ///
+    /// * `individual` - The route whose length is computed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    /// use genetic_algo::gen_traits::Objective;
+    /// use genetic_algo::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]);
+    /// assert_eq!(distance_matrix.objective(&Route::new(vec![0, 1])), 2.0);
+    /// ```
+    fn objective(&self, individual: &Route) -> f64 {
+        self.get_distance(&individual.indexes)
+    }
+    /// A route has the same length in both directions if the distance matrix is symmetric.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    /// use genetic_algo::gen_traits::Objective;
+    ///
+    /// assert!(DistanceMat::new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]).is_symmetric());
+    /// assert!(!DistanceMat::new(vec![vec![0.0, 1.0], vec![2.0, 0.0]]).is_symmetric());
+    /// ```
+    fn is_symmetric(&self) -> bool {
+        self.distances.iter().enumerate().all(|(from, row)| {
+            row.iter()
+                .enumerate()
+                .all(|(to, distance)| *distance == self.distances[to][from])
+        })
+    }
+}
+// A multi-objective TSP: a route is judged on its length on every distance matrix.
+impl MultiObjective for Vec<DistanceMat> {
+    type Individual = Route;
+    /// Compute the round-trip length of the route on every distance matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `individual` - The route whose lengths are computed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::distance_mat::DistanceMat;
+    /// use genetic_algo::gen_traits::MultiObjective;
+    /// use genetic_algo::route::Route;
+    ///
+    /// let objectives = vec![
+    ///     DistanceMat::new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]),
+    ///     DistanceMat::new(vec![vec![0.0, 3.0], vec![3.0, 0.0]]),
+    /// ];
+    /// assert_eq!(objectives.objectives(&Route::new(vec![0, 1])), vec![2.0, 6.0]);
+    /// ```
+    fn objectives(&self, individual: &Route) -> Vec<f64> {
+        self.iter()
+            .map(|distance_mat| distance_mat.get_distance(&individual.indexes))
+            .collect()
     }
 }
 
@@ -82,23 +1668,996 @@ mod test_distance_mat {
         let dist_mat = DistanceMat::new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]);
         assert_eq!(dist_mat.distances, vec![vec![0.0, 1.0], vec![1.0, 0.0]]);
     }
+    mod test_from_coordinates {
+        use super::*;
+        #[test]
+        fn euclidean() {
+            let dist_mat = DistanceMat::from_coordinates(&[(0.0, 0.0), (3.0, 4.0), (0.0, 1.0)]);
+            assert_eq!(
+                dist_mat.distances,
+                vec![
+                    vec![0.0, 5.0, 1.0],
+                    vec![5.0, 0.0, 18.0_f64.sqrt()],
+                    vec![1.0, 18.0_f64.sqrt(), 0.0]
+                ]
+            );
+        }
+        #[test]
+        fn unit_weights_are_euclidean() {
+            let points = [(0.0, 0.0), (3.0, 4.0), (-1.5, 2.0), (0.25, -7.0)];
+            assert_eq!(
+                DistanceMat::from_coordinates_scaled(&points, 1.0, 1.0).distances,
+                DistanceMat::from_coordinates(&points).distances
+            );
+        }
+        #[test]
+        fn scaled_axes() {
+            let dist_mat =
+                DistanceMat::from_coordinates_scaled(&[(0.0, 0.0), (1.0, 2.0)], 3.0, 2.0);
+            assert_eq!(dist_mat.distances, vec![vec![0.0, 5.0], vec![5.0, 0.0]]);
+        }
+        #[test]
+        fn keeps_coordinates() {
+            let points = [(0.0, 0.0), (3.0, 4.0), (-1.5, 2.0)];
+            assert_eq!(
+                DistanceMat::from_coordinates(&points).coordinates(),
+                Some(&points[..])
+            );
+            assert_eq!(
+                DistanceMat::from_coordinates_scaled(&points, 2.0, 0.5).coordinates(),
+                Some(&points[..])
+            );
+        }
+        #[test]
+        fn explicit_distances_have_no_coordinates() {
+            assert_eq!(test_dist_mat().coordinates(), None);
+        }
+        #[test]
+        fn ignored_axis() {
+            let dist_mat =
+                DistanceMat::from_coordinates_scaled(&[(0.0, 0.0), (1.0, 2.0)], 0.0, 1.0);
+            assert_eq!(dist_mat.distances, vec![vec![0.0, 2.0], vec![2.0, 0.0]]);
+        }
+    }
     #[test]
     fn test_dist_same_node() {
-        assert_eq!(test_dist_mat().get_distance(&vec![0, 0]), 0.0);
+        assert_eq!(test_dist_mat().get_distance(&[0, 0]), 0.0);
     }
     #[test]
     fn test_dist_two_nodes() {
-        assert_eq!(test_dist_mat().get_distance(&vec![0, 1]), 2.0);
-        assert_eq!(test_dist_mat().get_distance(&vec![0, 2]), 4.0);
-        assert_eq!(test_dist_mat().get_distance(&vec![1, 2]), 6.0);
+        assert_eq!(test_dist_mat().get_distance(&[0, 1]), 2.0);
+        assert_eq!(test_dist_mat().get_distance(&[0, 2]), 4.0);
+        assert_eq!(test_dist_mat().get_distance(&[1, 2]), 6.0);
     }
     #[test]
     fn test_dist_three_nodes() {
-        assert_eq!(test_dist_mat().get_distance(&vec![0, 1, 2]), 6.0);
-        assert_eq!(test_dist_mat().get_distance(&vec![0, 2, 1]), 6.0);
+        assert_eq!(test_dist_mat().get_distance(&[0, 1, 2]), 6.0);
+        assert_eq!(test_dist_mat().get_distance(&[0, 2, 1]), 6.0);
     }
     #[test]
     fn test_dist_repeat_visit() {
         assert_eq!(test_dist_mat().get_distance(&[0, 2, 1, 2]), 10.0);
     }
+    mod test_get_distance_subset {
+        use super::*;
+        fn asymmetric_dist_mat() -> DistanceMat {
+            DistanceMat::new(vec![
+                vec![0.0, 1.0, 2.0, 4.0],
+                vec![5.0, 0.0, 3.0, 6.0],
+                vec![7.0, 8.0, 0.0, 9.0],
+                vec![10.0, 11.0, 12.0, 0.0],
+            ])
+        }
+        #[test]
+        fn two_nodes() {
+            let distance_mat = asymmetric_dist_mat();
+            assert_eq!(distance_mat.get_distance_subset(&[1, 3], false), 6.0);
+            assert_eq!(distance_mat.get_distance_subset(&[1, 3], true), 17.0);
+            assert_eq!(distance_mat.get_distance_subset(&[3, 1], false), 11.0);
+        }
+        #[test]
+        fn three_nodes() {
+            let distance_mat = asymmetric_dist_mat();
+            assert_eq!(distance_mat.get_distance_subset(&[0, 2, 3], false), 11.0);
+            assert_eq!(distance_mat.get_distance_subset(&[0, 2, 3], true), 21.0);
+            assert_eq!(distance_mat.get_distance_subset(&[2, 1, 0], true), 15.0);
+        }
+        #[test]
+        fn full_closed_path_is_round_trip() {
+            let distance_mat = asymmetric_dist_mat();
+            assert_eq!(
+                distance_mat.get_distance_subset(&[2, 0, 3, 1], true),
+                distance_mat.get_distance(&[2, 0, 3, 1])
+            );
+        }
+        #[test]
+        fn tiny_paths() {
+            let distance_mat = asymmetric_dist_mat();
+            assert_eq!(distance_mat.get_distance_subset(&[], true), 0.0);
+            assert_eq!(distance_mat.get_distance_subset(&[2], true), 0.0);
+            assert_eq!(distance_mat.get_distance_subset(&[2], false), 0.0);
+        }
+        #[test]
+        #[should_panic]
+        fn unknown_node() {
+            asymmetric_dist_mat().get_distance_subset(&[0, 4], false);
+        }
+    }
+    mod test_cached_objective {
+        use super::*;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        // Compute the length of a route on a distance matrix and count how often it did so.
+        #[derive(Debug)]
+        struct CountingObjective {
+            distance_mat: DistanceMat,
+            n_calls: AtomicUsize,
+            n_symmetry_checks: AtomicUsize,
+        }
+        impl Objective for CountingObjective {
+            type Individual = Route;
+            fn objective(&self, individual: &Route) -> f64 {
+                self.n_calls.fetch_add(1, Ordering::SeqCst);
+                self.distance_mat.get_distance(&individual.indexes)
+            }
+            fn is_symmetric(&self) -> bool {
+                self.n_symmetry_checks.fetch_add(1, Ordering::SeqCst);
+                self.distance_mat.is_symmetric()
+            }
+        }
+        fn counting(distances: Vec<Vec<f64>>) -> CachedObjective<CountingObjective> {
+            CachedObjective::new(CountingObjective {
+                distance_mat: DistanceMat::new(distances),
+                n_calls: AtomicUsize::new(0),
+                n_symmetry_checks: AtomicUsize::new(0),
+            })
+        }
+        #[test]
+        fn canonical_tours_evaluated_once() {
+            let cached = counting(test_dist_mat().distances);
+            let length = cached.objective(&Route::new(vec![0, 1, 2]));
+            for route in [
+                vec![0, 1, 2],
+                vec![1, 2, 0],
+                vec![2, 0, 1],
+                vec![2, 1, 0],
+                vec![0, 2, 1],
+            ] {
+                assert_eq!(cached.objective(&Route::new(route)), length);
+            }
+            assert_eq!(cached.inner().n_calls.load(Ordering::SeqCst), 1);
+            assert_eq!(cached.cache_len(), 1);
+        }
+        #[test]
+        fn symmetry_checked_once() {
+            let cached = counting(test_dist_mat().distances);
+            for route in [vec![0, 1, 2], vec![1, 2, 0], vec![2, 1, 0]] {
+                cached.objective(&Route::new(route));
+            }
+            assert!(cached.is_symmetric());
+            assert_eq!(cached.inner().n_symmetry_checks.load(Ordering::SeqCst), 1);
+        }
+        #[test]
+        fn asymmetric_directions_differ() {
+            let cached = counting(vec![
+                vec![0.0, 1.0, 1.0],
+                vec![5.0, 0.0, 1.0],
+                vec![1.0, 5.0, 0.0],
+            ]);
+            assert_eq!(cached.objective(&Route::new(vec![0, 1, 2])), 3.0);
+            assert_eq!(cached.objective(&Route::new(vec![1, 2, 0])), 3.0);
+            assert_eq!(cached.objective(&Route::new(vec![2, 1, 0])), 11.0);
+            assert_eq!(cached.inner().n_calls.load(Ordering::SeqCst), 2);
+        }
+        #[test]
+        fn clear_cache_recomputes() {
+            let cached = counting(test_dist_mat().distances);
+            cached.objective(&Route::new(vec![0, 1, 2]));
+            cached.clear_cache();
+            assert_eq!(cached.cache_len(), 0);
+            cached.objective(&Route::new(vec![0, 1, 2]));
+            assert_eq!(cached.inner().n_calls.load(Ordering::SeqCst), 2);
+        }
+    }
+    mod test_from_coordinates_par {
+        use super::*;
+        fn random_points(n_points: usize) -> Vec<(f64, f64)> {
+            let mut rng = rand::thread_rng();
+            (0..n_points)
+                .map(|_| (rng.gen_range(-100.0..100.0), rng.gen_range(-100.0..100.0)))
+                .collect()
+        }
+        #[test]
+        fn same_as_serial() {
+            for n_points in [0, 1, 2, 7, 100] {
+                let points = random_points(n_points);
+                let parallel = DistanceMat::from_coordinates_par(&points);
+                let serial = DistanceMat::from_coordinates(&points);
+                assert_eq!(parallel.distances, serial.distances);
+                assert_eq!(parallel.coordinates(), serial.coordinates());
+            }
+        }
+        // Run with `cargo test --release -- --ignored --nocapture` to compare the timings.
+        #[test]
+        #[ignore]
+        fn benchmark_against_serial() {
+            let points = random_points(2000);
+            let start = std::time::Instant::now();
+            let serial = DistanceMat::from_coordinates(&points);
+            let serial_time = start.elapsed();
+            let start = std::time::Instant::now();
+            let parallel = DistanceMat::from_coordinates_par(&points);
+            let parallel_time = start.elapsed();
+            println!(
+                "n = 2000: serial {:?}, parallel {:?}",
+                serial_time, parallel_time
+            );
+            assert_eq!(serial.distances, parallel.distances);
+        }
+    }
+    mod test_from_coordinates_squared {
+        use super::*;
+        #[test]
+        fn squares_of_euclidean_distances() {
+            let points = [(0.0, 0.0), (1.5, -2.0), (4.0, 1.0), (-3.0, 0.5)];
+            let squared = DistanceMat::from_coordinates_squared(&points);
+            let euclidean = DistanceMat::from_coordinates(&points);
+            for from in 0..points.len() {
+                for to in 0..points.len() {
+                    assert!(
+                        (squared.distance_between(from, to)
+                            - euclidean.distance_between(from, to).powi(2))
+                        .abs()
+                            < 1e-9
+                    );
+                }
+            }
+            assert_eq!(squared.coordinates(), Some(&points[..]));
+        }
+        #[test]
+        fn ranking_can_differ() {
+            // The edges of the first route are 2, 1, 2 and 1 long, those of the second one 1, 1, 1
+            // and 3: both are equally long in the euclidean metric, but not in the squared one.
+            let points = [(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (3.0, 0.0)];
+            let squared = DistanceMat::from_coordinates_squared(&points);
+            assert_eq!(squared.get_distance(&[0, 2, 3, 1]), 10.0);
+            assert_eq!(squared.get_distance(&[0, 1, 2, 3]), 12.0);
+            let euclidean = DistanceMat::from_coordinates(&points);
+            assert_eq!(euclidean.get_distance(&[0, 2, 3, 1]), 6.0);
+            assert_eq!(euclidean.get_distance(&[0, 1, 2, 3]), 6.0);
+        }
+    }
+    mod test_try_from_rows {
+        use super::*;
+        #[test]
+        fn borrowed_rows() {
+            let rows = vec![
+                vec![0.0, 1.0, 2.0],
+                vec![1.0, 0.0, 3.0],
+                vec![2.0, 3.0, 0.0],
+            ];
+            let distance_mat = DistanceMat::try_from_rows(&rows).unwrap();
+            assert!(distance_mat.distances_approx_eq(&test_dist_mat(), 0.0));
+            let via_trait = DistanceMat::try_from(&rows[..]).unwrap();
+            assert!(via_trait.distances_approx_eq(&test_dist_mat(), 0.0));
+            // The rows are still usable after the construction.
+            assert_eq!(rows.len(), 3);
+        }
+        #[test]
+        fn ragged_rows() {
+            let rows = vec![vec![0.0, 1.0, 2.0], vec![1.0, 0.0], vec![2.0, 3.0, 0.0]];
+            let error = DistanceMatError::NotSquare {
+                row: 1,
+                expected: 3,
+                found: 2,
+            };
+            assert_eq!(DistanceMat::try_from_rows(&rows).unwrap_err(), error);
+            assert_eq!(DistanceMat::try_from(&rows[..]).unwrap_err(), error);
+            assert_eq!(DistanceMat::try_new(rows).unwrap_err(), error);
+        }
+        #[test]
+        fn too_many_columns() {
+            assert_eq!(
+                DistanceMat::try_new(vec![vec![0.0, 1.0, 2.0], vec![1.0, 0.0, 3.0]]).unwrap_err(),
+                DistanceMatError::NotSquare {
+                    row: 0,
+                    expected: 2,
+                    found: 3
+                }
+            );
+        }
+        #[test]
+        fn empty() {
+            assert_eq!(DistanceMat::try_from_rows(&[]).unwrap().n_units(), 0);
+        }
+    }
+    mod test_from_coordinates_nd {
+        use super::*;
+        #[test]
+        fn three_dimensions() {
+            let distance_mat = DistanceMat::from_coordinates_nd(&[
+                vec![0.0, 0.0, 0.0],
+                vec![1.0, 2.0, 2.0],
+                vec![0.0, 3.0, 4.0],
+            ])
+            .unwrap();
+            assert_eq!(
+                distance_mat.distances,
+                vec![
+                    vec![0.0, 3.0, 5.0],
+                    vec![3.0, 0.0, 6.0_f64.sqrt()],
+                    vec![5.0, 6.0_f64.sqrt(), 0.0],
+                ]
+            );
+            assert_eq!(distance_mat.coordinates(), None);
+        }
+        #[test]
+        fn same_as_two_dimensions() {
+            let points = [(0.0, 0.0), (3.0, 4.0), (0.0, 1.0)];
+            let distance_mat = DistanceMat::from_coordinates_nd(
+                &points
+                    .iter()
+                    .map(|(x, y)| vec![*x, *y])
+                    .collect::<Vec<Vec<f64>>>(),
+            )
+            .unwrap();
+            assert!(distance_mat.distances_approx_eq(&DistanceMat::from_coordinates(&points), 0.0));
+        }
+        #[test]
+        fn mismatched_dimensions() {
+            assert_eq!(
+                DistanceMat::from_coordinates_nd(&[vec![0.0, 0.0], vec![1.0, 1.0], vec![1.0]])
+                    .unwrap_err(),
+                DistanceMatError::DimensionMismatch {
+                    point: 2,
+                    expected: 2,
+                    found: 1
+                }
+            );
+        }
+        #[test]
+        fn no_points() {
+            assert_eq!(DistanceMat::from_coordinates_nd(&[]).unwrap().n_units(), 0);
+        }
+    }
+    #[test]
+    fn test_from_points() {
+        let points = vec![(0.0, 0.0), (3.0, 4.0), (0.0, 1.0)];
+        let expected = DistanceMat::from_coordinates(&points);
+        let from_slice = DistanceMat::from(&points[..]);
+        let from_vec: DistanceMat = points.clone().into();
+        assert_eq!(from_vec.distances, expected.distances);
+        assert_eq!(from_slice.distances, expected.distances);
+        assert_eq!(from_vec.coordinates(), Some(&points[..]));
+    }
+    mod test_brute_force_optimal {
+        use super::*;
+        #[test]
+        fn five_cities() {
+            // A regular pentagon whose nodes are numbered out of order. The optimal route visits
+            // them around the circle, each side is 2 * sin(36°) long.
+            let order = [0, 3, 1, 4, 2];
+            let mut points = vec![(0.0, 0.0); 5];
+            for (position, node) in order.iter().enumerate() {
+                let angle = 2.0 * std::f64::consts::PI * position as f64 / 5.0;
+                points[*node] = (angle.cos(), angle.sin());
+            }
+            let (route, distance) = DistanceMat::from_coordinates(&points)
+                .brute_force_optimal()
+                .unwrap();
+            assert!((distance - 10.0 * 36.0_f64.to_radians().sin()).abs() < 1e-9);
+            assert!(route.indexes == order || route.indexes == [0, 2, 4, 1, 3]);
+        }
+        #[test]
+        fn matches_all_routes() {
+            let distance_mat = DistanceMat::from_coordinates(&[
+                (0.0, 0.0),
+                (4.0, 1.0),
+                (1.0, 3.0),
+                (5.0, 5.0),
+                (2.0, 1.0),
+                (3.0, 4.0),
+            ]);
+            let (route, distance) = distance_mat.brute_force_optimal().unwrap();
+            assert_eq!(distance, distance_mat.get_distance(&route.indexes));
+            let mut rng = rand::thread_rng();
+            for _ in 0..500 {
+                let other = crate::utils::random_permutation_with(&[0, 1, 2, 3, 4, 5], &mut rng);
+                assert!(distance <= distance_mat.get_distance(&other) + 1e-9);
+            }
+        }
+        #[test]
+        fn tiny_instances() {
+            assert_eq!(
+                DistanceMat::new(vec![vec![0.0]]).brute_force_optimal(),
+                Some((Route::new(vec![0]), 0.0))
+            );
+            assert_eq!(
+                test_dist_mat().brute_force_optimal(),
+                Some((Route::new(vec![0, 1, 2]), 6.0))
+            );
+        }
+        #[test]
+        fn refuses_large_instances() {
+            assert_eq!(DistanceMat::new(vec![]).brute_force_optimal(), None);
+            assert_eq!(
+                crate::test_utils::circle_dist_mat(11).brute_force_optimal(),
+                None
+            );
+        }
+    }
+    mod test_lower_bounds {
+        use super::*;
+        fn unit_square() -> DistanceMat {
+            DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)])
+        }
+        #[test]
+        fn mst() {
+            // The cheapest edges 0-1 and 0-2 connect all nodes.
+            assert_eq!(test_dist_mat().mst_lower_bound(), 3.0);
+            // Three sides of the square.
+            assert_eq!(unit_square().mst_lower_bound(), 3.0);
+        }
+        #[test]
+        fn one_tree() {
+            assert_eq!(test_dist_mat().one_tree_lower_bound(), 6.0);
+            // The spanning tree 1-2-3 plus the sides 0-1 and 0-3 is the optimal round-trip.
+            assert_eq!(unit_square().one_tree_lower_bound(), 4.0);
+        }
+        #[test]
+        fn bounds_are_below_optimum() {
+            let distance_mat = crate::test_utils::circle_dist_mat(8);
+            let optimum = distance_mat.get_distance(&[0, 1, 2, 3, 4, 5, 6, 7]);
+            assert!(distance_mat.mst_lower_bound() <= distance_mat.one_tree_lower_bound());
+            assert!(distance_mat.one_tree_lower_bound() <= optimum + 1e-9);
+        }
+        #[test]
+        fn tiny_instances() {
+            assert_eq!(DistanceMat::new(vec![]).mst_lower_bound(), 0.0);
+            assert_eq!(
+                DistanceMat::new(vec![vec![0.0]]).one_tree_lower_bound(),
+                0.0
+            );
+            assert_eq!(
+                DistanceMat::new(vec![vec![0.0, 2.0], vec![2.0, 0.0]]).one_tree_lower_bound(),
+                2.0
+            );
+        }
+    }
+    mod test_cluster_solve {
+        use super::*;
+        use crate::test_utils::valid_permutation;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        // Two rings of 6 points with radius 1, which are 100 apart. The nodes of both rings
+        // alternate, so that the clusters are not just ranges of nodes.
+        fn two_rings() -> DistanceMat {
+            DistanceMat::from_coordinates(
+                &(0..12)
+                    .map(|node| {
+                        let angle = 2.0 * std::f64::consts::PI * (node / 2) as f64 / 6.0;
+                        let offset = if node % 2 == 0 { 0.0 } else { 100.0 };
+                        (offset + angle.cos(), angle.sin())
+                    })
+                    .collect::<Vec<(f64, f64)>>(),
+            )
+        }
+        #[test]
+        fn two_clusters() {
+            let distance_mat = two_rings();
+            let route =
+                distance_mat.cluster_solve(2, &GaConfig::default(), &mut rand::thread_rng());
+            valid_permutation(&route.indexes, &(0..12).collect::<Vec<usize>>());
+            // Both rings are 6 long and the two bridges are at most 100 long each. Every other
+            // route crosses between the rings at least four times.
+            assert!(distance_mat.get_distance(&route.indexes) <= 212.0 + 1e-9);
+        }
+        #[test]
+        fn clusters_smaller_than_population() {
+            // Two squares 100 apart, each of which only has 4! = 24 different routes.
+            let distance_mat = DistanceMat::from_coordinates(&[
+                (0.0, 0.0),
+                (1.0, 0.0),
+                (1.0, 1.0),
+                (0.0, 1.0),
+                (100.0, 0.0),
+                (101.0, 0.0),
+                (101.0, 1.0),
+                (100.0, 1.0),
+            ]);
+            let config = GaConfig {
+                population_size: 30,
+                ..GaConfig::default()
+            };
+            let route = distance_mat.cluster_solve(2, &config, &mut rand::thread_rng());
+            valid_permutation(&route.indexes, &(0..8).collect::<Vec<usize>>());
+            // Every route that crosses between the squares more than twice is at least 396 long.
+            assert!(distance_mat.get_distance(&route.indexes) < 208.0);
+        }
+        #[test]
+        fn starts_at_node_0() {
+            let route = two_rings().cluster_solve(2, &GaConfig::default(), &mut rand::thread_rng());
+            assert_eq!(route.indexes[0], 0);
+        }
+        #[test]
+        fn same_seed_same_route() {
+            let distance_mat = two_rings();
+            let solve = |seed: u64| {
+                distance_mat.cluster_solve(
+                    2,
+                    &GaConfig::default(),
+                    &mut StdRng::seed_from_u64(seed),
+                )
+            };
+            assert_eq!(solve(11), solve(11));
+        }
+        #[test]
+        fn one_cluster_per_node() {
+            let route =
+                test_dist_mat().cluster_solve(5, &GaConfig::default(), &mut rand::thread_rng());
+            valid_permutation(&route.indexes, &[0, 1, 2]);
+        }
+        #[test]
+        fn clusters_partition_nodes() {
+            let clusters = two_rings().cluster_nodes(2, &mut rand::thread_rng());
+            assert_eq!(clusters.len(), 2);
+            for cluster in clusters {
+                assert_eq!(cluster.len(), 6);
+                assert!(cluster.iter().all(|node| node % 2 == cluster[0] % 2));
+            }
+        }
+        #[test]
+        #[should_panic]
+        fn no_clusters() {
+            test_dist_mat().cluster_solve(0, &GaConfig::default(), &mut rand::thread_rng());
+        }
+    }
+    mod test_embed_2d {
+        use super::*;
+        fn assert_distances_preserved(points: &[(f64, f64)], tol: f64) {
+            let original = DistanceMat::from_coordinates(points);
+            let embedded = DistanceMat::from_coordinates(&original.embed_2d());
+            assert!(original.distances_approx_eq(&embedded, tol));
+        }
+        #[test]
+        fn recovers_planar_points() {
+            assert_distances_preserved(
+                &[
+                    (0.0, 0.0),
+                    (4.0, 0.5),
+                    (5.0, 3.0),
+                    (1.0, 4.0),
+                    (-2.0, 2.5),
+                    (2.0, 1.5),
+                    (3.0, -2.0),
+                ],
+                1e-6,
+            );
+        }
+        #[test]
+        fn recovers_collinear_points() {
+            assert_distances_preserved(&[(0.0, 0.0), (1.0, 1.0), (3.0, 3.0), (-2.0, -2.0)], 1e-6);
+        }
+        #[test]
+        fn tiny_matrices() {
+            assert_eq!(DistanceMat::new(Vec::new()).embed_2d(), Vec::new());
+            assert_eq!(
+                DistanceMat::new(vec![vec![0.0]]).embed_2d(),
+                vec![(0.0, 0.0)]
+            );
+            assert_distances_preserved(&[(0.0, 0.0), (3.0, 4.0)], 1e-6);
+        }
+    }
+    mod test_metric_closure {
+        use super::*;
+        #[test]
+        fn fills_missing_edge() {
+            let dist_mat = DistanceMat::new(vec![
+                vec![0.0, 1.0, f64::INFINITY, 5.0],
+                vec![1.0, 0.0, 2.0, f64::INFINITY],
+                vec![f64::INFINITY, 2.0, 0.0, 1.0],
+                vec![5.0, f64::INFINITY, 1.0, 0.0],
+            ]);
+            assert_eq!(
+                dist_mat.metric_closure().distances,
+                vec![
+                    vec![0.0, 1.0, 3.0, 4.0],
+                    vec![1.0, 0.0, 2.0, 3.0],
+                    vec![3.0, 2.0, 0.0, 1.0],
+                    vec![4.0, 3.0, 1.0, 0.0],
+                ]
+            );
+        }
+        #[test]
+        fn metric_matrix_unchanged() {
+            assert_eq!(
+                test_dist_mat().metric_closure().distances,
+                test_dist_mat().distances
+            );
+        }
+        #[test]
+        fn disconnected_nodes_stay_infinite() {
+            let dist_mat =
+                DistanceMat::new(vec![vec![0.0, f64::INFINITY], vec![f64::INFINITY, 0.0]]);
+            assert_eq!(
+                dist_mat.metric_closure().distance_between(0, 1),
+                f64::INFINITY
+            );
+        }
+    }
+    mod test_nearest_neighbor_route {
+        use super::*;
+        use crate::test_utils::{circle_dist_mat, valid_permutation};
+        // Starting from node 0, the heuristic first turns left and has to come back later,
+        // every other start leads to a shorter route.
+        fn detour() -> DistanceMat {
+            DistanceMat::from_coordinates(&[
+                (1.0, 0.0),
+                (0.0, 0.0),
+                (2.0, 0.0),
+                (3.0, 0.0),
+                (0.0, 1.2),
+                (3.0, 1.0),
+                (1.5, 3.0),
+            ])
+        }
+        #[test]
+        fn visits_nearest_nodes() {
+            assert_eq!(
+                detour().nearest_neighbor_route(0),
+                Route::new(vec![0, 1, 4, 2, 3, 5, 6])
+            );
+            valid_permutation(
+                &circle_dist_mat(7).nearest_neighbor_route(3).indexes,
+                &[0, 1, 2, 3, 4, 5, 6],
+            );
+        }
+        #[test]
+        fn best_start_is_not_longer() {
+            let distance_mat = detour();
+            let from_first =
+                distance_mat.get_distance(&distance_mat.nearest_neighbor_route(0).indexes);
+            for max_starts in 1..=8 {
+                let best = distance_mat.best_nearest_neighbor_route(max_starts);
+                valid_permutation(&best.indexes, &[0, 1, 2, 3, 4, 5, 6]);
+                assert!(distance_mat.get_distance(&best.indexes) <= from_first);
+            }
+            assert!(
+                distance_mat.get_distance(&distance_mat.best_nearest_neighbor_route(2).indexes)
+                    < from_first
+            );
+        }
+        #[test]
+        fn empty_matrix() {
+            assert_eq!(
+                DistanceMat::new(vec![]).best_nearest_neighbor_route(3),
+                Route::new(vec![])
+            );
+        }
+        #[test]
+        #[should_panic]
+        fn invalid_start() {
+            test_dist_mat().nearest_neighbor_route(3);
+        }
+    }
+    mod test_cheapest_insertion_route {
+        use super::*;
+        use crate::test_utils::{circle_dist_mat, valid_permutation};
+        #[test]
+        fn valid_route() {
+            let route = circle_dist_mat(13).cheapest_insertion_route();
+            valid_permutation(&route.indexes, &(0..13).collect::<Vec<usize>>());
+        }
+        #[test]
+        fn close_to_optimum_on_circle() {
+            let distance_mat = circle_dist_mat(20);
+            let optimal_distance = distance_mat.get_distance(&(0..20).collect::<Vec<usize>>());
+            let route = distance_mat.cheapest_insertion_route();
+            assert!(distance_mat.get_distance(&route.indexes) <= 1.05 * optimal_distance);
+        }
+        #[test]
+        fn tiny_matrices() {
+            assert_eq!(
+                DistanceMat::new(vec![]).cheapest_insertion_route(),
+                Route::new(vec![])
+            );
+            assert_eq!(
+                DistanceMat::new(vec![vec![0.0]]).cheapest_insertion_route(),
+                Route::new(vec![0])
+            );
+            valid_permutation(
+                &test_dist_mat().cheapest_insertion_route().indexes,
+                &[0, 1, 2],
+            );
+        }
+    }
+    mod test_get_distance {
+        use super::*;
+        #[test]
+        fn round_trip() {
+            assert_eq!(test_dist_mat().get_distance(&[0, 1, 2]), 6.0);
+            assert_eq!(test_dist_mat().get_distance(&[1, 2]), 6.0);
+            assert_eq!(test_dist_mat().get_distance(&[2]), 0.0);
+        }
+        #[test]
+        fn same_as_edges() {
+            let distance_mat = crate::test_utils::circle_dist_mat(50);
+            let route = crate::utils::random_permutation_with(
+                &(0..50).collect::<Vec<usize>>(),
+                &mut rand::thread_rng(),
+            );
+            let mut expected = distance_mat.distance_between(route[49], route[0]);
+            for edge in 0..49 {
+                expected += distance_mat.distance_between(route[edge], route[edge + 1]);
+            }
+            assert_eq!(distance_mat.get_distance(&route), expected);
+        }
+        #[test]
+        fn two_three_and_four_nodes() {
+            let distance_mat = DistanceMat::new(vec![
+                vec![0.0, 1.0, 7.0, 3.0],
+                vec![4.0, 0.0, 2.0, 9.0],
+                vec![6.0, 5.0, 0.0, 1.0],
+                vec![2.0, 8.0, 3.0, 0.0],
+            ]);
+            assert_eq!(distance_mat.get_distance(&[2, 1]), 5.0 + 2.0);
+            assert_eq!(distance_mat.get_distance(&[0, 3, 1]), 3.0 + 8.0 + 4.0);
+            assert_eq!(
+                distance_mat.get_distance(&[1, 0, 2, 3]),
+                4.0 + 7.0 + 1.0 + 8.0
+            );
+        }
+        // Run with `cargo test --release -- --ignored --nocapture` to see the time per call.
+        #[test]
+        #[ignore]
+        fn benchmark_1000_nodes() {
+            let distance_mat = crate::test_utils::circle_dist_mat(1000);
+            let route = (0..1000).collect::<Vec<usize>>();
+            let n_calls: u32 = 10_000;
+            let mut total = 0.0;
+            let start = std::time::Instant::now();
+            for _ in 0..n_calls {
+                total += distance_mat.get_distance(std::hint::black_box(&route));
+            }
+            let elapsed = start.elapsed();
+            println!("n = 1000: {:?} per call", elapsed / n_calls);
+            assert!(total > 0.0);
+        }
+        #[test]
+        #[should_panic]
+        fn empty_route() {
+            test_dist_mat().get_distance(&[]);
+        }
+    }
+    #[test]
+    fn test_get_distances() {
+        let routes = vec![vec![0, 1], vec![0, 1, 2], vec![2, 0, 1], vec![0, 2, 1, 2]];
+        assert_eq!(
+            test_dist_mat().get_distances(&routes),
+            routes
+                .iter()
+                .map(|route| test_dist_mat().get_distance(route))
+                .collect::<Vec<f64>>()
+        );
+        assert_eq!(test_dist_mat().get_distances(&[]), Vec::<f64>::new());
+    }
+    mod test_route_distance {
+        use super::*;
+        #[test]
+        fn same_as_get_distance() {
+            let distance_mat = crate::test_utils::circle_dist_mat(20);
+            let route = Route::new(crate::utils::random_permutation_with(
+                &(0..20).collect::<Vec<usize>>(),
+                &mut rand::thread_rng(),
+            ));
+            assert_eq!(
+                distance_mat.route_distance(&route),
+                distance_mat.get_distance(&route.indexes)
+            );
+            assert_eq!(
+                route.distance(&distance_mat),
+                distance_mat.route_distance(&route)
+            );
+        }
+    }
+    mod test_try_get_finite_distance {
+        use super::*;
+        #[test]
+        fn finite_route() {
+            assert_eq!(
+                test_dist_mat().try_get_finite_distance(&[0, 1, 2]),
+                Some(6.0)
+            );
+            assert_eq!(test_dist_mat().try_get_finite_distance(&[]), Some(0.0));
+        }
+        #[test]
+        fn short_circuits_on_infinite_edge() {
+            let distance_mat = DistanceMat::new(vec![
+                vec![0.0, f64::INFINITY, 1.0],
+                vec![f64::INFINITY, 0.0, 1.0],
+                vec![1.0, 1.0, 0.0],
+            ]);
+            // Node 7 does not exist, so it would panic if the edges after the infinite first
+            // edge were visited.
+            assert_eq!(distance_mat.try_get_finite_distance(&[0, 1, 7]), None);
+            assert_eq!(distance_mat.try_get_finite_distance(&[2, 0, 1]), None);
+        }
+        #[test]
+        fn nan_edge() {
+            let distance_mat = DistanceMat::new(vec![vec![0.0, f64::NAN], vec![1.0, 0.0]]);
+            assert_eq!(distance_mat.try_get_finite_distance(&[0, 1]), None);
+            assert_eq!(distance_mat.try_get_finite_distance(&[1, 0]), None);
+        }
+    }
+    mod test_edge_distances {
+        use super::*;
+        #[test]
+        fn sums_to_distance() {
+            for route in [vec![0, 1], vec![0, 1, 2], vec![2, 0, 1], vec![0, 2, 1, 2]] {
+                let edges = test_dist_mat().edge_distances(&route);
+                assert_eq!(edges.len(), route.len());
+                assert_eq!(
+                    edges.iter().sum::<f64>(),
+                    test_dist_mat().get_distance(&route)
+                );
+            }
+        }
+        #[test]
+        fn includes_closing_edge() {
+            assert_eq!(test_dist_mat().edge_distances(&[2, 1]), vec![3.0, 3.0]);
+        }
+        #[test]
+        fn empty_route() {
+            assert_eq!(test_dist_mat().edge_distances(&[]), Vec::<f64>::new());
+        }
+    }
+    mod test_swap_delta {
+        use super::*;
+        fn assert_delta_matches_swap(distance_mat: &DistanceMat, route: &[usize]) {
+            for pos_a in 0..route.len() {
+                for pos_b in 0..route.len() {
+                    let mut swapped = route.to_vec();
+                    swapped.swap(pos_a, pos_b);
+                    let delta = distance_mat.swap_delta(route, pos_a, pos_b);
+                    assert!(
+                        (distance_mat.get_distance(route) + delta
+                            - distance_mat.get_distance(&swapped))
+                        .abs()
+                            < 1e-9,
+                        "Swapping {} and {} of {:?}",
+                        pos_a,
+                        pos_b,
+                        route
+                    );
+                }
+            }
+        }
+        #[test]
+        fn matches_swap_including_adjacent_positions() {
+            let distance_mat = DistanceMat::from_coordinates(&[
+                (0.0, 0.0),
+                (3.0, 1.0),
+                (5.0, 4.0),
+                (1.0, 6.0),
+                (-2.0, 3.0),
+                (2.0, 2.0),
+            ]);
+            assert_delta_matches_swap(&distance_mat, &[0, 3, 1, 5, 2, 4]);
+        }
+        #[test]
+        fn asymmetric_matrix() {
+            let distance_mat = DistanceMat::new(vec![
+                vec![0.0, 1.0, 7.0, 3.0],
+                vec![4.0, 0.0, 2.0, 9.0],
+                vec![6.0, 5.0, 0.0, 1.0],
+                vec![2.0, 8.0, 3.0, 0.0],
+            ]);
+            assert_delta_matches_swap(&distance_mat, &[2, 0, 3, 1]);
+        }
+        #[test]
+        fn tiny_routes() {
+            assert_delta_matches_swap(&test_dist_mat(), &[0, 1]);
+            assert_delta_matches_swap(&test_dist_mat(), &[2, 0, 1]);
+        }
+        #[test]
+        #[should_panic]
+        fn position_out_of_route() {
+            test_dist_mat().swap_delta(&[0, 1, 2], 0, 3);
+        }
+    }
+    mod test_get_distance_iter {
+        use super::*;
+        #[test]
+        fn same_as_slice() {
+            for route in [vec![0, 1], vec![0, 1, 2], vec![2, 0, 1], vec![0, 2, 1, 2]] {
+                assert_eq!(
+                    test_dist_mat().get_distance_iter(route.iter().cloned()),
+                    test_dist_mat().get_distance(&route)
+                );
+            }
+        }
+        #[test]
+        fn from_lazy_iterator() {
+            assert_eq!(test_dist_mat().get_distance_iter((0..3).rev()), 6.0);
+        }
+        #[test]
+        fn empty_route() {
+            assert_eq!(test_dist_mat().get_distance_iter(Vec::new()), 0.0);
+        }
+    }
+    #[test]
+    fn test_distance_between() {
+        assert_eq!(test_dist_mat().distance_between(0, 2), 2.0);
+        assert_eq!(test_dist_mat().distance_between(2, 1), 3.0);
+    }
+    mod test_distances_approx_eq {
+        use super::*;
+        #[test]
+        fn within_and_beyond_tolerance() {
+            let distance_mat = DistanceMat::new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]);
+            let close = DistanceMat::new(vec![vec![0.0, 1.0 + 1e-10], vec![1.0, 0.0]]);
+            let far = DistanceMat::new(vec![vec![0.0, 1.1], vec![1.0, 0.0]]);
+            assert!(distance_mat.distances_approx_eq(&close, 1e-9));
+            assert!(!distance_mat.distances_approx_eq(&far, 1e-9));
+            assert!(distance_mat.distances_approx_eq(&far, 0.2));
+        }
+        #[test]
+        fn different_shape() {
+            assert!(!test_dist_mat().distances_approx_eq(
+                &DistanceMat::new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]),
+                f64::INFINITY
+            ));
+        }
+        #[test]
+        fn infinite_distances() {
+            let distance_mat = DistanceMat::new(vec![vec![0.0, f64::INFINITY], vec![1.0, 0.0]]);
+            let other = DistanceMat::new(vec![vec![0.0, f64::INFINITY], vec![1.0, 0.0]]);
+            assert!(distance_mat.distances_approx_eq(&other, 0.0));
+        }
+    }
+    mod test_is_compatible {
+        use super::*;
+        #[test]
+        fn compatible_route() {
+            assert!(test_dist_mat().is_compatible(&Route::new(vec![1, 2, 0])));
+        }
+        #[test]
+        fn route_of_other_matrix() {
+            let larger =
+                DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+            let route = Route::new(vec![3, 1, 0, 2]);
+            assert!(larger.is_compatible(&route));
+            assert!(!test_dist_mat().is_compatible(&route));
+            assert!(!larger.is_compatible(&Route::new(vec![1, 2, 0])));
+        }
+        #[test]
+        fn error_message() {
+            assert_eq!(
+                DistanceMatError::IncompatibleRoute {
+                    route: vec![3, 1, 0, 2],
+                    n_units: 3
+                }
+                .to_string(),
+                "route [3, 1, 0, 2] does not visit the 3 nodes of the matrix exactly once"
+            );
+        }
+    }
+    mod test_route_matches {
+        use super::*;
+        #[test]
+        fn valid_route() {
+            assert!(test_dist_mat().route_matches(&[2, 0, 1]));
+        }
+        #[test]
+        fn too_short() {
+            assert!(!test_dist_mat().route_matches(&[2, 0]));
+        }
+        #[test]
+        fn duplicated_node() {
+            assert!(!test_dist_mat().route_matches(&[2, 0, 2]));
+        }
+        #[test]
+        fn unknown_node() {
+            assert!(!test_dist_mat().route_matches(&[2, 0, 3]));
+        }
+    }
 }
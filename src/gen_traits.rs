@@ -30,8 +30,8 @@ pub trait Individual<'a>: Clone {
     /// # Arguments
     ///
     /// * `cost_data` - The data that might be needed to compute your fitness. If you use
-    /// genetic algorithm to solve a traveling salesman problem, the `cost_data` will typically
-    /// contain your distance matrix.
+    ///   genetic algorithm to solve a traveling salesman problem, the `cost_data` will typically
+    ///   contain your distance matrix.
     ///
     fn fitness(&self, cost_data: &Self::IndividualCost) -> f64;
 }
@@ -53,7 +53,7 @@ pub trait Population<'a> {
     fn fitnesses(
         &'a self,
         cost_data: &'a <<Self as Population<'a>>::Individual as Individual<'a>>::IndividualCost,
-    ) -> Vec<(f64, &Self::Individual)> {
+    ) -> Vec<(f64, &'a Self::Individual)> {
         self.iter()
             .map(|solution| (solution.fitness(cost_data), solution))
             .collect()
@@ -64,7 +64,7 @@ pub trait Population<'a> {
     ///
     /// * `n` - The number of individuals you would like to get
     /// * `cost_data` - The cost data structure your individuals need to compute
-    /// their fitness.
+    ///   their fitness.
     ///
     fn get_n_fittest(
         &'a self,
@@ -100,8 +100,8 @@ pub trait Population<'a> {
     ///
     /// The evolution consists of the following stages:
     /// 1) `crossover` between all 1,...,n indivials. Each individual will not be `crossover`ed
-    /// with itself, but as the crossover is not neccessarily symmetric `indivdual_a.crossover(indivual_b)` as well
-    /// as `individual_b.crossover(individual_a)` will be computed.
+    ///    with itself, but as the crossover is not neccessarily symmetric `indivdual_a.crossover(indivual_b)` as well
+    ///    as `individual_b.crossover(individual_a)` will be computed.
     /// 2) `mutate` is applied to all individuals.
     ///
     /// The difference to the `evolve_individuals` function is that this needs to be implemented in the struct
@@ -117,8 +117,8 @@ pub trait Population<'a> {
     ///
     /// The evolution consists of the following stages:
     /// 1) `crossover` between all 1,...,n indivials. Each individual will not be `crossover`ed
-    /// with itself, but as the crossover is not neccessarily symmetric `indivdual_a.crossover(indivual_b)` as well
-    /// as `individual_b.crossover(individual_a)` will be computed.
+    ///    with itself, but as the crossover is not neccessarily symmetric `indivdual_a.crossover(indivual_b)` as well
+    ///    as `individual_b.crossover(individual_a)` will be computed.
     /// 2) `mutate` is applied to all individuals.
     ///
     /// # Arguments
@@ -131,7 +131,7 @@ pub trait Population<'a> {
             // for all individuals 1 .. n crossover with all other individuals excluding the same individual.
             .iter()
             .enumerate()
-            .map(|(idx, main_individual)| {
+            .flat_map(|(idx, main_individual)| {
                 self.iter()
                     // Skip the individual itself, e.g. don't crossover the individual with itself.
                     .enumerate()
@@ -140,10 +140,44 @@ pub trait Population<'a> {
                         main_individual.crossover(individual).mutate(mutate_prob)
                     })
             })
-            .flatten()
             .chain(self.iter().cloned())
             .collect()
     }
     /// Iterate over the individuals in your population.
     fn iter(&'a self) -> Self::IndividualCollection;
 }
+
+/// The single objective an individual is judged on, e.g. the length of a route. Lower values are
+/// better.
+pub trait Objective {
+    /// The Type of individuals the objective is computed for.
+    type Individual;
+    /// Compute the objective of an individual.
+    ///
+    /// # Arguments
+    ///
+    /// * `individual` - The individual whose objective is computed.
+    ///
+    fn objective(&self, individual: &Self::Individual) -> f64;
+    /// Does an individual keep its objective if it is reversed, e.g. if a route is travelled in
+    /// the opposite direction? Caches use this to share one entry between both directions. By
+    /// default individuals are assumed to depend on their direction.
+    fn is_symmetric(&self) -> bool {
+        false
+    }
+}
+
+/// Several objectives an individual is judged on at once, e.g. the length of a route on several
+/// distance matrices. Lower values are better in every objective.
+pub trait MultiObjective {
+    /// The Type of individuals the objectives are computed for.
+    type Individual;
+    /// Compute all objectives of an individual. Every individual has to get the same number of
+    /// objectives in the same order.
+    ///
+    /// # Arguments
+    ///
+    /// * `individual` - The individual whose objectives are computed.
+    ///
+    fn objectives(&self, individual: &Self::Individual) -> Vec<f64>;
+}
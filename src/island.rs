@@ -0,0 +1,267 @@
+use crate::adaptive::AdaptiveOperators;
+use crate::config::GaConfig;
+use crate::distance_mat::{DistanceMat, DistanceMatError};
+use crate::route::Route;
+use crate::routes::Routes;
+use crossbeam_utils::thread;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Several populations, the islands, that are evolved independently of each other. From time to
+/// time the best route of every island migrates to the next island, so good routes spread while
+/// the islands keep their diversity.
+///
+/// Every island has its own random number generator, so the islands can be evolved in parallel
+/// and a run started with `IslandModel::from_seed` is reproducible independent of how the threads
+/// are scheduled.
+#[derive(Debug, Clone)]
+pub struct IslandModel {
+    /// The populations of the islands.
+    pub islands: Vec<Routes>,
+    /// The random number generator of every island.
+    rngs: Vec<StdRng>,
+    /// The adaptive selection of the mutation and crossover operators of every island, used with
+    /// `GaConfig::adaptive_operators`.
+    operators: Vec<AdaptiveOperators>,
+}
+
+impl IslandModel {
+    /// Create `n_islands` islands with random populations. The seed of every island is derived
+    /// from `master_seed`, so the same master seed always leads to the same run.
+    ///
+    /// # Arguments
+    ///
+    /// * `master_seed` - The seed the seeds of the islands are derived from.
+    /// * `n_islands` - How many islands should be evolved.
+    /// * `population_size` - How many routes every island starts with.
+    /// * `distance_mat` - The distance matrix the routes are created for.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::island::IslandModel;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let island_model = IslandModel::from_seed(42, 4, 3, &distance_matrix);
+    /// assert_eq!(island_model.islands.len(), 4);
+    /// ```
+    pub fn from_seed(
+        master_seed: u64,
+        n_islands: usize,
+        population_size: usize,
+        distance_mat: &DistanceMat,
+    ) -> Self {
+        let mut master_rng = StdRng::seed_from_u64(master_seed);
+        let mut rngs = (0..n_islands)
+            .map(|_| StdRng::seed_from_u64(master_rng.gen()))
+            .collect::<Vec<StdRng>>();
+        IslandModel {
+            islands: rngs
+                .iter_mut()
+                .map(|rng| Routes::random_with(population_size, distance_mat.n_units(), rng))
+                .collect(),
+            operators: vec![AdaptiveOperators::default(); n_islands],
+            rngs,
+        }
+    }
+    /// Evolve all islands in parallel for `generations` generations with `Routes::step`. Every
+    /// `migration_interval` generations the best route of every island is injected into the next
+    /// island, where it replaces the worst route. The last island sends its best route to the
+    /// first one. With a `migration_interval` of 0 the islands never exchange routes.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the fitness of the routes is computed on.
+    /// * `config` - The parameters of the genetic algorithm used on every island.
+    /// * `generations` - For how many generations the islands should be evolved.
+    /// * `migration_interval` - After how many generations the best routes migrate.
+    ///
+    /// # Errors
+    ///
+    /// If a route of an island is not compatible with `distance_mat`, see
+    /// `Routes::check_compatible`. No island is evolved then.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::island::IslandModel;
+    /// use genetic_algo::config::GaConfig;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+    /// let config = GaConfig {
+    ///     population_size: 6,
+    ///     ..GaConfig::default()
+    /// };
+    /// let mut island_model = IslandModel::from_seed(42, 2, 6, &distance_matrix);
+    /// island_model.evolve(&distance_matrix, &config, 10, 5).unwrap();
+    /// println!("{:?}", island_model.best(&distance_matrix));
+    /// ```
+    pub fn evolve(
+        &mut self,
+        distance_mat: &DistanceMat,
+        config: &GaConfig,
+        generations: usize,
+        migration_interval: usize,
+    ) -> Result<(), DistanceMatError> {
+        for island in &self.islands {
+            island.check_compatible(distance_mat)?;
+        }
+        let epoch_length = if migration_interval == 0 {
+            generations
+        } else {
+            migration_interval
+        };
+        let mut remaining_generations = generations;
+        while remaining_generations > 0 {
+            let epoch_generations = epoch_length.min(remaining_generations);
+            thread::scope(|s| {
+                for ((island, rng), operators) in self
+                    .islands
+                    .iter_mut()
+                    .zip(self.rngs.iter_mut())
+                    .zip(self.operators.iter_mut())
+                {
+                    s.spawn(move |_| {
+                        for _ in 0..epoch_generations {
+                            *island =
+                                island.step_with_operators(distance_mat, config, operators, rng);
+                        }
+                    });
+                }
+            })
+            .unwrap();
+            remaining_generations -= epoch_generations;
+            if migration_interval > 0 && epoch_generations == migration_interval {
+                self.migrate(distance_mat);
+            }
+        }
+        Ok(())
+    }
+    /// Inject the best route of every island into the next island.
+    fn migrate(&mut self, distance_mat: &DistanceMat) {
+        let migrants = self
+            .islands
+            .iter()
+            .map(|island| island.best(distance_mat).cloned())
+            .collect::<Vec<Option<Route>>>();
+        let n_islands = self.islands.len();
+        for (idx, migrant) in migrants.into_iter().enumerate() {
+            if let Some(migrant) = migrant {
+                self.islands[(idx + 1) % n_islands].inject(migrant, distance_mat);
+            }
+        }
+    }
+    /// Get the shortest route over all islands. If several routes are the shortest, the first one
+    /// in lexicographic order is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the length of the routes is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::island::IslandModel;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let island_model = IslandModel::from_seed(42, 2, 3, &distance_matrix);
+    /// assert!(island_model.best(&distance_matrix).is_some());
+    /// ```
+    pub fn best(&self, distance_mat: &DistanceMat) -> Option<&Route> {
+        self.islands
+            .iter()
+            .filter_map(|island| island.best(distance_mat))
+            .min_by(|route_a, route_b| {
+                route_a
+                    .distance(distance_mat)
+                    .partial_cmp(&route_b.distance(distance_mat))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| route_a.cmp(route_b))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen_traits::Population;
+    use crate::test_utils::circle_dist_mat;
+    mod test_from_seed {
+        use super::*;
+        #[test]
+        fn same_seed_same_islands() {
+            let distance_mat = circle_dist_mat(8);
+            let island_model = IslandModel::from_seed(7, 3, 10, &distance_mat);
+            assert_eq!(
+                island_model.islands,
+                IslandModel::from_seed(7, 3, 10, &distance_mat).islands
+            );
+            assert!(island_model
+                .islands
+                .iter()
+                .all(|island| island.ages().len() == 10));
+        }
+        #[test]
+        fn islands_differ() {
+            let island_model = IslandModel::from_seed(7, 2, 10, &circle_dist_mat(8));
+            assert_ne!(island_model.islands[0], island_model.islands[1]);
+        }
+    }
+    mod test_evolve {
+        use super::*;
+        #[test]
+        fn reproducible_in_parallel() {
+            let distance_mat = circle_dist_mat(10);
+            let config = GaConfig {
+                population_size: 10,
+                ..GaConfig::default()
+            };
+            let run = || {
+                let mut island_model = IslandModel::from_seed(123, 4, 10, &distance_mat);
+                island_model.evolve(&distance_mat, &config, 12, 5).unwrap();
+                island_model
+            };
+            let (first_run, second_run) = (run(), run());
+            assert_eq!(first_run.islands, second_run.islands);
+            assert_eq!(
+                first_run.best(&distance_mat),
+                second_run.best(&distance_mat)
+            );
+        }
+        #[test]
+        fn migration_spreads_best_route() {
+            let distance_mat = circle_dist_mat(10);
+            let config = GaConfig {
+                population_size: 10,
+                ..GaConfig::default()
+            };
+            // Both runs evolve the same islands, but only one of them lets the routes migrate.
+            let mut without_migration = IslandModel::from_seed(1, 3, 10, &distance_mat);
+            without_migration
+                .evolve(&distance_mat, &config, 1, 0)
+                .unwrap();
+            let mut with_migration = IslandModel::from_seed(1, 3, 10, &distance_mat);
+            with_migration.evolve(&distance_mat, &config, 1, 1).unwrap();
+            for idx in 0..3 {
+                let migrant = without_migration.islands[(idx + 2) % 3]
+                    .best(&distance_mat)
+                    .unwrap();
+                assert!(with_migration.islands[idx]
+                    .iter()
+                    .any(|route| route == migrant));
+            }
+        }
+        #[test]
+        fn incompatible_matrix() {
+            let mut island_model = IslandModel::from_seed(3, 2, 6, &circle_dist_mat(8));
+            let before = island_model.islands.clone();
+            assert!(island_model
+                .evolve(&circle_dist_mat(6), &GaConfig::default(), 3, 1)
+                .is_err());
+            assert_eq!(island_model.islands, before);
+        }
+    }
+}
@@ -4,10 +4,18 @@
 //!
 //! This crates contains utitlities to run genetic algorithms and solve Traveling Salesman Problems.
 
+/// The `adaptive`-module contains the `AdaptiveOperators`-class that learns which mutation and crossover
+/// operators improve the routes of an instance.
+pub mod adaptive;
+/// The `config`-module contains the `GaConfig`-class that holds the parameters of a genetic algorithm run.
+pub mod config;
 /// Represent a distance Matrix as a Vec<Vec<f64>>.
 pub mod distance_mat;
 /// Traits used for abstraction of gen-algo
 pub mod gen_traits;
+/// The `island`-module contains the `IslandModel`-class that evolves several populations in parallel
+/// and lets their best routes migrate between them.
+pub mod island;
 /// The `route`-module contains the `Route`-class, the individual element of the TSP that implements
 /// important methods like `crossover` or `mutate`.
 pub mod route;
@@ -17,8 +25,13 @@ pub mod routes;
 /// The `subsequence`-module contains a helper function, `Subsequence` that gives you functionality to select elements
 /// before, in and after a subsequence of a Vector. It is used extensively in the `ordered_crossover`-function.
 mod subsequence;
+/// The `test_support`-module contains helpers to test your own operators, e.g. in property tests. It is
+/// only available with the `test-support` feature.
+#[cfg(feature = "test-support")]
+pub mod test_support;
 /// the `test-utils`-module contains utitlities for testing and include for example the construction of test-data
 /// or the comparison of specializied objects (like permutations).
+#[cfg(test)]
 mod test_utils;
 /// The `utils`-module contains utility that are used throughout the rest of the code base. The underlying `ordered_crossover`-
 /// function is implemented here.
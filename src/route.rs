@@ -1,12 +1,182 @@
 use crate::distance_mat::DistanceMat;
 use crate::gen_traits::Individual;
 use crate::subsequence::Subsequence;
-use crate::utils::{change_order, get_random_elem_from_range, ordered_crossover, remove_elem};
-use rand::seq::SliceRandom;
+use crate::utils::{
+    change_order, ordered_crossover, random_index_with, random_unit_with, remove_elem,
+    sample_indexes_with,
+};
+use rand::{Rng, RngCore};
 use std::cmp::max;
+use std::collections::VecDeque;
+
+/// The longest segment `Route::or_opt` moves.
+const OR_OPT_MAX_SEGMENT_LENGTH: usize = 3;
+/// By how much `Route::checked_apply` allows a move to lengthen the route, to ignore rounding.
+const CHECKED_APPLY_TOLERANCE: f64 = 1e-9;
+
+/// The errors that can occur while reading routes with `Routes::from_tour_lines` or
+/// `Route::from_one_indexed_str`.
+#[derive(Debug)]
+pub enum TourParseError {
+    /// Reading from the reader failed.
+    Io(std::io::Error),
+    /// A line contains something that is not a node between 1 and the number of nodes.
+    InvalidNode {
+        /// The number of the line, starting at 1.
+        line: usize,
+        /// The text that could not be read as node.
+        token: String,
+    },
+    /// A line does not visit every node exactly once.
+    NotAPermutation {
+        /// The number of the line, starting at 1.
+        line: usize,
+    },
+}
+
+impl std::fmt::Display for TourParseError {
+    /// Describe the error, including the offending line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::TourParseError;
+    ///
+    /// let error = TourParseError::NotAPermutation { line: 3 };
+    /// assert_eq!(error.to_string(), "line 3 does not visit every node exactly once");
+    /// ```
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TourParseError::Io(error) => write!(f, "could not read the tours: {}", error),
+            TourParseError::InvalidNode { line, token } => {
+                write!(f, "line {} contains the invalid node {:?}", line, token)
+            }
+            TourParseError::NotAPermutation { line } => {
+                write!(f, "line {} does not visit every node exactly once", line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TourParseError {}
+
+impl TourParseError {
+    /// Report the error on `line` instead of the line it was found on.
+    pub(crate) fn on_line(self, line: usize) -> Self {
+        match self {
+            TourParseError::Io(error) => TourParseError::Io(error),
+            TourParseError::InvalidNode { token, .. } => {
+                TourParseError::InvalidNode { line, token }
+            }
+            TourParseError::NotAPermutation { .. } => TourParseError::NotAPermutation { line },
+        }
+    }
+}
+
+/// How a local search like `Route::two_opt_with_strategy` chooses the move it applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImprovementStrategy {
+    /// Apply every improving move as soon as it is found. Each pass is cheap, but the route may
+    /// end in a worse local optimum.
+    FirstImprovement,
+    /// Check all moves and only apply the one that improves the route the most. Each pass scans
+    /// every move, which is slower, but usually leads to slightly shorter routes.
+    BestImprovement,
+}
+
+/// The mutations `Route::maybe_mutate` can apply to a route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MutationKind {
+    /// Move one node in front of another node, as `mutate_with` does.
+    Insertion,
+    /// Swap the nodes at two different positions.
+    Swap,
+    /// Reverse the order of the nodes between two different positions.
+    Inversion,
+    /// Reconnect four segments of the route, as `double_bridge` does.
+    DoubleBridge,
+}
+
+/// An Or-opt move applied by `Route::or_opt_traced`: the `chain_len` consecutive nodes starting
+/// at position `from` were removed and inserted again so that they start at position `to` of the
+/// moved route.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrOptMove {
+    /// The position of the first moved node before the move.
+    pub from: usize,
+    /// The position of the first moved node after the move.
+    pub to: usize,
+    /// How many consecutive nodes were moved.
+    pub chain_len: usize,
+    /// By how much the move changed the length of the round-trip, negative as it was improving.
+    pub delta: f64,
+}
+
+/// A crossover operator that combines two parent routes into a child, e.g. to plug a custom
+/// operator into the evolution with `GaConfig::crossover`. The operator is shared between the
+/// threads of an `IslandModel`, so it has to be `Send` and `Sync`.
+pub trait CrossoverOp: std::fmt::Debug + Send + Sync {
+    /// Create a child from the parents `a` and `b`. The child has to visit the same nodes as
+    /// the parents.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The first parent.
+    /// * `b` - The second parent.
+    /// * `rng` - The random number generator used by the operator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::{CrossoverOp, OrderedCrossover, Route};
+    ///
+    /// let child = OrderedCrossover.apply(
+    ///     &Route::new(vec![0,1,2,3]),
+    ///     &Route::new(vec![3,1,0,2]),
+    ///     &mut rand::thread_rng(),
+    /// );
+    /// assert_eq!(child.indexes.len(), 4);
+    /// ```
+    fn apply(&self, a: &Route, b: &Route, rng: &mut dyn RngCore) -> Route;
+}
+
+/// The ordered crossover: a random subsequence is taken from the first parent and the remaining
+/// nodes are filled in in the order of the second parent, like the first child of
+/// `Route::crossover_pair`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderedCrossover;
+
+// Take the subsequence from `a` and the order of the remaining nodes from `b`.
+impl CrossoverOp for OrderedCrossover {
+    fn apply(&self, a: &Route, b: &Route, mut rng: &mut dyn RngCore) -> Route {
+        ordered_crossover(
+            a,
+            b,
+            Subsequence::random_subsequence_with(a.indexes.len(), &mut rng),
+        )
+    }
+}
+
+/// The ordered crossover that keeps `depot` at the first position of the child, see
+/// `Route::crossover_fixed_depot`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedDepotCrossover {
+    /// The node every child starts with.
+    pub depot: usize,
+}
+
+// Crossover like `OrderedCrossover` and rotate the child to the depot.
+impl CrossoverOp for FixedDepotCrossover {
+    fn apply(&self, a: &Route, b: &Route, mut rng: &mut dyn RngCore) -> Route {
+        a.crossover_fixed_depot(b, self.depot, &mut rng)
+    }
+}
 
 /// The `Route` is an invidiual in the traveling salemens problem that is a valid route.
-#[derive(Debug, PartialEq, Clone, Eq, Hash)]
+///
+/// Routes are ordered lexicographically by the order in which they visit the nodes. This ordering
+/// is used to break ties between routes of equal length, so that selecting routes is reproducible.
+#[derive(Debug, PartialEq, Clone, Eq, Hash, PartialOrd, Ord)]
 pub struct Route {
     /// The order in which the nodes should be visited.
     pub indexes: Vec<usize>,
@@ -28,106 +198,1156 @@ impl Route {
     pub fn new(indexes: Vec<usize>) -> Self {
         Self { indexes }
     }
-}
-impl<'a> Individual<'a> for Route {
-    // The Distance matrix is needed by the individuals to compute their fitness on.
-    type IndividualCost = DistanceMat;
-    /// Randomly changes the order of two nodes in the route
+    /// Complete a partial route with the nearest neighbor heuristic: the nodes of `partial` are
+    /// visited first, in their order, and afterwards the closest node that was not visited yet is
+    /// always visited next. If several nodes are the closest, the one with the lowest index is
+    /// visited. An empty `partial` route starts at node 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `partial` - The nodes that are visited first.
+    /// * `distance_mat` - The distance matrix whose nodes the route should visit.
+    ///
+    /// # Panics
+    ///
+    /// If `partial` contains a node twice or a node that is not part of `distance_mat`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (5.0, 0.0)]);
+    /// assert_eq!(Route::complete_greedily(&[3, 0], &distance_matrix), Route::new(vec![3, 0, 1, 2]));
+    /// ```
+    pub fn complete_greedily(partial: &[usize], distance_mat: &DistanceMat) -> Route {
+        let n_nodes = distance_mat.n_units();
+        let mut visited = vec![false; n_nodes];
+        for node in partial {
+            assert!(
+                *node < n_nodes && !visited[*node],
+                "The partial route {:?} visits a node twice or a node that is not one of the {} nodes.",
+                partial,
+                n_nodes
+            );
+            visited[*node] = true;
+        }
+        let mut route = partial.to_vec();
+        if route.is_empty() && n_nodes > 0 {
+            visited[0] = true;
+            route.push(0);
+        }
+        while route.len() < n_nodes {
+            let last = route[route.len() - 1];
+            let nearest = (0..n_nodes)
+                .filter(|node| !visited[*node])
+                .min_by(|a, b| {
+                    distance_mat
+                        .distance_between(last, *a)
+                        .partial_cmp(&distance_mat.distance_between(last, *b))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .unwrap();
+            visited[nearest] = true;
+            route.push(nearest);
+        }
+        Route::new(route)
+    }
+    /// Read a route whose nodes are numbered from 1 to `n_nodes`, e.g. as written by
+    /// `to_one_indexed_string`, and convert it to the nodes 0..n used by the crate. The nodes
+    /// are separated by whitespace or commas.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The nodes of the route, starting at 1.
+    /// * `n_nodes` - The number of nodes the route has to visit.
+    ///
+    /// # Errors
+    ///
+    /// `TourParseError::InvalidNode` if a node is not a number between 1 and `n_nodes`, or
+    /// `TourParseError::NotAPermutation` if not every node is visited exactly once. As `s` is
+    /// read as a single line, the errors are reported on line 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    ///
+    /// assert_eq!(Route::from_one_indexed_str("3 1 2", 3).unwrap(), Route::new(vec![2,0,1]));
+    /// assert!(Route::from_one_indexed_str("0 1 2", 3).is_err());
+    /// ```
+    pub fn from_one_indexed_str(s: &str, n_nodes: usize) -> Result<Route, TourParseError> {
+        let indexes = s
+            .split(|character: char| character.is_whitespace() || character == ',')
+            .filter(|token| !token.is_empty())
+            .map(|token| match token.parse::<usize>() {
+                Ok(node) if (1..=n_nodes).contains(&node) => Ok(node - 1),
+                _ => Err(TourParseError::InvalidNode {
+                    line: 1,
+                    token: token.to_string(),
+                }),
+            })
+            .collect::<Result<Vec<usize>, TourParseError>>()?;
+        let mut visited = vec![false; n_nodes];
+        for node in indexes.iter() {
+            visited[*node] = true;
+        }
+        if indexes.len() != n_nodes || visited.iter().any(|visited| !visited) {
+            return Err(TourParseError::NotAPermutation { line: 1 });
+        }
+        Ok(Route::new(indexes))
+    }
+    /// Write the route with the nodes numbered from 1 instead of 0, separated by spaces, e.g. to
+    /// share it with people who count the nodes from 1. It can be read back with
+    /// `from_one_indexed_str`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    ///
+    /// assert_eq!(Route::new(vec![2,0,1]).to_one_indexed_string(), "3 1 2");
+    /// ```
+    pub fn to_one_indexed_string(&self) -> String {
+        self.indexes
+            .iter()
+            .map(|node| (node + 1).to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+    /// Get the nodes of the route in the order in which they are visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    ///
+    /// assert_eq!(Route::new(vec![2,0,1]).nodes(), &[2,0,1]);
+    /// ```
+    pub fn nodes(&self) -> &[usize] {
+        &self.indexes
+    }
+    /// Get the position at which a node is visited in the route.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node that should be looked up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    ///
+    /// assert_eq!(Route::new(vec![2,0,1]).position_of(0), Some(1));
+    /// ```
+    pub fn position_of(&self, node: usize) -> Option<usize> {
+        self.indexes
+            .iter()
+            .position(|visited_node| *visited_node == node)
+    }
+    /// Get the node that is visited after `node`. The node after the last node is the first
+    /// node, as the route is a round-trip.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node whose successor should be looked up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    ///
+    /// assert_eq!(Route::new(vec![2,0,1]).successor(1), Some(2));
+    /// ```
+    pub fn successor(&self, node: usize) -> Option<usize> {
+        self.position_of(node)
+            .map(|position| self.indexes[(position + 1) % self.indexes.len()])
+    }
+    /// Get the node that is visited before `node`. The node before the first node is the last
+    /// node, as the route is a round-trip.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node whose predecessor should be looked up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    ///
+    /// assert_eq!(Route::new(vec![2,0,1]).predecessor(2), Some(1));
+    /// ```
+    pub fn predecessor(&self, node: usize) -> Option<usize> {
+        self.position_of(node)
+            .map(|position| self.indexes[(position + self.indexes.len() - 1) % self.indexes.len()])
+    }
+    /// Get the position of every node in the route, e.g. the inverse permutation of the route.
+    /// The position of node `i` is the `i`-th element. Use this instead of `position_of`
+    /// if many nodes need to be looked up. The route needs to be a permutation of `0..n`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    ///
+    /// assert_eq!(Route::new(vec![2,0,1]).positions(), vec![1,2,0]);
+    /// ```
+    pub fn positions(&self) -> Vec<usize> {
+        let mut positions = vec![0; self.indexes.len()];
+        for (position, node) in self.indexes.iter().enumerate() {
+            positions[*node] = position;
+        }
+        positions
+    }
+    /// Count the pairs of nodes that are visited in a different order by both routes, e.g. the
+    /// Kendall tau distance of the two sequences. Identical routes have a distance of 0 and
+    /// reversed routes the maximal distance of `n * (n - 1) / 2`. In contrast to comparing edges,
+    /// this measures how differently the routes order the nodes. Both routes need to be
+    /// permutations of `0..n`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The route this route is compared with.
+    ///
+    /// # Panics
+    ///
+    /// If the routes have a different length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    ///
+    /// assert_eq!(Route::new(vec![0,1,2,3]).kendall_tau(&Route::new(vec![1,0,3,2])), 2);
+    /// ```
+    pub fn kendall_tau(&self, other: &Route) -> usize {
+        assert_eq!(
+            self.indexes.len(),
+            other.indexes.len(),
+            "Only routes of the same length can be compared."
+        );
+        // Where `other` visits the nodes in the order in which `self` visits them. Every pair
+        // that is out of order in this sequence is visited in a different order.
+        let other_positions = other.positions();
+        let positions = self
+            .indexes
+            .iter()
+            .map(|node| other_positions[*node])
+            .collect::<Vec<usize>>();
+        positions
+            .iter()
+            .enumerate()
+            .map(|(idx, position)| {
+                positions[(idx + 1)..]
+                    .iter()
+                    .filter(|later_position| *later_position < position)
+                    .count()
+            })
+            .sum()
+    }
+    /// Compute the length of the round-trip, e.g. `DistanceMat::get_distance` of the nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the length is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(Route::new(vec![0,1,2]).distance(&distance_matrix), 6.0);
+    /// ```
+    pub fn distance(&self, distance_mat: &DistanceMat) -> f64 {
+        distance_mat.route_distance(self)
+    }
+    /// Compute by which fraction the route is longer than a lower bound of the optimal route,
+    /// e.g. `DistanceMat::mst_lower_bound`. A gap of 0.12 means that the route is 12% longer than
+    /// the bound. If the bound is 0, the gap is 0 for a route of length 0 and infinite otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the length of the route is computed on.
+    /// * `bound` - The lower bound the route is compared with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let gap = Route::new(vec![0,1,2]).optimality_gap(&distance_matrix, distance_matrix.mst_lower_bound());
+    /// println!("{:.0}% above the MST bound.", 100.0 * gap);
+    /// ```
+    pub fn optimality_gap(&self, distance_mat: &DistanceMat, bound: f64) -> f64 {
+        let distance = self.distance(distance_mat);
+        if bound == 0.0 {
+            return if distance == 0.0 { 0.0 } else { f64::INFINITY };
+        }
+        (distance - bound) / bound
+    }
+    /// Replace the route by the result `new` of a local-search move and check in debug builds
+    /// that the move did not lengthen the route, e.g. to catch bugs in a custom local search while
+    /// developing it. Release builds only return `new`, without computing any distances.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the length of the routes is computed on.
+    /// * `new` - The route after the move.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, if `new` is longer than the route.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+    /// let route = Route::new(vec![0,2,1,3]);
+    /// let improved_route = route.checked_apply(&distance_matrix, route.two_opt(&distance_matrix));
+    /// ```
+    pub fn checked_apply(&self, distance_mat: &DistanceMat, new: Route) -> Route {
+        if cfg!(debug_assertions) {
+            let (old_distance, new_distance) =
+                (self.distance(distance_mat), new.distance(distance_mat));
+            assert!(
+                new_distance <= old_distance + CHECKED_APPLY_TOLERANCE,
+                "The move from {:?} to {:?} lengthens the route from {} to {}.",
+                self.indexes,
+                new.indexes,
+                old_distance,
+                new_distance
+            );
+        }
+        new
+    }
+    /// Find the longest edge of the round-trip, including the edge from the last node back to the
+    /// first node. Returns the node the edge starts at, the node it ends at and its length.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the length of the edges is computed on.
+    ///
+    /// # Panics
+    ///
+    /// If the route is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(Route::new(vec![0,1,2]).longest_edge(&distance_matrix), (1, 2, 3.0));
+    /// ```
+    pub fn longest_edge(&self, distance_mat: &DistanceMat) -> (usize, usize, f64) {
+        (0..self.indexes.len())
+            .map(|position| {
+                let from = self.indexes[position];
+                let to = self.indexes[(position + 1) % self.indexes.len()];
+                (from, to, distance_mat.distance_between(from, to))
+            })
+            .fold(
+                None,
+                |longest: Option<(usize, usize, f64)>, edge| match longest {
+                    Some(longest) if longest.2 >= edge.2 => Some(longest),
+                    _ => Some(edge),
+                },
+            )
+            .expect("An empty route has no edges.")
+    }
+    /// Randomly move one node of the route in front of another node, sampling from the random
+    /// number generator provided. This is the mutation `Individual::mutate` applies.
     ///
     /// # Arguments
     ///
     /// * `prob` - The probability with which the indexes will be changed
+    /// * `rng` - The random number generator used for the mutation.
     ///
     /// # Examples
     ///
     /// ```
     /// use genetic_algo::route::Route;
-    /// use genetic_algo::gen_traits::Individual;
     ///
-    /// let my_individual = Route::from(Route::new(vec![0,1,2]));
-    /// let my_mutated_indiviual =  my_individual.mutate(1.0);
+    /// let my_individual = Route::new(vec![0,1,2]);
+    /// let my_mutated_indiviual = my_individual.mutate_with(1.0, &mut rand::thread_rng());
     /// ```
-    fn mutate(self, prob: f32) -> Self {
+    pub fn mutate_with(self, prob: f32, rng: &mut impl Rng) -> Route {
         Route {
-            indexes: if get_random_elem_from_range(0.0..1.0) > prob {
+            indexes: if random_unit_with(rng) > f64::from(prob) {
                 // With probabilty (1-prop) don't do any mutation.
                 self.indexes
             } else {
                 // else mutation is applied.
                 // To do so first sample an element to put another element in front of.
-                let put_before_idx: usize = get_random_elem_from_range(0..(self.indexes.len() - 1));
+                let put_before_idx: usize = random_index_with(0..(self.indexes.len() - 1), rng);
+                // Sample the element that should be put before `put_before_idx`. Should not be
+                // the `put_before_idx` itself.
+                let move_candidates = remove_elem(
+                    remove_elem(
+                        (0..(self.indexes.len() - 1)).collect::<Vec<usize>>(),
+                        put_before_idx,
+                    ),
+                    max(put_before_idx, 1) - 1,
+                );
                 change_order(
                     &self.indexes,
                     put_before_idx,
-                    // Sample the element that should be put before `put_before_idx`. Should not be
-                    // the `put_before_idx` itself.
-                    *remove_elem(
-                        remove_elem(
-                            (0..(self.indexes.len() - 1)).collect::<Vec<usize>>(),
-                            put_before_idx,
-                        ),
-                        max(put_before_idx, 1) - 1,
-                    )
-                    .choose(&mut rand::thread_rng())
-                    .unwrap_or(&((put_before_idx + 1) % self.indexes.len())),
+                    *move_candidates
+                        .get(random_index_with(0..move_candidates.len(), rng))
+                        .unwrap_or(&((put_before_idx + 1) % self.indexes.len())),
                 )
             },
         }
     }
-    /// Crossover this invidual with another individual to create a new individual. Currently
-    /// uses the `ordered_crossover` algorithm.
+    /// With a probability of `rate` apply the mutation `kind` to the route, otherwise return an
+    /// unchanged copy of it. Routes with less than two nodes cannot be mutated and are always
+    /// returned unchanged. On routes with two nodes an insertion swaps the two nodes.
     ///
     /// # Arguments
     ///
-    /// * `other` - The other individual you would like to crossover with this individual.
+    /// * `rate` - The probability with which the route is mutated.
+    /// * `kind` - The mutation that is applied.
+    /// * `rng` - The random number generator used for the mutation.
     ///
     /// # Examples
     ///
     /// ```
-    /// use genetic_algo::route::Route;
-    /// use genetic_algo::gen_traits::Individual;
+    /// use genetic_algo::route::{MutationKind, Route};
     ///
-    /// let my_individual = Route::from(Route::new(vec![0,1,2]));
-    /// let my_individual = my_individual.crossover(
-    ///     &Route::from(Route::new(vec![1,0,2]))
-    /// );
+    /// let my_individual = Route::new(vec![0,1,2,3]);
+    /// let my_mutated_individual = my_individual.maybe_mutate(0.5, MutationKind::Swap, &mut rand::thread_rng());
+    /// assert_eq!(my_individual.maybe_mutate(0.0, MutationKind::Swap, &mut rand::thread_rng()), my_individual);
     /// ```
-    fn crossover(&self, other: &Route) -> Self {
-        ordered_crossover(
-            self,
-            other,
-            Subsequence::random_subsequence(self.indexes.len()),
-        )
+    pub fn maybe_mutate(&self, rate: f64, kind: MutationKind, rng: &mut impl Rng) -> Route {
+        let n_nodes = self.indexes.len();
+        if n_nodes < 2 || random_unit_with(rng) >= rate {
+            return self.clone();
+        }
+        match kind {
+            // Moving one of two nodes in front of the other one swaps them, `mutate_with` cannot
+            // handle routes this short.
+            MutationKind::Insertion if n_nodes == 2 => Route {
+                indexes: vec![self.indexes[1], self.indexes[0]],
+            },
+            MutationKind::Insertion => self.clone().mutate_with(1.0, rng),
+            MutationKind::Swap | MutationKind::Inversion => {
+                let positions = sample_indexes_with(n_nodes, 2, rng);
+                let (first, second) = (
+                    positions[0].min(positions[1]),
+                    positions[0].max(positions[1]),
+                );
+                let mut indexes = self.indexes.clone();
+                if kind == MutationKind::Swap {
+                    indexes.swap(first, second);
+                } else {
+                    indexes[first..=second].reverse();
+                }
+                Route { indexes }
+            }
+            MutationKind::DoubleBridge => self.double_bridge(rng),
+        }
     }
-    /// Compute how much distance the individual implies with its order of nodes
-    /// and the distance matrix.
+    /// Get the lengths of all routes one move of the mutation `kind` away from this route, e.g.
+    /// to analyse how rugged the fitness landscape around it is. Every move is counted, even if
+    /// two moves lead to the same route, so a route with `n` nodes has
+    /// * `n * (n - 1)` neighbors for `MutationKind::Insertion`, one for every node and every
+    ///   other position it can be moved to,
+    /// * `n * (n - 1) / 2` neighbors for `MutationKind::Swap` and `MutationKind::Inversion`, one
+    ///   for every pair of positions,
+    /// * `(n - 1) * (n - 2) * (n - 3) / 6` neighbors for `MutationKind::DoubleBridge`, one for
+    ///   every choice of three cut points.
     ///
     /// # Arguments
     ///
-    /// * `distance_matrix` - Distance Matrix that determines the length of the proposed
-    /// route
+    /// * `distance_mat` - The distance matrix the lengths of the neighbors are computed on.
+    /// * `kind` - The mutation that defines the neighborhood.
     ///
     /// # Examples
     ///
     /// ```
-    /// use genetic_algo::route::Route;
     /// use genetic_algo::distance_mat::DistanceMat;
-    /// use genetic_algo::gen_traits::Individual;
+    /// use genetic_algo::route::{MutationKind, Route};
     ///
-    /// let my_individual = Route::from(Route::new(vec![0,1,2]));
-    /// println!("Fitness of your individual: {}", my_individual.fitness(
-    ///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]))
-    /// )
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let my_individual = Route::new(vec![0,1,2]);
+    /// assert_eq!(my_individual.neighborhood_lengths(&distance_matrix, MutationKind::Swap), vec![6.0, 6.0, 6.0]);
     /// ```
-    ///
-    fn fitness(&self, distance_mat: &DistanceMat) -> f64 {
-        -distance_mat.get_distance(&self.indexes[..])
+    pub fn neighborhood_lengths(&self, distance_mat: &DistanceMat, kind: MutationKind) -> Vec<f64> {
+        let n_nodes = self.indexes.len();
+        // The neighbors are built one after another in the same buffer and only their lengths
+        // are kept.
+        let mut neighbor = self.indexes.clone();
+        let mut lengths = Vec::new();
+        match kind {
+            MutationKind::Insertion => {
+                for from in 0..n_nodes {
+                    for to in (0..n_nodes).filter(|to| *to != from) {
+                        neighbor.copy_from_slice(&self.indexes);
+                        let node = neighbor.remove(from);
+                        neighbor.insert(to, node);
+                        lengths.push(distance_mat.get_distance(&neighbor));
+                    }
+                }
+            }
+            MutationKind::Swap | MutationKind::Inversion => {
+                for first in 0..n_nodes {
+                    for second in (first + 1)..n_nodes {
+                        neighbor.copy_from_slice(&self.indexes);
+                        if kind == MutationKind::Swap {
+                            neighbor.swap(first, second);
+                        } else {
+                            neighbor[first..=second].reverse();
+                        }
+                        lengths.push(distance_mat.get_distance(&neighbor));
+                    }
+                }
+            }
+            MutationKind::DoubleBridge => {
+                for first_cut in 1..n_nodes {
+                    for second_cut in (first_cut + 1)..n_nodes {
+                        for third_cut in (second_cut + 1)..n_nodes {
+                            neighbor.clear();
+                            neighbor.extend_from_slice(&self.indexes[..first_cut]);
+                            neighbor.extend_from_slice(&self.indexes[second_cut..third_cut]);
+                            neighbor.extend_from_slice(&self.indexes[first_cut..second_cut]);
+                            neighbor.extend_from_slice(&self.indexes[third_cut..]);
+                            lengths.push(distance_mat.get_distance(&neighbor));
+                        }
+                    }
+                }
+            }
+        }
+        lengths
+    }
+    /// Mutate every position of the route independently: with a probability of `rate` the node at
+    /// a position is swapped with the node at another, randomly chosen position. In contrast to
+    /// `mutate_with`, which changes at most one node, `rate` controls how much the route changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate` - The probability with which each position takes part in a swap.
+    /// * `rng` - The random number generator used for the mutation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    ///
+    /// let my_individual = Route::new(vec![0,1,2,3,4]);
+    /// let my_mutated_individual = my_individual.mutate_each_gene(0.2, &mut rand::thread_rng());
+    /// ```
+    pub fn mutate_each_gene(&self, rate: f64, rng: &mut impl Rng) -> Route {
+        let mut indexes = self.indexes.clone();
+        let n_nodes = indexes.len();
+        if n_nodes < 2 {
+            return Route { indexes };
+        }
+        for position in 0..n_nodes {
+            if random_unit_with(rng) < rate {
+                // Sample from all other positions by skipping over the position itself.
+                let other_position = random_index_with(0..(n_nodes - 1), rng);
+                indexes.swap(
+                    position,
+                    other_position + (other_position >= position) as usize,
+                );
+            }
+        }
+        Route { indexes }
+    }
+    /// Crossover this route with another route and return both children that result from
+    /// the same subsequence. The first child takes the subsequence from `self` and fills in the
+    /// remaining nodes in the order of `other`, the second child does the opposite.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other route you would like to crossover with this route.
+    /// * `rng` - The random number generator used to sample the subsequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    ///
+    /// let my_individual = Route::new(vec![0,1,2,3]);
+    /// let (child_a, child_b) = my_individual.crossover_pair(
+    ///     &Route::new(vec![3,1,0,2]),
+    ///     &mut rand::thread_rng(),
+    /// );
+    /// ```
+    pub fn crossover_pair(&self, other: &Route, rng: &mut impl Rng) -> (Route, Route) {
+        let subsequence = Subsequence::random_subsequence_with(self.indexes.len(), rng);
+        (
+            ordered_crossover(self, other, subsequence.clone()),
+            ordered_crossover(other, self, subsequence),
+        )
+    }
+    /// Crossover this route with another route like `crossover_pair`, but keep the `depot` at
+    /// the first position of the child, e.g. for a TSP whose tour has to start at a fixed city.
+    /// The child is rotated until it starts at the depot, which does not change its round-trip.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other route you would like to crossover with this route.
+    /// * `depot` - The node the child has to start with.
+    /// * `rng` - The random number generator used to sample the subsequence.
+    ///
+    /// # Panics
+    ///
+    /// If the `depot` is not part of the routes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    ///
+    /// let my_individual = Route::new(vec![0,1,2,3]);
+    /// let child = my_individual.crossover_fixed_depot(
+    ///     &Route::new(vec![0,3,1,2]),
+    ///     0,
+    ///     &mut rand::thread_rng(),
+    /// );
+    /// assert_eq!(child.indexes[0], 0);
+    /// ```
+    pub fn crossover_fixed_depot(&self, other: &Route, depot: usize, rng: &mut impl Rng) -> Route {
+        let subsequence = Subsequence::random_subsequence_with(self.indexes.len(), rng);
+        let mut child = ordered_crossover(self, other, subsequence);
+        let depot_position = child
+            .position_of(depot)
+            .unwrap_or_else(|| panic!("The depot {} is not part of the route.", depot));
+        child.indexes.rotate_left(depot_position);
+        child
+    }
+    /// Perturb the route with a double-bridge move. The route is cut into four segments `A`, `B`,
+    /// `C` and `D` which are then reconnected in the order `A-C-B-D`. The move cannot be undone by
+    /// a single 2-opt move and is therefore typically used as the "kick" between local search runs.
+    /// Routes with less than four nodes cannot be cut into four segments and are returned unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator used to sample the cut points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    ///
+    /// let my_individual = Route::new(vec![0,1,2,3,4,5,6,7]);
+    /// let my_perturbed_individual = my_individual.double_bridge(&mut rand::thread_rng());
+    /// ```
+    pub fn double_bridge(&self, rng: &mut impl Rng) -> Route {
+        if self.indexes.len() < 4 {
+            return self.clone();
+        }
+        // Sample three distinct cut points in 1..n, such that all four segments are non-empty.
+        let mut cuts = sample_indexes_with(self.indexes.len() - 1, 3, rng)
+            .into_iter()
+            .map(|cut| cut + 1)
+            .collect::<Vec<usize>>();
+        cuts.sort_unstable();
+        self.reconnect_segments(cuts[0], cuts[1], cuts[2])
+    }
+    /// Improve the route with the 2-opt local search. Every pair of edges `(a, b)` and `(c, d)`
+    /// is checked and replaced by `(a, c)` and `(b, d)` (reversing the nodes in between) as
+    /// soon as that shortens the round-trip. The search stops once no improving move is left.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the length of the route is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let improved_route = Route::new(vec![0,1,2]).two_opt(&distance_matrix);
+    /// ```
+    pub fn two_opt(&self, distance_mat: &DistanceMat) -> Route {
+        self.two_opt_with_strategy(distance_mat, ImprovementStrategy::FirstImprovement)
+    }
+    /// Improve the route with the 2-opt local search like `two_opt`, but choose whether the first
+    /// or the best improving move is applied. `ImprovementStrategy::FirstImprovement` is typically
+    /// faster, as improving moves are applied while the moves are scanned, while
+    /// `ImprovementStrategy::BestImprovement` needs a full scan per move but tends to find
+    /// slightly shorter routes. Both stop once no improving move is left.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the length of the route is computed on.
+    /// * `strategy` - Which of the improving moves is applied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::{ImprovementStrategy, Route};
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+    /// let improved_route = Route::new(vec![0,2,1,3])
+    ///     .two_opt_with_strategy(&distance_matrix, ImprovementStrategy::BestImprovement);
+    /// assert_eq!(distance_matrix.get_distance(&improved_route.indexes), 4.0);
+    /// ```
+    pub fn two_opt_with_strategy(
+        &self,
+        distance_mat: &DistanceMat,
+        strategy: ImprovementStrategy,
+    ) -> Route {
+        self.two_opt_with_budget(distance_mat, strategy, usize::MAX)
+            .0
+    }
+    /// Improve the route with the 2-opt local search like `two_opt`, but apply at most
+    /// `max_moves` improving moves. This bounds the runtime of a call on large instances, at the
+    /// price of possibly returning a route that 2-opt could still improve.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the length of the route is computed on.
+    /// * `max_moves` - How many improving moves are applied at most.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+    /// let route = Route::new(vec![0,2,1,3]);
+    /// assert_eq!(route.two_opt_bounded(&distance_matrix, 0), route);
+    /// let improved_route = route.two_opt_bounded(&distance_matrix, 1);
+    /// assert_eq!(distance_matrix.get_distance(&improved_route.indexes), 4.0);
+    /// ```
+    pub fn two_opt_bounded(&self, distance_mat: &DistanceMat, max_moves: usize) -> Route {
+        self.two_opt_with_budget(
+            distance_mat,
+            ImprovementStrategy::FirstImprovement,
+            max_moves,
+        )
+        .0
+    }
+    /// Improve the route with the 2-opt local search like `two_opt`, but with don't-look bits:
+    /// only the edges of nodes that are flagged as active are tried. A node becomes inactive once
+    /// no improving move starts at one of its two edges, and only the four nodes whose edges are
+    /// changed by a move become active again. This skips most of the scans on large instances and
+    /// still stops at a route no 2-opt move can improve, although not necessarily the same one
+    /// `two_opt` stops at.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the length of the route is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+    /// let improved_route = Route::new(vec![0,2,1,3]).two_opt_dlb(&distance_matrix);
+    /// assert_eq!(distance_matrix.get_distance(&improved_route.indexes), 4.0);
+    /// ```
+    pub fn two_opt_dlb(&self, distance_mat: &DistanceMat) -> Route {
+        let mut route = self.clone();
+        let n_nodes = route.indexes.len();
+        // Without four nodes there are no two edges that do not share a node.
+        if n_nodes < 4 {
+            return route;
+        }
+        let mut positions = vec![0; n_nodes];
+        for (position, node) in route.indexes.iter().enumerate() {
+            positions[*node] = position;
+        }
+        // A node is queued exactly if its don't-look bit is not set.
+        let mut dont_look = vec![false; n_nodes];
+        let mut active = route.indexes.iter().copied().collect::<VecDeque<usize>>();
+        while let Some(node) = active.pop_front() {
+            let mut improving_move = None;
+            // The position of the first node of the edge after and the edge before the node.
+            'edges: for edge in [positions[node], (positions[node] + n_nodes - 1) % n_nodes] {
+                for other_edge in 0..n_nodes {
+                    let (first, second) = (edge.min(other_edge), edge.max(other_edge));
+                    // Both edges share a node if they are neighbors or the first and the
+                    // closing edge.
+                    if second < first + 2 || (first == 0 && second == n_nodes - 1) {
+                        continue;
+                    }
+                    if two_opt_delta(distance_mat, &route.indexes, first, second) < -f64::EPSILON {
+                        improving_move = Some((first, second));
+                        break 'edges;
+                    }
+                }
+            }
+            match improving_move {
+                Some((first, second)) => {
+                    let changed_nodes = [
+                        route.indexes[first],
+                        route.indexes[first + 1],
+                        route.indexes[second],
+                        route.indexes[(second + 1) % n_nodes],
+                    ];
+                    route.apply_two_opt(first, second);
+                    for position in (first + 1)..=second {
+                        positions[route.indexes[position]] = position;
+                    }
+                    for changed_node in changed_nodes {
+                        if dont_look[changed_node] {
+                            dont_look[changed_node] = false;
+                            active.push_back(changed_node);
+                        }
+                    }
+                    // The node itself stays active, as its other edge might still be improved.
+                    active.push_back(node);
+                }
+                None => dont_look[node] = true,
+            }
+        }
+        route
+    }
+    /// Run the 2-opt local search until no improving move is left or `max_moves` moves were
+    /// applied and return the improved route with the number of applied moves.
+    fn two_opt_with_budget(
+        &self,
+        distance_mat: &DistanceMat,
+        strategy: ImprovementStrategy,
+        max_moves: usize,
+    ) -> (Route, usize) {
+        let mut route = self.clone();
+        let n_nodes = route.indexes.len();
+        let mut n_moves = 0;
+        let mut improved = true;
+        while improved && n_moves < max_moves {
+            improved = false;
+            let mut best_move: Option<(usize, usize, f64)> = None;
+            for first in 0..n_nodes.saturating_sub(2) {
+                for second in (first + 2)..n_nodes {
+                    // Both edges share a node if they are the first and the closing edge.
+                    if first == 0 && second == n_nodes - 1 {
+                        continue;
+                    }
+                    if n_moves == max_moves {
+                        return (route, n_moves);
+                    }
+                    let delta = two_opt_delta(distance_mat, &route.indexes, first, second);
+                    if delta >= -f64::EPSILON {
+                        continue;
+                    }
+                    match strategy {
+                        ImprovementStrategy::FirstImprovement => {
+                            route.apply_two_opt(first, second);
+                            n_moves += 1;
+                            improved = true;
+                        }
+                        ImprovementStrategy::BestImprovement => {
+                            if best_move.is_none_or(|(_, _, best_delta)| delta < best_delta) {
+                                best_move = Some((first, second, delta));
+                            }
+                        }
+                    }
+                }
+            }
+            if let Some((first, second, _)) = best_move {
+                route.apply_two_opt(first, second);
+                n_moves += 1;
+                improved = true;
+            }
+        }
+        (route, n_moves)
+    }
+    /// Improve the route with the Or-opt local search. Every segment of one to three consecutive
+    /// nodes (that does not wrap around the end of the route) is moved between two other
+    /// neighbouring nodes as soon as that shortens the round-trip. The search stops once no
+    /// improving move is left.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the length of the route is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (2.0, 1.0), (0.0, 1.0)]);
+    /// let improved_route = Route::new(vec![0, 2, 1, 3, 4]).or_opt(&distance_matrix);
+    /// assert_eq!(distance_matrix.get_distance(&improved_route.indexes), 6.0);
+    /// ```
+    pub fn or_opt(&self, distance_mat: &DistanceMat) -> Route {
+        self.or_opt_traced(distance_mat, OR_OPT_MAX_SEGMENT_LENGTH)
+            .0
+    }
+    /// Improve the route with the Or-opt local search like `or_opt`, but move segments of up to
+    /// `max_chain` nodes and also return every move that was applied, in order. This allows to
+    /// inspect or visualize how the local search got to the improved route.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the length of the route is computed on.
+    /// * `max_chain` - The longest segment that is moved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::{OrOptMove, Route};
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (2.0, 1.0), (0.0, 1.0)]);
+    /// let (improved_route, moves) = Route::new(vec![0, 2, 1, 3, 4]).or_opt_traced(&distance_matrix, 3);
+    /// assert_eq!(distance_matrix.get_distance(&improved_route.indexes), 6.0);
+    /// assert!(moves.iter().all(|applied: &OrOptMove| applied.delta < 0.0));
+    /// ```
+    pub fn or_opt_traced(
+        &self,
+        distance_mat: &DistanceMat,
+        max_chain: usize,
+    ) -> (Route, Vec<OrOptMove>) {
+        let mut route = self.clone();
+        let mut moves = Vec::new();
+        while let Some((improved, applied)) = route.improving_or_opt_move(distance_mat, max_chain) {
+            route = improved;
+            moves.push(applied);
+        }
+        (route, moves)
+    }
+    /// Find the first Or-opt move of a segment of at most `max_chain` nodes that shortens the
+    /// route and return the moved route together with the move.
+    fn improving_or_opt_move(
+        &self,
+        distance_mat: &DistanceMat,
+        max_chain: usize,
+    ) -> Option<(Route, OrOptMove)> {
+        let n_nodes = self.indexes.len();
+        let distance = |from: usize, to: usize| distance_mat.distance_between(from, to);
+        for segment_length in 1..=max_chain {
+            // Without at least three other nodes the segment can only be put back where it was.
+            if n_nodes < segment_length + 3 {
+                break;
+            }
+            for start in 0..=(n_nodes - segment_length) {
+                let end = start + segment_length - 1;
+                let (first, last) = (self.indexes[start], self.indexes[end]);
+                let before = self.indexes[(start + n_nodes - 1) % n_nodes];
+                let after = self.indexes[(end + 1) % n_nodes];
+                let removal_gain =
+                    distance(before, first) + distance(last, after) - distance(before, after);
+                let rest = self.indexes[..start]
+                    .iter()
+                    .chain(self.indexes[(end + 1)..].iter())
+                    .cloned()
+                    .collect::<Vec<usize>>();
+                for position in 0..rest.len() {
+                    let (from, to) = (rest[position], rest[(position + 1) % rest.len()]);
+                    // Inserting the segment between `before` and `after` restores the route.
+                    if from == before {
+                        continue;
+                    }
+                    let insertion_cost =
+                        distance(from, first) + distance(last, to) - distance(from, to);
+                    let delta = insertion_cost - removal_gain;
+                    if delta < -f64::EPSILON {
+                        let mut indexes = rest;
+                        indexes.splice(
+                            (position + 1)..(position + 1),
+                            self.indexes[start..=end].iter().cloned(),
+                        );
+                        return Some((
+                            Route { indexes },
+                            OrOptMove {
+                                from: start,
+                                to: position + 1,
+                                chain_len: segment_length,
+                                delta,
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+        None
+    }
+    /// Apply a 2-opt move in place: the edges starting at the positions `first` and `second` are
+    /// replaced by reversing the nodes at the positions `first + 1..=second`. Applying the same
+    /// move again restores the original route, so trial moves can be undone without allocating.
+    ///
+    /// # Arguments
+    ///
+    /// * `first` - The position at which the first edge starts.
+    /// * `second` - The position at which the second edge starts, needs to be larger than `first`
+    ///   and smaller than the number of nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    ///
+    /// let mut route = Route::new(vec![0,1,2,3,4]);
+    /// route.apply_two_opt(0, 3);
+    /// assert_eq!(route, Route::new(vec![0,3,2,1,4]));
+    /// route.apply_two_opt(0, 3);
+    /// assert_eq!(route, Route::new(vec![0,1,2,3,4]));
+    /// ```
+    pub fn apply_two_opt(&mut self, first: usize, second: usize) {
+        self.indexes[(first + 1)..=second].reverse();
+    }
+    /// Search a short route with iterated local search: the route is improved with `two_opt`,
+    /// then repeatedly perturbed with `double_bridge` and improved again. A perturbed route is only
+    /// accepted if it is shorter than the best route found so far, which is returned in the end.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the length of the route is computed on.
+    /// * `iterations` - How many perturbations should be tried?
+    /// * `rng` - The random number generator used for the perturbations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let improved_route = Route::new(vec![0,1,2]).iterated_local_search(
+    ///     &distance_matrix,
+    ///     10,
+    ///     &mut rand::thread_rng(),
+    /// );
+    /// ```
+    pub fn iterated_local_search(
+        &self,
+        distance_mat: &DistanceMat,
+        iterations: usize,
+        rng: &mut impl Rng,
+    ) -> Route {
+        let mut best_route = self.two_opt(distance_mat);
+        let mut best_distance = distance_mat.get_distance(&best_route.indexes);
+        for _ in 0..iterations {
+            let candidate = best_route.double_bridge(rng).two_opt(distance_mat);
+            let candidate_distance = distance_mat.get_distance(&candidate.indexes);
+            if candidate_distance < best_distance {
+                best_route = candidate;
+                best_distance = candidate_distance;
+            }
+        }
+        best_route
+    }
+    /// Cut the route before the positions `first_cut < second_cut < third_cut` into the
+    /// segments `A`, `B`, `C`, `D` and reconnect them as `A-C-B-D`.
+    fn reconnect_segments(&self, first_cut: usize, second_cut: usize, third_cut: usize) -> Route {
+        let (a, rest) = self.indexes.split_at(first_cut);
+        let (b, rest) = rest.split_at(second_cut - first_cut);
+        let (c, d) = rest.split_at(third_cut - second_cut);
+        Route {
+            indexes: [a, c, b, d].concat(),
+        }
+    }
+}
+/// Change of the round-trip length if the edges starting at the positions `first` and `second`
+/// are replaced by a 2-opt move, e.g. if the nodes `first + 1..=second` are reversed.
+///
+/// # Arguments
+///
+/// * `distance_mat` - The distance matrix the length of the route is computed on.
+/// * `indexes` - The order in which the nodes are visited.
+/// * `first` - The position at which the first edge starts.
+/// * `second` - The position at which the second edge starts, needs to be larger than `first`.
+///
+fn two_opt_delta(
+    distance_mat: &DistanceMat,
+    indexes: &[usize],
+    first: usize,
+    second: usize,
+) -> f64 {
+    let (a, b) = (indexes[first], indexes[first + 1]);
+    let (c, d) = (indexes[second], indexes[(second + 1) % indexes.len()]);
+    distance_mat.distance_between(a, c) + distance_mat.distance_between(b, d)
+        - distance_mat.distance_between(a, b)
+        - distance_mat.distance_between(c, d)
+}
+impl<'a> Individual<'a> for Route {
+    // The Distance matrix is needed by the individuals to compute their fitness on.
+    type IndividualCost = DistanceMat;
+    /// Randomly changes the order of two nodes in the route
+    ///
+    /// # Arguments
+    ///
+    /// * `prob` - The probability with which the indexes will be changed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::gen_traits::Individual;
+    ///
+    /// let my_individual = Route::from(Route::new(vec![0,1,2]));
+    /// let my_mutated_indiviual =  my_individual.mutate(1.0);
+    /// ```
+    fn mutate(self, prob: f32) -> Self {
+        self.mutate_with(prob, &mut rand::thread_rng())
+    }
+    /// Crossover this invidual with another individual to create a new individual. Currently
+    /// uses the `ordered_crossover` algorithm.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other individual you would like to crossover with this individual.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::gen_traits::Individual;
+    ///
+    /// let my_individual = Route::from(Route::new(vec![0,1,2]));
+    /// let my_individual = my_individual.crossover(
+    ///     &Route::from(Route::new(vec![1,0,2]))
+    /// );
+    /// ```
+    fn crossover(&self, other: &Route) -> Self {
+        ordered_crossover(
+            self,
+            other,
+            Subsequence::random_subsequence(self.indexes.len()),
+        )
+    }
+    /// Compute how much distance the individual implies with its order of nodes
+    /// and the distance matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_matrix` - Distance Matrix that determines the length of the proposed
+    ///   route
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    /// use genetic_algo::gen_traits::Individual;
+    ///
+    /// let my_individual = Route::from(Route::new(vec![0,1,2]));
+    /// println!("Fitness of your individual: {}", my_individual.fitness(
+    ///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]))
+    /// )
+    /// ```
+    ///
+    fn fitness(&self, distance_mat: &DistanceMat) -> f64 {
+        -distance_mat.get_distance(&self.indexes[..])
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::random_permutation_with;
     mod test_route {
         use super::*;
         use crate::test_utils::valid_permutation;
@@ -143,21 +1363,37 @@ mod tests {
                 vec![1, 2, 3, 4]
             )
         }
-        // Run the following test five times.
-        #[test]
-        #[test]
         #[test]
+        fn test_mutuate_100_prob() {
+            // Run the following test five times.
+            for _ in 0..5 {
+                assert_ne!(
+                    Route::new(vec![1, 2, 3, 4]).mutate(1.0).indexes,
+                    vec![1, 2, 3, 4]
+                )
+            }
+        }
         #[test]
+        fn test_mutuate_100_prob_3_elems() {
+            assert_ne!(Route::new(vec![1, 2, 3]).mutate(1.0).indexes, vec![1, 2, 3])
+        }
         #[test]
-        fn test_mutuate_100_prob() {
-            assert_ne!(
-                Route::new(vec![1, 2, 3, 4]).mutate(1.0).indexes,
+        fn test_mutate_with_no_prob() {
+            assert_eq!(
+                Route::new(vec![1, 2, 3, 4])
+                    .mutate_with(0.0, &mut rand::thread_rng())
+                    .indexes,
                 vec![1, 2, 3, 4]
             )
         }
         #[test]
-        fn test_mutuate_100_prob_3_elems() {
-            assert_ne!(Route::new(vec![1, 2, 3]).mutate(1.0).indexes, vec![1, 2, 3])
+        fn test_mutate_with_100_prob() {
+            let mut rng = rand::thread_rng();
+            for _ in 0..5 {
+                let mutated = Route::new(vec![1, 2, 3, 4]).mutate_with(1.0, &mut rng);
+                assert_ne!(mutated.indexes, vec![1, 2, 3, 4]);
+                valid_permutation(&mutated.indexes, &[1, 2, 3, 4]);
+            }
         }
         #[test]
         fn test_mutate_simple_run() {
@@ -190,6 +1426,917 @@ mod tests {
             assert!(n_no_crossover <= n_tests / 5);
         }
     }
+    mod test_maybe_mutate {
+        use super::*;
+        use crate::test_utils::valid_permutation;
+        const KINDS: [MutationKind; 4] = [
+            MutationKind::Insertion,
+            MutationKind::Swap,
+            MutationKind::Inversion,
+            MutationKind::DoubleBridge,
+        ];
+        #[test]
+        fn zero_rate_never_mutates() {
+            let route = Route::new(vec![0, 5, 2, 3, 1, 4, 7, 6]);
+            let mut rng = rand::thread_rng();
+            for kind in KINDS {
+                for _ in 0..200 {
+                    assert_eq!(route.maybe_mutate(0.0, kind, &mut rng), route);
+                }
+            }
+        }
+        #[test]
+        fn mutated_routes_are_valid() {
+            let route = Route::new(vec![0, 5, 2, 3, 1, 4, 7, 6]);
+            let mut rng = rand::thread_rng();
+            for kind in KINDS {
+                for _ in 0..200 {
+                    valid_permutation(
+                        &route.maybe_mutate(1.0, kind, &mut rng).indexes,
+                        &route.indexes,
+                    );
+                }
+            }
+        }
+        #[test]
+        fn two_nodes() {
+            let route = Route::new(vec![1, 0]);
+            let mut rng = rand::thread_rng();
+            for kind in KINDS {
+                let expected = if kind == MutationKind::DoubleBridge {
+                    // A double bridge needs at least four nodes.
+                    route.clone()
+                } else {
+                    Route::new(vec![0, 1])
+                };
+                for _ in 0..50 {
+                    assert_eq!(route.maybe_mutate(1.0, kind, &mut rng), expected);
+                }
+            }
+        }
+        #[test]
+        fn swap_and_inversion_always_change() {
+            let route = Route::new(vec![0, 5, 2, 3, 1, 4, 7, 6]);
+            let mut rng = rand::thread_rng();
+            for kind in [MutationKind::Swap, MutationKind::Inversion] {
+                for _ in 0..200 {
+                    assert_ne!(route.maybe_mutate(1.0, kind, &mut rng), route);
+                }
+            }
+        }
+        #[test]
+        fn tiny_routes() {
+            for kind in KINDS {
+                let route = Route::new(vec![3]);
+                assert_eq!(
+                    route.maybe_mutate(1.0, kind, &mut rand::thread_rng()),
+                    route
+                );
+            }
+        }
+    }
+    mod test_neighborhood_lengths {
+        use super::*;
+        use crate::test_utils::circle_dist_mat;
+        #[test]
+        fn neighborhood_sizes() {
+            let distance_mat = circle_dist_mat(7);
+            let route = Route::new(vec![0, 5, 2, 3, 1, 4, 6]);
+            for (kind, size) in [
+                (MutationKind::Insertion, 42),
+                (MutationKind::Swap, 21),
+                (MutationKind::Inversion, 21),
+                (MutationKind::DoubleBridge, 20),
+            ] {
+                assert_eq!(route.neighborhood_lengths(&distance_mat, kind).len(), size);
+            }
+        }
+        #[test]
+        fn optimum_has_no_shorter_neighbor() {
+            let distance_mat = circle_dist_mat(7);
+            let route = Route::new((0..7).collect());
+            let length = route.distance(&distance_mat);
+            for kind in [
+                MutationKind::Insertion,
+                MutationKind::Swap,
+                MutationKind::Inversion,
+            ] {
+                assert!(route
+                    .neighborhood_lengths(&distance_mat, kind)
+                    .iter()
+                    .all(|neighbor_length| *neighbor_length >= length - 1e-9));
+            }
+        }
+        #[test]
+        fn same_as_built_neighbors() {
+            let distance_mat = DistanceMat::new(vec![
+                vec![0.0, 1.0, 7.0, 3.0, 5.0],
+                vec![4.0, 0.0, 2.0, 9.0, 1.0],
+                vec![6.0, 5.0, 0.0, 1.0, 8.0],
+                vec![2.0, 8.0, 3.0, 0.0, 4.0],
+                vec![3.0, 6.0, 2.0, 7.0, 0.0],
+            ]);
+            let route = Route::new(vec![3, 0, 4, 1, 2]);
+            let mut inversions = Vec::new();
+            let mut double_bridges = Vec::new();
+            for first in 0..5 {
+                for second in (first + 1)..5 {
+                    let mut indexes = route.indexes.clone();
+                    indexes[first..=second].reverse();
+                    inversions.push(distance_mat.get_distance(&indexes));
+                    if first > 0 {
+                        for third in (second + 1)..5 {
+                            double_bridges.push(
+                                route
+                                    .reconnect_segments(first, second, third)
+                                    .distance(&distance_mat),
+                            );
+                        }
+                    }
+                }
+            }
+            assert_eq!(
+                route.neighborhood_lengths(&distance_mat, MutationKind::Inversion),
+                inversions
+            );
+            assert_eq!(
+                route.neighborhood_lengths(&distance_mat, MutationKind::DoubleBridge),
+                double_bridges
+            );
+        }
+        #[test]
+        fn tiny_routes() {
+            let distance_mat = circle_dist_mat(3);
+            let route = Route::new(vec![2]);
+            assert!(route
+                .neighborhood_lengths(&distance_mat, MutationKind::Swap)
+                .is_empty());
+            assert!(Route::new(vec![0, 1, 2])
+                .neighborhood_lengths(&distance_mat, MutationKind::DoubleBridge)
+                .is_empty());
+        }
+    }
+    mod test_mutate_each_gene {
+        use super::*;
+        use crate::test_utils::valid_permutation;
+        #[test]
+        fn zero_rate_unchanged() {
+            let route = Route::new((0..20).collect());
+            assert_eq!(route.mutate_each_gene(0.0, &mut rand::thread_rng()), route);
+        }
+        #[test]
+        fn full_rate_disturbs_heavily() {
+            let route = Route::new((0..20).collect());
+            let mutated = route.mutate_each_gene(1.0, &mut rand::thread_rng());
+            valid_permutation(&route.indexes, &mutated.indexes);
+            let n_moved = route
+                .indexes
+                .iter()
+                .zip(mutated.indexes.iter())
+                .filter(|(node, mutated_node)| node != mutated_node)
+                .count();
+            assert!(n_moved >= 10);
+        }
+        #[test]
+        fn valid_for_any_rate() {
+            let route = Route::new((0..10).collect());
+            let mut rng = rand::thread_rng();
+            for rate in [0.1, 0.5, 0.9] {
+                valid_permutation(
+                    &route.indexes,
+                    &route.mutate_each_gene(rate, &mut rng).indexes,
+                );
+            }
+        }
+        #[test]
+        fn single_node() {
+            let route = Route::new(vec![0]);
+            assert_eq!(route.mutate_each_gene(1.0, &mut rand::thread_rng()), route);
+        }
+    }
+    mod test_neighbors {
+        use super::*;
+        #[test]
+        fn position_of() {
+            let route = Route::new(vec![3, 1, 0, 2]);
+            assert_eq!(route.position_of(3), Some(0));
+            assert_eq!(route.position_of(2), Some(3));
+            assert_eq!(route.position_of(4), None);
+        }
+        #[test]
+        fn successor() {
+            let route = Route::new(vec![3, 1, 0, 2]);
+            assert_eq!(route.successor(1), Some(0));
+            assert_eq!(route.successor(2), Some(3));
+            assert_eq!(route.successor(4), None);
+        }
+        #[test]
+        fn predecessor() {
+            let route = Route::new(vec![3, 1, 0, 2]);
+            assert_eq!(route.predecessor(0), Some(1));
+            assert_eq!(route.predecessor(3), Some(2));
+            assert_eq!(route.predecessor(4), None);
+        }
+        #[test]
+        fn single_node() {
+            let route = Route::new(vec![0]);
+            assert_eq!(route.successor(0), Some(0));
+            assert_eq!(route.predecessor(0), Some(0));
+        }
+        #[test]
+        fn positions() {
+            let route = Route::new(vec![3, 1, 0, 2]);
+            assert_eq!(route.positions(), vec![2, 1, 3, 0]);
+            for node in 0..4 {
+                assert_eq!(route.position_of(node), Some(route.positions()[node]));
+            }
+        }
+    }
+    mod test_kendall_tau {
+        use super::*;
+        #[test]
+        fn hand_computed() {
+            // The pairs (0, 2), (0, 3), (1, 2) and (1, 3) are visited in a different order.
+            assert_eq!(
+                Route::new(vec![0, 1, 2, 3, 4]).kendall_tau(&Route::new(vec![2, 3, 0, 1, 4])),
+                4
+            );
+            assert_eq!(
+                Route::new(vec![2, 0, 1]).kendall_tau(&Route::new(vec![0, 1, 2])),
+                2
+            );
+        }
+        #[test]
+        fn identical_and_reversed() {
+            let route = Route::new(vec![3, 1, 4, 0, 2, 5]);
+            let mut reversed = route.clone();
+            reversed.indexes.reverse();
+            assert_eq!(route.kendall_tau(&route), 0);
+            assert_eq!(route.kendall_tau(&reversed), 15);
+        }
+        #[test]
+        fn symmetric() {
+            let route_a = Route::new(vec![3, 1, 4, 0, 2, 5]);
+            let route_b = Route::new(vec![5, 1, 0, 2, 3, 4]);
+            assert_eq!(route_a.kendall_tau(&route_b), route_b.kendall_tau(&route_a));
+        }
+        #[test]
+        #[should_panic]
+        fn different_lengths() {
+            Route::new(vec![0, 1]).kendall_tau(&Route::new(vec![0, 1, 2]));
+        }
+    }
+    mod test_checked_apply {
+        use super::*;
+        use crate::test_utils::circle_dist_mat;
+        #[test]
+        fn improving_move_passes() {
+            let distance_mat = circle_dist_mat(6);
+            let route = Route::new(vec![0, 3, 1, 4, 2, 5]);
+            let improved = route.two_opt(&distance_mat);
+            assert_eq!(
+                route.checked_apply(&distance_mat, improved.clone()),
+                improved
+            );
+            assert_eq!(route.checked_apply(&distance_mat, route.clone()), route);
+        }
+        #[test]
+        #[cfg(debug_assertions)]
+        #[should_panic(expected = "lengthens the route")]
+        fn worsening_move_trips_guard() {
+            let distance_mat = circle_dist_mat(6);
+            let route = Route::new(vec![0, 1, 2, 3, 4, 5]);
+            route.checked_apply(&distance_mat, Route::new(vec![0, 3, 1, 4, 2, 5]));
+        }
+    }
+    mod test_optimality_gap {
+        use super::*;
+        use crate::test_utils::test_dist_mat;
+        #[test]
+        fn known_gap() {
+            // The route is 6 long and the minimum spanning tree weighs 3.
+            let route = Route::new(vec![0, 1, 2]);
+            assert_eq!(route.distance(&test_dist_mat()), 6.0);
+            assert_eq!(
+                route.optimality_gap(&test_dist_mat(), test_dist_mat().mst_lower_bound()),
+                1.0
+            );
+            assert_eq!(route.optimality_gap(&test_dist_mat(), 6.0), 0.0);
+        }
+        #[test]
+        fn zero_bound() {
+            let route = Route::new(vec![0, 1, 2]);
+            assert_eq!(route.optimality_gap(&test_dist_mat(), 0.0), f64::INFINITY);
+            assert_eq!(
+                Route::new(vec![0]).optimality_gap(&test_dist_mat(), 0.0),
+                0.0
+            );
+        }
+    }
+    mod test_longest_edge {
+        use super::*;
+        use crate::test_utils::test_dist_mat;
+        #[test]
+        fn inner_edge() {
+            assert_eq!(
+                Route::new(vec![0, 1, 2]).longest_edge(&test_dist_mat()),
+                (1, 2, 3.0)
+            );
+        }
+        #[test]
+        fn closing_edge() {
+            assert_eq!(
+                Route::new(vec![2, 0, 1]).longest_edge(&test_dist_mat()),
+                (1, 2, 3.0)
+            );
+        }
+        #[test]
+        fn square() {
+            let distance_mat =
+                DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 2.0)]);
+            assert_eq!(
+                Route::new(vec![0, 1, 2, 3]).longest_edge(&distance_mat),
+                (3, 0, 2.0)
+            );
+        }
+        #[test]
+        #[should_panic]
+        fn empty_route() {
+            Route::new(vec![]).longest_edge(&test_dist_mat());
+        }
+    }
+    #[test]
+    fn test_lexicographic_ordering() {
+        assert!(Route::new(vec![0, 2, 1]) < Route::new(vec![1, 0, 2]));
+        assert!(Route::new(vec![0, 1, 2]) < Route::new(vec![0, 2, 1]));
+        assert!(Route::new(vec![0, 1]) < Route::new(vec![0, 1, 2]));
+    }
+    mod test_crossover_op {
+        use super::*;
+        use crate::test_utils::valid_permutation;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn ordered_crossover_is_first_child_of_pair() {
+            let route_a = Route::new(vec![0, 1, 2, 3, 4, 5]);
+            let route_b = Route::new(vec![5, 3, 1, 0, 4, 2]);
+            for seed in 0..10 {
+                let child =
+                    OrderedCrossover.apply(&route_a, &route_b, &mut StdRng::seed_from_u64(seed));
+                let (first_child, _) =
+                    route_a.crossover_pair(&route_b, &mut StdRng::seed_from_u64(seed));
+                assert_eq!(child, first_child);
+            }
+        }
+        #[test]
+        fn fixed_depot_crossover_starts_at_depot() {
+            let route_a = Route::new(vec![0, 1, 2, 3, 4, 5]);
+            let route_b = Route::new(vec![5, 3, 1, 0, 4, 2]);
+            let operator: &dyn CrossoverOp = &FixedDepotCrossover { depot: 3 };
+            for _ in 0..10 {
+                let child = operator.apply(&route_a, &route_b, &mut rand::thread_rng());
+                valid_permutation(&child.indexes, &route_a.indexes);
+                assert_eq!(child.indexes[0], 3);
+            }
+        }
+    }
+    mod test_crossover_pair {
+        use super::*;
+        use crate::test_utils::valid_permutation;
+
+        #[test]
+        fn children_are_valid_and_differ() {
+            let n_tests = 1000;
+            let route_a = Route {
+                indexes: vec![0, 12, 7, 3, 9, 8, 11, 5, 13, 1, 4, 6, 10, 15, 2, 14],
+            };
+            let route_b = Route {
+                indexes: vec![7, 10, 15, 12, 2, 9, 5, 3, 1, 6, 4, 13, 14, 11, 8, 0],
+            };
+            let mut rng = rand::thread_rng();
+            let mut n_same_children = 0;
+            for _ in 0..n_tests {
+                let (child_a, child_b) = route_a.crossover_pair(&route_b, &mut rng);
+                valid_permutation(&child_a.indexes, &route_a.indexes);
+                valid_permutation(&child_b.indexes, &route_a.indexes);
+                if child_a == child_b {
+                    n_same_children += 1;
+                }
+            }
+            assert!(n_same_children <= n_tests / 5);
+        }
+    }
+    mod test_crossover_fixed_depot {
+        use super::*;
+        use crate::test_utils::valid_permutation;
+        #[test]
+        fn depot_stays_first() {
+            let route_a = Route::new(vec![0, 12, 7, 3, 9, 8, 11, 5, 13, 1, 4, 6, 10, 15, 2, 14]);
+            let route_b = Route::new(vec![0, 7, 10, 15, 12, 2, 9, 5, 3, 1, 6, 4, 13, 14, 11, 8]);
+            let mut rng = rand::thread_rng();
+            for _ in 0..1000 {
+                let child = route_a.crossover_fixed_depot(&route_b, 0, &mut rng);
+                assert_eq!(child.indexes[0], 0);
+                valid_permutation(&child.indexes, &route_a.indexes);
+            }
+        }
+        #[test]
+        fn other_depot() {
+            let route_a = Route::new(vec![3, 0, 1, 2, 4]);
+            let route_b = Route::new(vec![4, 2, 3, 1, 0]);
+            for _ in 0..100 {
+                let child = route_a.crossover_fixed_depot(&route_b, 3, &mut rand::thread_rng());
+                assert_eq!(child.indexes[0], 3);
+                valid_permutation(&child.indexes, &route_a.indexes);
+            }
+        }
+        #[test]
+        #[should_panic]
+        fn missing_depot() {
+            Route::new(vec![0, 1, 2]).crossover_fixed_depot(
+                &Route::new(vec![2, 1, 0]),
+                5,
+                &mut rand::thread_rng(),
+            );
+        }
+    }
+    mod test_double_bridge {
+        use super::*;
+        use crate::test_utils::valid_permutation;
+        use std::collections::HashSet;
+
+        /// All undirected edges of the round-trip, including the edge back to the start.
+        fn edges(route: &Route) -> HashSet<(usize, usize)> {
+            (0..route.indexes.len())
+                .map(|idx| {
+                    let from = route.indexes[idx];
+                    let to = route.indexes[(idx + 1) % route.indexes.len()];
+                    (from.min(to), from.max(to))
+                })
+                .collect()
+        }
+        #[test]
+        fn replaces_segment_edges() {
+            let route = Route::new((0..10).collect());
+            let perturbed = route.reconnect_segments(2, 5, 7);
+            assert_eq!(perturbed.indexes, vec![0, 1, 5, 6, 2, 3, 4, 7, 8, 9]);
+            // A-C-B-D keeps the closing edge D-A, so the edges between A|B, B|C and C|D
+            // are replaced by A|C, C|B and B|D.
+            assert_eq!(
+                edges(&route)
+                    .difference(&edges(&perturbed))
+                    .collect::<HashSet<_>>(),
+                HashSet::from([&(1, 2), &(4, 5), &(6, 7)])
+            );
+            assert_eq!(
+                edges(&perturbed)
+                    .difference(&edges(&route))
+                    .collect::<HashSet<_>>(),
+                HashSet::from([&(1, 5), &(2, 6), &(4, 7)])
+            );
+        }
+        #[test]
+        fn random_cuts_are_valid() {
+            let route = Route::new((0..12).collect());
+            let mut rng = rand::thread_rng();
+            for _ in 0..100 {
+                let perturbed = route.double_bridge(&mut rng);
+                valid_permutation(&route.indexes, &perturbed.indexes);
+                // If both `B` and `C` consist of a single node, the edge between them survives.
+                let n_removed = edges(&route).difference(&edges(&perturbed)).count();
+                assert!(n_removed == 2 || n_removed == 3);
+                assert_eq!(
+                    edges(&perturbed).difference(&edges(&route)).count(),
+                    n_removed
+                );
+            }
+        }
+        #[test]
+        fn too_short_route_unchanged() {
+            let route = Route::new(vec![2, 0, 1]);
+            assert_eq!(route.double_bridge(&mut rand::thread_rng()), route);
+        }
+    }
+    mod test_two_opt {
+        use super::*;
+        use crate::test_utils::{circle_dist_mat, valid_permutation};
+        #[test]
+        fn untangles_crossing() {
+            // Visiting the points on a circle in the order 0, 2, 1, 3 crosses edges.
+            let distance_mat = circle_dist_mat(4);
+            let improved = Route::new(vec![0, 2, 1, 3]).two_opt(&distance_mat);
+            valid_permutation(&improved.indexes, &[0, 1, 2, 3]);
+            assert!(
+                distance_mat.get_distance(&improved.indexes)
+                    < distance_mat.get_distance(&[0, 2, 1, 3])
+            );
+        }
+        #[test]
+        fn reaches_local_optimum() {
+            let distance_mat = circle_dist_mat(12);
+            let route = Route::new(random_permutation_with(
+                &(0..12).collect::<Vec<usize>>(),
+                &mut rand::thread_rng(),
+            ));
+            let improved = route.two_opt(&distance_mat);
+            valid_permutation(&improved.indexes, &route.indexes);
+            for first in 0..10 {
+                for second in (first + 2)..12 {
+                    if !(first == 0 && second == 11) {
+                        assert!(
+                            two_opt_delta(&distance_mat, &improved.indexes, first, second)
+                                >= -f64::EPSILON
+                        );
+                    }
+                }
+            }
+        }
+    }
+    mod test_two_opt_dlb {
+        use super::*;
+        use crate::test_utils::{circle_dist_mat, valid_permutation};
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn same_optimum_as_two_opt() {
+            // On a circle every 2-opt local optimum is the optimal route.
+            let mut rng = StdRng::seed_from_u64(11);
+            for n_nodes in 4..14 {
+                let distance_mat = circle_dist_mat(n_nodes);
+                let route = Route::new(random_permutation_with(
+                    &(0..n_nodes).collect::<Vec<usize>>(),
+                    &mut rng,
+                ));
+                let improved = route.two_opt_dlb(&distance_mat);
+                valid_permutation(&improved.indexes, &route.indexes);
+                assert!(
+                    (improved.distance(&distance_mat)
+                        - route.two_opt(&distance_mat).distance(&distance_mat))
+                    .abs()
+                        < 1e-9
+                );
+            }
+        }
+        #[test]
+        fn reaches_local_optimum() {
+            let mut rng = StdRng::seed_from_u64(3);
+            let points = (0..30)
+                .map(|_| (rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0)))
+                .collect::<Vec<(f64, f64)>>();
+            let distance_mat = DistanceMat::from_coordinates(&points);
+            let route = Route::new(random_permutation_with(
+                &(0..30).collect::<Vec<usize>>(),
+                &mut rng,
+            ));
+            let improved = route.two_opt_dlb(&distance_mat);
+            assert!(improved.distance(&distance_mat) < route.distance(&distance_mat));
+            assert_eq!(improved.two_opt(&distance_mat), improved);
+        }
+        #[test]
+        fn tiny_routes() {
+            let distance_mat = circle_dist_mat(3);
+            for route in [
+                Route::new(vec![]),
+                Route::new(vec![1]),
+                Route::new(vec![2, 0, 1]),
+            ] {
+                assert_eq!(route.two_opt_dlb(&distance_mat), route);
+            }
+        }
+    }
+    mod test_two_opt_bounded {
+        use super::*;
+        use crate::test_utils::{circle_dist_mat, valid_permutation};
+        #[test]
+        fn never_exceeds_max_moves() {
+            let distance_mat = circle_dist_mat(15);
+            let mut rng = rand::thread_rng();
+            for max_moves in 0..6 {
+                let route = Route::new(random_permutation_with(
+                    &(0..15).collect::<Vec<usize>>(),
+                    &mut rng,
+                ));
+                let (bounded, n_moves) = route.two_opt_with_budget(
+                    &distance_mat,
+                    ImprovementStrategy::FirstImprovement,
+                    max_moves,
+                );
+                assert!(n_moves <= max_moves);
+                assert_eq!(bounded, route.two_opt_bounded(&distance_mat, max_moves));
+                valid_permutation(&bounded.indexes, &route.indexes);
+                assert!(
+                    distance_mat.get_distance(&bounded.indexes)
+                        <= distance_mat.get_distance(&route.indexes)
+                );
+            }
+        }
+        #[test]
+        fn length_is_non_increasing_in_max_moves() {
+            let distance_mat = circle_dist_mat(12);
+            let route = Route::new(vec![0, 5, 2, 7, 4, 9, 6, 1, 8, 3, 11, 10]);
+            let mut last_distance = distance_mat.get_distance(&route.indexes);
+            for max_moves in 1..8 {
+                let distance = distance_mat
+                    .get_distance(&route.two_opt_bounded(&distance_mat, max_moves).indexes);
+                assert!(distance <= last_distance + f64::EPSILON);
+                last_distance = distance;
+            }
+        }
+        #[test]
+        fn unbounded_is_two_opt() {
+            let distance_mat = circle_dist_mat(10);
+            let route = Route::new(vec![0, 5, 2, 7, 4, 9, 6, 1, 8, 3]);
+            assert_eq!(
+                route.two_opt_bounded(&distance_mat, usize::MAX),
+                route.two_opt(&distance_mat)
+            );
+        }
+    }
+    mod test_two_opt_with_strategy {
+        use super::*;
+        use crate::test_utils::{circle_dist_mat, valid_permutation};
+        #[test]
+        fn both_reach_local_optimum() {
+            let distance_mat = circle_dist_mat(12);
+            let mut rng = rand::thread_rng();
+            for strategy in [
+                ImprovementStrategy::FirstImprovement,
+                ImprovementStrategy::BestImprovement,
+            ] {
+                for _ in 0..10 {
+                    let route = Route::new(random_permutation_with(
+                        &(0..12).collect::<Vec<usize>>(),
+                        &mut rng,
+                    ));
+                    let improved = route.two_opt_with_strategy(&distance_mat, strategy);
+                    valid_permutation(&improved.indexes, &route.indexes);
+                    for first in 0..10 {
+                        for second in (first + 2)..12 {
+                            if !(first == 0 && second == 11) {
+                                assert!(
+                                    two_opt_delta(&distance_mat, &improved.indexes, first, second)
+                                        >= -f64::EPSILON
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        #[test]
+        fn first_improvement_is_two_opt() {
+            let distance_mat = circle_dist_mat(10);
+            let route = Route::new(vec![0, 5, 2, 7, 4, 9, 6, 1, 8, 3]);
+            assert_eq!(
+                route.two_opt_with_strategy(&distance_mat, ImprovementStrategy::FirstImprovement),
+                route.two_opt(&distance_mat)
+            );
+        }
+        #[test]
+        fn best_improvement_applies_best_move() {
+            // Only one move is improving, so both strategies apply it.
+            let distance_mat = circle_dist_mat(4);
+            assert_eq!(
+                Route::new(vec![0, 2, 1, 3])
+                    .two_opt_with_strategy(&distance_mat, ImprovementStrategy::BestImprovement),
+                Route::new(vec![0, 1, 2, 3])
+            );
+        }
+    }
+    mod test_or_opt {
+        use super::*;
+        use crate::test_utils::{circle_dist_mat, valid_permutation};
+        #[test]
+        fn moves_misplaced_node() {
+            let distance_mat = circle_dist_mat(8);
+            let improved = Route::new(vec![0, 1, 5, 2, 3, 4, 6, 7]).or_opt(&distance_mat);
+            assert!(
+                (distance_mat.get_distance(&improved.indexes)
+                    - distance_mat.get_distance(&[0, 1, 2, 3, 4, 5, 6, 7]))
+                .abs()
+                    < 1e-9
+            );
+        }
+        #[test]
+        fn moves_segment() {
+            let distance_mat = circle_dist_mat(9);
+            let improved = Route::new(vec![0, 6, 7, 8, 1, 2, 3, 4, 5]).or_opt(&distance_mat);
+            valid_permutation(&improved.indexes, &(0..9).collect::<Vec<usize>>());
+            assert!(
+                distance_mat.get_distance(&improved.indexes)
+                    < distance_mat.get_distance(&[0, 6, 7, 8, 1, 2, 3, 4, 5])
+            );
+        }
+        #[test]
+        fn never_worse() {
+            let distance_mat = circle_dist_mat(12);
+            let mut rng = rand::thread_rng();
+            for _ in 0..20 {
+                let route = Route::new(random_permutation_with(
+                    &(0..12).collect::<Vec<usize>>(),
+                    &mut rng,
+                ));
+                let improved = route.or_opt(&distance_mat);
+                valid_permutation(&improved.indexes, &route.indexes);
+                assert!(
+                    distance_mat.get_distance(&improved.indexes)
+                        <= distance_mat.get_distance(&route.indexes)
+                );
+                assert_eq!(
+                    improved.improving_or_opt_move(&distance_mat, OR_OPT_MAX_SEGMENT_LENGTH),
+                    None
+                );
+            }
+        }
+        #[test]
+        fn tiny_routes() {
+            assert_eq!(
+                Route::new(vec![1, 0, 2]).or_opt(&circle_dist_mat(3)),
+                Route::new(vec![1, 0, 2])
+            );
+        }
+    }
+    mod test_one_indexed {
+        use super::*;
+        #[test]
+        fn round_trip() {
+            let route = Route::new(vec![3, 0, 4, 1, 2]);
+            assert_eq!(
+                Route::from_one_indexed_str(&route.to_one_indexed_string(), 5).unwrap(),
+                route
+            );
+        }
+        #[test]
+        fn zero_prints_as_one() {
+            assert_eq!(Route::new(vec![0]).to_one_indexed_string(), "1");
+            assert_eq!(Route::new(vec![1, 0]).to_one_indexed_string(), "2 1");
+        }
+        #[test]
+        fn accepts_commas() {
+            assert_eq!(
+                Route::from_one_indexed_str("2, 3,1", 3).unwrap(),
+                Route::new(vec![1, 2, 0])
+            );
+        }
+        #[test]
+        fn invalid_routes() {
+            assert!(matches!(
+                Route::from_one_indexed_str("1 0 2", 3),
+                Err(TourParseError::InvalidNode { line: 1, .. })
+            ));
+            assert!(matches!(
+                Route::from_one_indexed_str("1 2 2", 3),
+                Err(TourParseError::NotAPermutation { line: 1 })
+            ));
+        }
+    }
+    mod test_complete_greedily {
+        use super::*;
+        use crate::test_utils::{circle_dist_mat, valid_permutation};
+        #[test]
+        fn completes_two_city_prefix() {
+            let distance_mat = circle_dist_mat(8);
+            let completed = Route::complete_greedily(&[4, 0], &distance_mat);
+            valid_permutation(&completed.indexes, &(0..8).collect::<Vec<usize>>());
+            assert_eq!(completed.indexes[..2], [4, 0]);
+            // Both neighbours of 0 are equally close, so the lower one is visited first.
+            assert_eq!(completed, Route::new(vec![4, 0, 1, 2, 3, 5, 6, 7]));
+        }
+        #[test]
+        fn complete_prefix_is_kept() {
+            let route =
+                Route::complete_greedily(&[2, 0, 1], &DistanceMat::new(vec![vec![0.0; 3]; 3]));
+            assert_eq!(route, Route::new(vec![2, 0, 1]));
+        }
+        #[test]
+        fn empty_prefix_is_nearest_neighbor_route() {
+            let distance_mat = circle_dist_mat(6);
+            assert_eq!(
+                Route::complete_greedily(&[], &distance_mat),
+                distance_mat.nearest_neighbor_route(0)
+            );
+        }
+        #[test]
+        #[should_panic]
+        fn duplicate_node() {
+            Route::complete_greedily(&[1, 1], &circle_dist_mat(4));
+        }
+        #[test]
+        #[should_panic]
+        fn node_out_of_matrix() {
+            Route::complete_greedily(&[4], &circle_dist_mat(4));
+        }
+    }
+    mod test_or_opt_traced {
+        use super::*;
+        use crate::test_utils::circle_dist_mat;
+        fn replay(route: &Route, moves: &[OrOptMove]) -> Route {
+            let mut indexes = route.indexes.clone();
+            for applied in moves {
+                let segment = indexes
+                    .drain(applied.from..(applied.from + applied.chain_len))
+                    .collect::<Vec<usize>>();
+                indexes.splice(applied.to..applied.to, segment);
+            }
+            Route::new(indexes)
+        }
+        #[test]
+        fn replaying_moves_reproduces_route() {
+            let distance_mat = circle_dist_mat(12);
+            let mut rng = rand::thread_rng();
+            for max_chain in 1..=4 {
+                let route = Route::new(random_permutation_with(
+                    &(0..12).collect::<Vec<usize>>(),
+                    &mut rng,
+                ));
+                let (improved, moves) = route.or_opt_traced(&distance_mat, max_chain);
+                assert_eq!(replay(&route, &moves), improved);
+                assert!(moves
+                    .iter()
+                    .all(|applied| applied.chain_len <= max_chain && applied.delta < 0.0));
+                let total_delta = moves.iter().map(|applied| applied.delta).sum::<f64>();
+                assert!(
+                    (distance_mat.get_distance(&route.indexes) + total_delta
+                        - distance_mat.get_distance(&improved.indexes))
+                    .abs()
+                        < 1e-9
+                );
+            }
+        }
+        #[test]
+        fn default_chain_is_or_opt() {
+            let distance_mat = circle_dist_mat(9);
+            let route = Route::new(vec![0, 6, 7, 8, 1, 2, 3, 4, 5]);
+            assert_eq!(
+                route
+                    .or_opt_traced(&distance_mat, OR_OPT_MAX_SEGMENT_LENGTH)
+                    .0,
+                route.or_opt(&distance_mat)
+            );
+        }
+        #[test]
+        fn no_moves_without_chain() {
+            let route = Route::new(vec![0, 6, 7, 8, 1, 2, 3, 4, 5]);
+            assert_eq!(
+                route.or_opt_traced(&circle_dist_mat(9), 0),
+                (route.clone(), Vec::new())
+            );
+        }
+    }
+    mod test_apply_two_opt {
+        use super::*;
+        #[test]
+        fn reverses_segment() {
+            let mut route = Route::new(vec![0, 1, 2, 3, 4, 5]);
+            route.apply_two_opt(1, 4);
+            assert_eq!(route, Route::new(vec![0, 1, 4, 3, 2, 5]));
+        }
+        #[test]
+        fn applying_twice_restores_route() {
+            let original = Route::new(vec![4, 2, 0, 5, 1, 3]);
+            for first in 0..5 {
+                for second in (first + 1)..6 {
+                    let mut route = original.clone();
+                    route.apply_two_opt(first, second);
+                    route.apply_two_opt(first, second);
+                    assert_eq!(route, original);
+                }
+            }
+        }
+        #[test]
+        fn delta_matches_applied_move() {
+            let distance_mat = crate::test_utils::circle_dist_mat(6);
+            let mut route = Route::new(vec![4, 2, 0, 5, 1, 3]);
+            let distance_before = distance_mat.get_distance(&route.indexes);
+            let delta = two_opt_delta(&distance_mat, &route.indexes, 1, 4);
+            route.apply_two_opt(1, 4);
+            assert!(
+                (distance_mat.get_distance(&route.indexes) - (distance_before + delta)).abs()
+                    < 1e-9
+            );
+        }
+    }
+    mod test_iterated_local_search {
+        use super::*;
+        use crate::test_utils::{circle_dist_mat, valid_permutation};
+        #[test]
+        fn not_worse_than_two_opt() {
+            let distance_mat = circle_dist_mat(15);
+            let mut rng = rand::thread_rng();
+            for _ in 0..10 {
+                let route = Route::new(random_permutation_with(
+                    &(0..15).collect::<Vec<usize>>(),
+                    &mut rand::thread_rng(),
+                ));
+                let searched = route.iterated_local_search(&distance_mat, 20, &mut rng);
+                valid_permutation(&searched.indexes, &route.indexes);
+                assert!(
+                    distance_mat.get_distance(&searched.indexes)
+                        <= distance_mat.get_distance(&route.two_opt(&distance_mat).indexes)
+                );
+            }
+        }
+    }
     mod test_fitness {
         use super::*;
         use crate::test_utils::test_dist_mat;
@@ -1,19 +1,422 @@
-use crate::distance_mat::DistanceMat;
-use crate::gen_traits::{Individual, Population};
+use crate::adaptive::AdaptiveOperators;
+use crate::config::{GaConfig, Interpolation, LocalSearchKind, Schedule, Selection};
+use crate::distance_mat::{DistanceMat, DistanceMatError};
+use crate::gen_traits::{Individual, MultiObjective, Population};
 
 use crate::route::Route;
-use crate::utils::random_permutation;
+pub use crate::route::TourParseError;
+use crate::utils::{
+    random_index_with, random_permutation_with, random_unit_with, shuffle_with,
+    weighted_index_with, XorShiftRng,
+};
 use crossbeam_utils::thread;
-use std::collections::HashSet;
+use rand::Rng;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::convert::From;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::sync::Arc;
 use std::time::Instant;
 
+/// The seed of the random number generator used by `Routes::evolve_deterministic`.
+const DETERMINISTIC_SEED: u64 = 42;
+/// How many tours `Routes::seeded_from_edge_weights` draws per route of the population before it
+/// gives up on finding distinct tours and fills the population with random routes.
+const MAX_SEEDING_ATTEMPTS_PER_ROUTE: usize = 100;
+
 /// The `Population` is your current pools of routes that you would to improve by evolving them.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct Routes {
     /// An individual routes is made from `routes`, e.g. individuals that might your given problem
     /// better of worse.
     routes: HashSet<Route>,
+    /// For how many generations each route survived. The ages are stored in the same order in
+    /// which `routes` is iterated.
+    ages: Vec<usize>,
+    /// The adaptive selection of the operators used by `step` with `GaConfig::adaptive_operators`,
+    /// carried over from generation to generation.
+    operators: AdaptiveOperators,
+}
+/// How the routes that survive into the next generation are chosen from the evolved population.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplacementStrategy {
+    /// Keep the fittest routes.
+    Fittest,
+    /// Keep the fittest routes that are at most `max_age` generations old. Older routes are retired
+    /// regardless of their fitness and replaced by younger ones.
+    AgeBased {
+        /// For how many generations can a route survive?
+        max_age: usize,
+    },
+    /// Let every child compete with the parent it shares the most edges with, and keep the child
+    /// only if it is shorter. The parents are paired at random, so every parent has exactly one
+    /// competitor and the population keeps its size. This preserves niches of similar routes
+    /// without any sharing parameters. `GaConfig::selection` and `GaConfig::elitism` are ignored,
+    /// as the shortest route can only be replaced by a shorter one anyway. Unlike classic
+    /// deterministic crowding, a shorter child that is already part of the population or that
+    /// already won another competition does not replace its parent, as it would not add a route
+    /// and the population would shrink. Outside of `Routes::step` there are no pairs of parents
+    /// and children, so the fittest routes are kept.
+    DeterministicCrowding,
+}
+/// Statistics about a generation that are passed to the callback of `Routes::evolve_with_callback`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerationStats<'a> {
+    /// The generation the statistics belong to, starting at 1 for the first evolved generation.
+    pub generation: usize,
+    /// The length of the shortest route in the generation.
+    pub best_distance: f64,
+    /// The shortest route in the generation, as returned by `Routes::best`.
+    pub best_route: &'a Route,
+    /// For how many generations the shortest route has not been shorter than the shortest route
+    /// of all previous generations, including the initial population. It is 0 whenever the
+    /// generation found a new shortest route.
+    pub generations_since_improvement: usize,
+}
+/// Summary statistics of the round-trip lengths of a population, see `Routes::fitness_stats`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FitnessStats {
+    /// The length of the shortest route.
+    pub best: f64,
+    /// The length of the longest route.
+    pub worst: f64,
+    /// The mean length of the routes.
+    pub mean: f64,
+    /// The standard deviation of the lengths of the routes.
+    pub std: f64,
+}
+
+/// An archive of the shortest distinct routes that were seen during a whole run, not only in the
+/// current generation, so the best route is never lost even if it only existed for a generation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HallOfFame {
+    /// How many routes are kept at most.
+    capacity: usize,
+    /// The kept routes with their lengths, the shortest first.
+    entries: Vec<(Route, f64)>,
+}
+
+impl HallOfFame {
+    /// Create an empty hall of fame that keeps the `capacity` shortest routes.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - How many routes are kept at most.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::HallOfFame;
+    ///
+    /// let hall_of_fame = HallOfFame::new(5);
+    /// assert!(hall_of_fame.entries().is_empty());
+    /// ```
+    pub fn new(capacity: usize) -> Self {
+        HallOfFame {
+            capacity,
+            entries: Vec::new(),
+        }
+    }
+    /// Add the routes of a population that are shorter than the kept ones. Routes that are
+    /// already kept are not added again. Routes of equal length are ordered like in
+    /// `Routes::sorted_by_fitness`.
+    ///
+    /// # Arguments
+    ///
+    /// * `routes` - The population whose routes should be considered.
+    /// * `distance_mat` - The distance matrix the length of the routes is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::{HallOfFame, Routes};
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let mut hall_of_fame = HallOfFame::new(1);
+    /// hall_of_fame.update(&Routes::from(vec![Route::new(vec![0,1]), Route::new(vec![1,2])]), &distance_matrix);
+    /// hall_of_fame.update(&Routes::from(vec![Route::new(vec![0,2])]), &distance_matrix);
+    /// assert_eq!(hall_of_fame.best(), Some(&Route::new(vec![0,1])));
+    /// ```
+    pub fn update(&mut self, routes: &Routes, distance_mat: &DistanceMat) {
+        for (route, distance) in routes.sorted_by_fitness(distance_mat) {
+            if self.entries.iter().any(|(kept, _)| kept == route) {
+                continue;
+            }
+            let position = self.entries.partition_point(|(kept, kept_distance)| {
+                kept_distance
+                    .partial_cmp(&distance)
+                    .unwrap_or(Ordering::Equal)
+                    .then_with(|| kept.cmp(route))
+                    == Ordering::Less
+            });
+            if position < self.capacity {
+                self.entries.insert(position, (route.clone(), distance));
+                self.entries.truncate(self.capacity);
+            }
+        }
+    }
+    /// Get the kept routes with their lengths, the shortest first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::{HallOfFame, Routes};
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let mut hall_of_fame = HallOfFame::new(2);
+    /// hall_of_fame.update(&Routes::from(vec![Route::new(vec![1,2]), Route::new(vec![0,1])]), &distance_matrix);
+    /// assert_eq!(
+    ///     hall_of_fame.entries(),
+    ///     &[(Route::new(vec![0,1]), 2.0), (Route::new(vec![1,2]), 6.0)]
+    /// );
+    /// ```
+    pub fn entries(&self) -> &[(Route, f64)] {
+        &self.entries
+    }
+    /// Get the shortest route that was seen, or `None` if no route was seen yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::HallOfFame;
+    ///
+    /// assert_eq!(HallOfFame::new(3).best(), None);
+    /// ```
+    pub fn best(&self) -> Option<&Route> {
+        self.entries.first().map(|(route, _)| route)
+    }
+}
+
+/// A population that is ordered by the length of its routes in a max-heap, for steady-state runs
+/// that repeatedly replace the longest route. Finding the longest route takes constant time and
+/// replacing it logarithmic time, while `Routes::inject` sorts the whole population every time.
+/// As `Routes`, the heap never contains a route twice.
+#[derive(Debug, Clone)]
+pub struct RoutesHeap {
+    /// The routes with their lengths and ages, the longest route on top.
+    entries: BinaryHeap<HeapEntry>,
+    /// The routes of `entries`, to find duplicates without searching the heap.
+    routes: HashSet<Route>,
+}
+
+/// A route of a `RoutesHeap` with its length and age.
+#[derive(Debug, Clone)]
+struct HeapEntry {
+    /// The length of the route.
+    distance: f64,
+    /// The route itself.
+    route: Route,
+    /// For how many generations the route survived.
+    age: usize,
+}
+
+// Entries are ordered by their length. Routes of the same length are ordered lexicographically,
+// so the longest route on top of the heap does not depend on the order of the insertions.
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then_with(|| self.route.cmp(&other.route))
+    }
+}
+// The partial order is the total order of `Ord`.
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+// Two entries are the same if they are equal in the order of `Ord`.
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for HeapEntry {}
+
+impl RoutesHeap {
+    /// Order the routes of a population by their length, keeping their ages.
+    ///
+    /// # Arguments
+    ///
+    /// * `routes` - The population that is ordered.
+    /// * `distance_mat` - The distance matrix the length of the routes is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::{Routes, RoutesHeap};
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
+    /// assert_eq!(RoutesHeap::new(&routes, &distance_matrix).len(), 2);
+    /// ```
+    pub fn new(routes: &Routes, distance_mat: &DistanceMat) -> Self {
+        RoutesHeap {
+            entries: routes
+                .ages_by_route()
+                .into_iter()
+                .map(|(route, age)| HeapEntry {
+                    distance: route.distance(distance_mat),
+                    route: route.clone(),
+                    age,
+                })
+                .collect(),
+            routes: routes.routes.clone(),
+        }
+    }
+    /// Get the number of routes in the heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::{Routes, RoutesHeap};
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0], vec![1.0,0.0]]);
+    /// assert_eq!(RoutesHeap::new(&Routes::from(vec![]), &distance_matrix).len(), 0);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    /// Check whether the heap has no routes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::{Routes, RoutesHeap};
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0], vec![1.0,0.0]]);
+    /// assert!(RoutesHeap::new(&Routes::from(vec![]), &distance_matrix).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    /// Get the longest route with its length, or `None` if the heap is empty. If several routes
+    /// are the longest, the last one in lexicographic order is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::{Routes, RoutesHeap};
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2,3]), Route::new(vec![0,2,1,3])]);
+    /// let heap = RoutesHeap::new(&routes, &distance_matrix);
+    /// assert_eq!(heap.peek_worst().unwrap().0, &Route::new(vec![0,2,1,3]));
+    /// ```
+    pub fn peek_worst(&self) -> Option<(&Route, f64)> {
+        self.entries
+            .peek()
+            .map(|entry| (&entry.route, entry.distance))
+    }
+    /// Add a route to the heap, which grows by one. Nothing changes if the route is already
+    /// part of the heap.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route that is added, with an age of 0.
+    /// * `distance_mat` - The distance matrix the length of the route is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::{Routes, RoutesHeap};
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let mut heap = RoutesHeap::new(&Routes::from(vec![]), &distance_matrix);
+    /// assert!(heap.insert(Route::new(vec![0,1,2]), &distance_matrix));
+    /// assert!(!heap.insert(Route::new(vec![0,1,2]), &distance_matrix));
+    /// ```
+    pub fn insert(&mut self, route: Route, distance_mat: &DistanceMat) -> bool {
+        if !self.routes.insert(route.clone()) {
+            return false;
+        }
+        self.entries.push(HeapEntry {
+            distance: route.distance(distance_mat),
+            route,
+            age: 0,
+        });
+        true
+    }
+    /// Replace the longest route with `route` and return the replaced route. Nothing changes and
+    /// `None` is returned if the heap is empty or the route is already part of it.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route that replaces the longest route, with an age of 0.
+    /// * `distance_mat` - The distance matrix the length of the route is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::{Routes, RoutesHeap};
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,2,1,3])]);
+    /// let mut heap = RoutesHeap::new(&routes, &distance_matrix);
+    /// let replaced = heap.replace_worst(Route::new(vec![0,1,2,3]), &distance_matrix);
+    /// assert_eq!(replaced, Some(Route::new(vec![0,2,1,3])));
+    /// assert_eq!(heap.peek_worst().unwrap().1, 4.0);
+    /// ```
+    pub fn replace_worst(&mut self, route: Route, distance_mat: &DistanceMat) -> Option<Route> {
+        if self.entries.is_empty() || self.routes.contains(&route) {
+            return None;
+        }
+        let mut worst = self.entries.peek_mut()?;
+        self.routes.remove(&worst.route);
+        self.routes.insert(route.clone());
+        let replaced = std::mem::replace(
+            &mut *worst,
+            HeapEntry {
+                distance: route.distance(distance_mat),
+                route,
+                age: 0,
+            },
+        );
+        Some(replaced.route)
+    }
+    /// Turn the heap back into a population, keeping the ages of the routes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::{Routes, RoutesHeap};
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
+    /// assert_eq!(RoutesHeap::new(&routes, &distance_matrix).into_routes(), routes);
+    /// ```
+    pub fn into_routes(self) -> Routes {
+        Routes::from_aged_routes(
+            self.entries
+                .into_iter()
+                .map(|entry| (entry.route, entry.age))
+                .collect(),
+        )
+    }
+}
+
+// Two populations are the same, if they contain the same routes with the same ages, independent
+// of the order in which they are stored and of what their operators learned.
+impl PartialEq for Routes {
+    fn eq(&self, other: &Self) -> bool {
+        self.routes == other.routes && self.ages_by_route() == other.ages_by_route()
+    }
 }
 // Convert a Vector of solutioons to a routes.
 impl From<Vec<Route>> for Routes {
@@ -22,7 +425,7 @@ impl From<Vec<Route>> for Routes {
     /// # Arguments
     ///
     /// * `routes` - The routes you collected so far and would like to put into your
-    /// routes.
+    ///   routes.
     ///
     /// # Examples
     ///
@@ -33,8 +436,11 @@ impl From<Vec<Route>> for Routes {
     /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
     /// ```
     fn from(routes: Vec<Route>) -> Self {
+        let routes: HashSet<Route> = routes.into_iter().collect();
         Routes {
-            routes: routes.into_iter().collect(),
+            ages: vec![0; routes.len()],
+            routes,
+            operators: AdaptiveOperators::default(),
         }
     }
 }
@@ -56,260 +462,3679 @@ impl Routes {
     /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
     /// ```
     pub fn random(n_routes: usize, route_length: usize) -> Self {
+        Routes::random_with(n_routes, route_length, &mut rand::thread_rng())
+    }
+    /// Create a new Population of random routes, drawn with the given random number generator.
+    /// With a seeded `rng` the same population is created every time.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_routes` - The number of routes your population of routes should contain.
+    /// * `route_length` - The length of an individual route.
+    /// * `rng` - The random number generator the routes are drawn with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// assert_eq!(
+    ///     Routes::random_with(5, 4, &mut StdRng::seed_from_u64(7)),
+    ///     Routes::random_with(5, 4, &mut StdRng::seed_from_u64(7))
+    /// );
+    /// ```
+    pub fn random_with(n_routes: usize, route_length: usize, rng: &mut impl Rng) -> Self {
         let all_objects = (0..route_length).collect::<Vec<usize>>();
         let mut routes = HashSet::new();
 
         while routes.len() < n_routes {
-            routes.insert(Route::new(random_permutation(&all_objects)));
+            routes.insert(Route::new(random_permutation_with(&all_objects, rng)));
         }
 
-        Routes { routes }
+        Routes {
+            ages: vec![0; routes.len()],
+            routes,
+            operators: AdaptiveOperators::default(),
+        }
     }
-}
-
-impl<'a> Population<'a> for Routes {
-    type Individual = Route;
-    type IndividualCollection = std::collections::hash_set::Iter<'a, Route>;
-
-    /// Given your pool of current routes, compute the fitness of your individuals to solve the
-    /// problem at hand.
+    /// Create a population whose tours follow a prior over the edges, e.g. from historical
+    /// solutions. Every tour starts at a random node and repeatedly moves on to an unvisited node
+    /// that is drawn with a probability inversely proportional to the bias of the edge, like a
+    /// randomized nearest-neighbor tour on `edge_bias`. Edges with a bias of 0 or less are always
+    /// preferred over all other edges. If not enough distinct tours are found, the population is
+    /// filled up with random routes.
     ///
     /// # Arguments
     ///
-    /// * `distance_mat` - The distances between nodes that is neccessary to computes how well the route
-    /// work in terms of the TSP
+    /// * `n_nodes` - The number of nodes every tour visits.
+    /// * `population_size` - The number of routes of the population.
+    /// * `edge_bias` - The bias of every edge, lower values are preferred.
+    /// * `rng` - The random number generator the tours are drawn with.
+    ///
+    /// # Panics
+    ///
+    /// If `edge_bias` does not have `n_nodes` nodes or the population cannot have
+    /// `population_size` distinct routes.
     ///
     /// # Examples
     ///
     /// ```
     /// use genetic_algo::routes::Routes;
-    /// use genetic_algo::route::Route;
     /// use genetic_algo::distance_mat::DistanceMat;
-    /// use crate::genetic_algo::gen_traits::Population;
     ///
-    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
-    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
-    /// println!("Your routes's fitnesses: {:?}", routes.fitnesses(&distance_matrix));
+    /// let edge_bias = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+    /// let routes = Routes::seeded_from_edge_weights(4, 5, &edge_bias, &mut rand::thread_rng());
+    /// assert_eq!(routes.ages().len(), 5);
     /// ```
-    // fn fitnesses(&self, distance_mat: &DistanceMat) -> Vec<(f64, &Route)> {
-    //     self.iter()
-    //         .map(|route| (route.fitness(distance_mat), route))
-    //         .collect()
-    // }
-    /// Get the n fittest individuals in your routes as new routes object. This is typically used
-    /// to select the top n inidividuals, before continuing to evolve the routes further.
+    pub fn seeded_from_edge_weights(
+        n_nodes: usize,
+        population_size: usize,
+        edge_bias: &DistanceMat,
+        rng: &mut impl Rng,
+    ) -> Self {
+        assert_eq!(
+            edge_bias.n_units(),
+            n_nodes,
+            "The edge bias has {} nodes, but the routes should have {} nodes.",
+            edge_bias.n_units(),
+            n_nodes
+        );
+        let mut routes = HashSet::new();
+        let mut n_attempts = 0;
+        while routes.len() < population_size
+            && n_attempts < MAX_SEEDING_ATTEMPTS_PER_ROUTE * population_size
+        {
+            routes.insert(biased_tour(edge_bias, rng));
+            n_attempts += 1;
+        }
+        if routes.len() < population_size {
+            let all_objects = (0..n_nodes).collect::<Vec<usize>>();
+            assert!(
+                (1..=n_nodes)
+                    .try_fold(1_usize, |n_routes, n| n_routes.checked_mul(n))
+                    .is_none_or(|n_routes| n_routes >= population_size),
+                "There are less than {} distinct routes with {} nodes.",
+                population_size,
+                n_nodes
+            );
+            while routes.len() < population_size {
+                routes.insert(Route::new(random_permutation_with(&all_objects, rng)));
+            }
+        }
+        Routes {
+            ages: vec![0; routes.len()],
+            routes,
+            operators: AdaptiveOperators::default(),
+        }
+    }
+    /// Read a population from tours that are stored one per line, e.g. as exported by other
+    /// solvers. The nodes of a tour are numbered from 1 to `n_nodes` and separated by whitespace
+    /// or commas. Empty lines are skipped. The same tour on several lines is only kept once.
     ///
     /// # Arguments
     ///
-    /// * `n` - The number of individuals you would like to have.
-    /// * `distance_mat` - The distance matrix the fitness should be evaluated on.
+    /// * `reader` - Where the tours are read from, e.g. a file.
+    /// * `n_nodes` - The number of nodes every tour has to visit.
+    ///
+    /// # Errors
+    ///
+    /// `TourParseError::InvalidNode` or `TourParseError::NotAPermutation` with the number of the
+    /// first line that is not a valid tour, or `TourParseError::Io` if reading fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::gen_traits::Population;
+    ///
+    /// let routes = Routes::from_tour_lines("1 2 3\n3 1 2\n".as_bytes(), 3).unwrap();
+    /// assert_eq!(routes.iter().count(), 2);
+    /// ```
+    pub fn from_tour_lines<R: Read>(reader: R, n_nodes: usize) -> Result<Routes, TourParseError> {
+        let mut routes = Vec::new();
+        for (line_idx, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line.map_err(TourParseError::Io)?;
+            if line
+                .split(|character: char| character.is_whitespace() || character == ',')
+                .all(|token| token.is_empty())
+            {
+                continue;
+            }
+            routes.push(
+                Route::from_one_indexed_str(&line, n_nodes)
+                    .map_err(|error| error.on_line(line_idx + 1))?,
+            );
+        }
+        Ok(Routes::from(routes))
+    }
+    /// Create a new population from routes and the number of generations they survived.
+    ///
+    /// # Arguments
+    ///
+    /// * `aged_routes` - The routes together with their ages.
+    ///
+    fn from_aged_routes(aged_routes: HashMap<Route, usize>) -> Self {
+        let routes = aged_routes.keys().cloned().collect::<HashSet<Route>>();
+        Routes {
+            ages: routes.iter().map(|route| aged_routes[route]).collect(),
+            routes,
+            operators: AdaptiveOperators::default(),
+        }
+    }
+    /// Get the age of every route, keyed by the route.
+    fn ages_by_route(&self) -> HashMap<&Route, usize> {
+        self.routes.iter().zip(self.ages.iter().cloned()).collect()
+    }
+    /// For how many generations did each route survive? Routes are 0 generations old when they
+    /// are created and age by one every time they are carried over into the next generation by
+    /// `evolve`. The ages are returned in the same order in which `iter` returns the routes.
     ///
     /// # Examples
     ///
     /// ```
     /// use genetic_algo::routes::Routes;
     /// use genetic_algo::route::Route;
-    /// use genetic_algo::distance_mat::DistanceMat;
-    /// use crate::genetic_algo::gen_traits::Population;
+    /// use genetic_algo::gen_traits::Population;
     ///
-    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
     /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
-    /// let my_fittest_routes = routes.get_fittest_population(2, &distance_matrix);
+    /// for (route, age) in routes.iter().zip(routes.evolve(0.5).ages()) {
+    ///     println!("{:?} is {} generations old", route, age);
+    /// }
     /// ```
-    fn get_fittest_population(&self, n: usize, distance_mat: &DistanceMat) -> Routes {
-        Routes::from(self.get_n_fittest(n, distance_mat))
+    pub fn ages(&self) -> &[usize] {
+        &self.ages
     }
-    /// Evolve your population.
+    /// Get the adaptive selection of the operators `step` uses with `GaConfig::adaptive_operators`,
+    /// together with everything it learned in the previous generations. A new population starts
+    /// with `AdaptiveOperators::default`.
     ///
-    /// The evolution consists of the following stages:
-    /// 1) `crossover` between all 1,...,n routes excluding the route itself.
-    /// 2) `mutate` is applied to all individuals.
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::adaptive::AdaptiveOperators;
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::config::GaConfig;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.5, 2.0)]);
+    /// let config = GaConfig {
+    ///     population_size: 10,
+    ///     adaptive_operators: true,
+    ///     ..GaConfig::default()
+    /// };
+    /// let mut routes = Routes::random(10, 5);
+    /// assert_eq!(routes.operators(), &AdaptiveOperators::default());
+    /// for _ in 0..5 {
+    ///     routes = routes.step(&distance_matrix, &config, &mut rand::thread_rng());
+    /// }
+    /// println!("{:?}", routes.operators().probabilities());
+    /// ```
+    pub fn operators(&self) -> &AdaptiveOperators {
+        &self.operators
+    }
+    /// Replace the adaptive selection of the operators that `step` uses, e.g. to register the
+    /// crossover operators it should choose from.
     ///
     /// # Arguments
     ///
-    /// * `mutate_prob` - The probabilty of an inviduals beeing mutated. Is applied via `individuals.mutate`.
+    /// * `operators` - The adaptive selection of the mutation and crossover operators.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::adaptive::AdaptiveOperators;
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::route::{FixedDepotCrossover, OrderedCrossover};
+    /// use std::sync::Arc;
+    ///
+    /// let routes = Routes::random(10, 5).with_operators(
+    ///     AdaptiveOperators::default()
+    ///         .with_crossovers(vec![Arc::new(OrderedCrossover), Arc::new(FixedDepotCrossover { depot: 0 })]),
+    /// );
+    /// assert_eq!(routes.operators().crossovers().len(), 2);
+    /// ```
+    pub fn with_operators(mut self, operators: AdaptiveOperators) -> Self {
+        self.operators = operators;
+        self
+    }
+    /// Get all routes of the population together with their round-trip length, sorted from the
+    /// shortest to the longest route. Routes of the same length are sorted by their lexicographic
+    /// order, so the result does not depend on how the routes are stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the length of the routes is computed on.
     ///
     /// # Examples
     ///
     /// ```
     /// use genetic_algo::routes::Routes;
     /// use genetic_algo::route::Route;
-    /// use crate::genetic_algo::gen_traits::Population;
     /// use genetic_algo::distance_mat::DistanceMat;
     ///
     /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
-    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
-    /// let evolved_routes = routes.evolve(0.5);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0])]);
+    /// for (route, distance) in routes.sorted_by_fitness(&distance_matrix) {
+    ///     println!("{:?}: {}", route, distance);
+    /// }
     /// ```
-    fn evolve(&self, mutate_prob: f32) -> Routes {
+    pub fn sorted_by_fitness(&self, distance_mat: &DistanceMat) -> Vec<(&Route, f64)> {
+        let mut routes_by_distance = self
+            .routes
+            .iter()
+            .map(|route| (route, distance_mat.get_distance(&route.indexes)))
+            .collect::<Vec<(&Route, f64)>>();
+        routes_by_distance.sort_by(|(route_a, distance_a), (route_b, distance_b)| {
+            distance_a
+                .partial_cmp(distance_b)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| route_a.cmp(route_b))
+        });
+        routes_by_distance
+    }
+    /// Draw `k` distinct routes uniformly at random with reservoir sampling in one pass, without
+    /// cloning the population. If the population has fewer than `k` routes, all of them are
+    /// returned. The routes are visited in the order in which they are stored, so the same seed
+    /// draws the same routes from a population and its clones, but not necessarily from another
+    /// population that contains the same routes.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - How many routes should be drawn.
+    /// * `rng` - The random number generator used for drawing the routes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    ///
+    /// let routes = Routes::random(10, 5);
+    /// assert_eq!(routes.sample(3, &mut rand::thread_rng()).len(), 3);
+    /// assert_eq!(routes.sample(20, &mut rand::thread_rng()).len(), 10);
+    /// ```
+    pub fn sample(&self, k: usize, rng: &mut impl Rng) -> Vec<&Route> {
+        let mut reservoir = Vec::with_capacity(k.min(self.routes.len()));
+        for (idx, route) in self.routes.iter().enumerate() {
+            if idx < k {
+                reservoir.push(route);
+            } else {
+                let replaced = random_index_with(0..(idx + 1), rng);
+                if replaced < k {
+                    reservoir[replaced] = route;
+                }
+            }
+        }
+        reservoir
+    }
+    /// Get the shortest route of the population. If several routes are the shortest, the first
+    /// one in lexicographic order is returned. An empty population has no best route.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the length of the routes is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0])]);
+    /// assert_eq!(routes.best(&distance_matrix), Some(&Route::new(vec![1,0])));
+    /// ```
+    pub fn best(&self, distance_mat: &DistanceMat) -> Option<&Route> {
+        self.sorted_by_fitness(distance_mat)
+            .into_iter()
+            .next()
+            .map(|(route, _)| route)
+    }
+    /// Measure how different the routes of the population are by the edges they use. Returns 0
+    /// if all routes use the same edges, e.g. if they are rotations or reversals of the same
+    /// round-trip, and 1 if the routes share as few edges as possible. All routes need to have
+    /// the same length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::route::Route;
+    ///
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2,3]), Route::new(vec![1,2,3,0])]);
+    /// assert_eq!(routes.edge_diversity(), 0.0);
+    /// ```
+    pub fn edge_diversity(&self) -> f64 {
+        let n_nodes = match self.routes.iter().next() {
+            Some(route) => route.indexes.len(),
+            None => return 0.0,
+        };
+        let distinct_edges = self
+            .routes
+            .iter()
+            .flat_map(|route| {
+                route
+                    .indexes
+                    .iter()
+                    .zip(route.indexes.iter().cycle().skip(1))
+                    .map(|(from, to)| (*from.min(to), *from.max(to)))
+            })
+            .collect::<HashSet<(usize, usize)>>()
+            .len();
+        let max_distinct_edges =
+            (n_nodes * self.routes.len()).min(n_nodes * n_nodes.saturating_sub(1) / 2);
+        if max_distinct_edges <= n_nodes {
+            return 0.0;
+        }
+        (distinct_edges.saturating_sub(n_nodes)) as f64 / (max_distinct_edges - n_nodes) as f64
+    }
+    /// Get the size of the tournaments the parents are currently chosen with: the size adapted
+    /// to the diversity of the population if `config.adaptive_selection` is set, the size of
+    /// `Selection::Tournament` otherwise. Returns `None` if the parents are not chosen with
+    /// tournaments.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The parameters of the genetic algorithm.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::config::{AdaptiveSelectionParams, GaConfig};
+    ///
+    /// let config = GaConfig {
+    ///     adaptive_selection: Some(AdaptiveSelectionParams { min_size: 2, max_size: 8 }),
+    ///     ..GaConfig::default()
+    /// };
+    /// let tournament_size = Routes::random(10, 20).effective_tournament_size(&config);
+    /// ```
+    pub fn effective_tournament_size(&self, config: &GaConfig) -> Option<usize> {
+        match (config.adaptive_selection, &config.selection) {
+            (Some(params), _) => Some(params.tournament_size(self.edge_diversity())),
+            (None, Selection::Tournament { size }) => Some(*size),
+            (None, _) => None,
+        }
+    }
+    /// Get the best, worst and mean round-trip length of the population and its standard
+    /// deviation, or `None` if the population is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the length of the routes is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::{FitnessStats, Routes};
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1]), Route::new(vec![0,2])]);
+    /// assert_eq!(
+    ///     routes.fitness_stats(&distance_matrix),
+    ///     Some(FitnessStats { best: 2.0, worst: 4.0, mean: 3.0, std: 1.0 })
+    /// );
+    /// ```
+    pub fn fitness_stats(&self, distance_mat: &DistanceMat) -> Option<FitnessStats> {
+        if self.routes.is_empty() {
+            return None;
+        }
+        let distances = self
+            .routes
+            .iter()
+            .map(|route| distance_mat.get_distance(&route.indexes))
+            .collect::<Vec<f64>>();
+        let n_routes = distances.len() as f64;
+        let mean = distances.iter().sum::<f64>() / n_routes;
+        Some(FitnessStats {
+            best: distances.iter().cloned().fold(f64::INFINITY, f64::min),
+            worst: distances.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+            mean,
+            std: (distances
+                .iter()
+                .map(|distance| (distance - mean).powi(2))
+                .sum::<f64>()
+                / n_routes)
+                .sqrt(),
+        })
+    }
+    /// Get the round-trip length at every percentile in `ps`, e.g. 0.5 for the median length.
+    /// Percentiles between two routes are interpolated linearly between their lengths.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the length of the routes is computed on.
+    /// * `ps` - The percentiles, each between 0 (the shortest route) and 1 (the longest route).
+    ///
+    /// # Panics
+    ///
+    /// If the population is empty or a percentile is not between 0 and 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1]), Route::new(vec![0,2])]);
+    /// assert_eq!(routes.fitness_percentiles(&distance_matrix, &[0.0, 0.5, 1.0]), vec![2.0, 3.0, 4.0]);
+    /// ```
+    pub fn fitness_percentiles(&self, distance_mat: &DistanceMat, ps: &[f64]) -> Vec<f64> {
+        assert!(
+            !self.routes.is_empty(),
+            "An empty population has no percentiles."
+        );
+        let distances = self
+            .sorted_by_fitness(distance_mat)
+            .into_iter()
+            .map(|(_, distance)| distance)
+            .collect::<Vec<f64>>();
+        ps.iter()
+            .map(|p| {
+                assert!(
+                    (0.0..=1.0).contains(p),
+                    "The percentile {} is not between 0 and 1.",
+                    p
+                );
+                let position = p * (distances.len() - 1) as f64;
+                let lower = position.floor() as usize;
+                let upper = position.ceil() as usize;
+                distances[lower] + (position - lower as f64) * (distances[upper] - distances[lower])
+            })
+            .collect()
+    }
+    /// Compute the crowding distance of the routes in a front, as used by NSGA-II to prefer routes
+    /// in sparsely populated regions of the front. For every objective the routes are sorted and
+    /// each route adds the normalised distance between its two neighbours. The routes with the
+    /// lowest and highest value of any objective get an infinite distance.
+    ///
+    /// # Arguments
+    ///
+    /// * `objs` - The objectives the routes are judged on.
+    /// * `front` - The routes in the front. The crowding distances are returned in this order.
+    ///
+    /// # Panics
+    ///
+    /// If a route in `front` is not part of the population.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let objectives = vec![
+    ///     DistanceMat::new(vec![vec![0.0, 1.0, 2.0], vec![1.0, 0.0, 3.0], vec![2.0, 3.0, 0.0]]),
+    ///     DistanceMat::new(vec![vec![0.0, 3.0, 2.0], vec![3.0, 0.0, 1.0], vec![2.0, 1.0, 0.0]]),
+    /// ];
+    /// let front = vec![Route::new(vec![0, 1]), Route::new(vec![1, 2])];
+    /// let routes = Routes::from(front.clone());
+    /// let distances = routes.crowding_distances(&objectives, &[&front[0], &front[1]]);
+    /// assert!(distances.iter().all(|distance| distance.is_infinite()));
+    /// ```
+    pub fn crowding_distances(
+        &self,
+        objs: &impl MultiObjective<Individual = Route>,
+        front: &[&Route],
+    ) -> Vec<f64> {
+        let objectives = front
+            .iter()
+            .map(|route| {
+                assert!(
+                    self.routes.contains(*route),
+                    "The route {:?} of the front is not part of the population.",
+                    route.indexes
+                );
+                objs.objectives(route)
+            })
+            .collect::<Vec<Vec<f64>>>();
+        // The values of each objective over the routes in the front.
+        let n_objectives = objectives.first().map_or(0, |objective| objective.len());
+        let values_by_objective = (0..n_objectives).map(|objective| {
+            objectives
+                .iter()
+                .map(|route_objectives| route_objectives[objective])
+                .collect::<Vec<f64>>()
+        });
+        let mut distances = vec![0.0; front.len()];
+        for values in values_by_objective {
+            let mut by_value = (0..front.len()).collect::<Vec<usize>>();
+            by_value.sort_by(|a, b| {
+                values[*a]
+                    .partial_cmp(&values[*b])
+                    .unwrap_or(Ordering::Equal)
+            });
+            let (first, last) = (by_value[0], by_value[front.len() - 1]);
+            distances[first] = f64::INFINITY;
+            distances[last] = f64::INFINITY;
+            let range = values[last] - values[first];
+            if range <= 0.0 {
+                continue;
+            }
+            for neighbours in by_value.windows(3) {
+                distances[neighbours[1]] += (values[neighbours[2]] - values[neighbours[0]]) / range;
+            }
+        }
+        distances
+    }
+    /// Select the routes that survive into the next generation, typically applied after `evolve`.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of routes that should survive.
+    /// * `distance_mat` - The distance matrix the fitness of the routes is computed on.
+    /// * `replacement` - How the surviving routes are chosen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::{ReplacementStrategy, Routes};
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    /// use genetic_algo::gen_traits::Population;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
+    /// let next_generation = routes
+    ///     .evolve(0.5)
+    ///     .select_survivors(2, &distance_matrix, &ReplacementStrategy::AgeBased { max_age: 5 });
+    /// ```
+    pub fn select_survivors(
+        &self,
+        n: usize,
+        distance_mat: &DistanceMat,
+        replacement: &ReplacementStrategy,
+    ) -> Routes {
+        match replacement {
+            ReplacementStrategy::Fittest => self.get_fittest_population(n, distance_mat),
+            ReplacementStrategy::AgeBased { max_age } => Routes::from_aged_routes(
+                self.ages_by_route()
+                    .into_iter()
+                    .filter(|(_, age)| age <= max_age)
+                    .map(|(route, age)| (route.clone(), age))
+                    .collect(),
+            )
+            .get_fittest_population(n, distance_mat),
+            ReplacementStrategy::DeterministicCrowding => {
+                self.get_fittest_population(n, distance_mat)
+            }
+        }
+    }
+    /// Choose the pairs of parents the offspring is created from, as configured by
+    /// `config.selection`.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the fitness of the routes is computed on.
+    /// * `config` - The parameters of the genetic algorithm.
+    /// * `rng` - The random number generator used for the selection.
+    ///
+    fn select_parents(
+        &self,
+        distance_mat: &DistanceMat,
+        config: &GaConfig,
+        rng: &mut impl Rng,
+    ) -> Vec<(&Route, &Route)> {
+        // Sort the parents, so that a seeded `rng` always leads to the same selection independent
+        // of the order in which the routes are stored.
+        let mut parents = self.routes.iter().collect::<Vec<&Route>>();
+        parents.sort();
+        let selection = match self.effective_tournament_size(config) {
+            Some(size) => Selection::Tournament { size },
+            None => config.selection.clone(),
+        };
+        match selection {
+            Selection::AllPairs => parents
+                .iter()
+                .enumerate()
+                .flat_map(|(idx, parent_a)| {
+                    parents
+                        .iter()
+                        .skip(idx + 1)
+                        .map(move |parent_b| (*parent_a, *parent_b))
+                })
+                .collect(),
+            Selection::Proportionate => {
+                if parents.is_empty() {
+                    return Vec::new();
+                }
+                let weights = config.fitness_scaling.scale(
+                    &parents
+                        .iter()
+                        .map(|parent| 1.0 / distance_mat.get_distance(&parent.indexes))
+                        .collect::<Vec<f64>>(),
+                );
+                // If no parent has a positive weight, every parent is equally likely.
+                let mut pick = || {
+                    weighted_index_with(&weights, rng)
+                        .unwrap_or_else(|| random_index_with(0..parents.len(), rng))
+                };
+                (0..config.population_size.div_ceil(2))
+                    .map(|_| (parents[pick()], parents[pick()]))
+                    .collect()
+            }
+            Selection::Tournament { size } => {
+                if parents.is_empty() {
+                    return Vec::new();
+                }
+                let distances = parents
+                    .iter()
+                    .map(|parent| distance_mat.get_distance(&parent.indexes))
+                    .collect::<Vec<f64>>();
+                // The parents are sorted, so ties are won by the first route in lexicographic order.
+                let mut tournament = || {
+                    (0..size.max(1))
+                        .map(|_| random_index_with(0..parents.len(), rng))
+                        .min_by(|a, b| {
+                            distances[*a]
+                                .partial_cmp(&distances[*b])
+                                .unwrap_or(Ordering::Equal)
+                                .then_with(|| a.cmp(b))
+                        })
+                        .map(|winner| parents[winner])
+                        .unwrap()
+                };
+                (0..config.population_size.div_ceil(2))
+                    .map(|_| (tournament(), tournament()))
+                    .collect()
+            }
+        }
+    }
+    /// Create the offspring of the population: the pairs of parents are chosen as configured by
+    /// `config.selection` and are `crossover`ed into two children with a probability of
+    /// `config.crossover_rate`, otherwise the children are copies of the two parents. Afterwards
+    /// the children are mutated with `config.mutation_rate` and refined with `config.local_search`.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the fitness of the routes is computed on.
+    /// * `config` - The parameters of the genetic algorithm.
+    /// * `rng` - The random number generator used for selection, crossover and mutation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::config::GaConfig;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2,3]), Route::new(vec![1,3,0,2])]);
+    /// let offspring = routes.reproduce(&distance_matrix, &GaConfig::default(), &mut rand::thread_rng());
+    /// ```
+    pub fn reproduce(
+        &self,
+        distance_mat: &DistanceMat,
+        config: &GaConfig,
+        rng: &mut impl Rng,
+    ) -> Routes {
+        self.reproduce_with_operators(distance_mat, config, &mut self.operators.clone(), rng)
+    }
+    /// Create the offspring like `reproduce`, but with `config.adaptive_operators` the mutations
+    /// and crossovers are chosen by `operators` and the improvements they achieve are recorded in
+    /// it.
+    fn reproduce_with_operators(
+        &self,
+        distance_mat: &DistanceMat,
+        config: &GaConfig,
+        operators: &mut AdaptiveOperators,
+        rng: &mut impl Rng,
+    ) -> Routes {
+        let mut offspring = Vec::new();
+        for (parent_a, parent_b) in self.select_parents(distance_mat, config, rng) {
+            let (child_a, child_b) =
+                create_children(parent_a, parent_b, distance_mat, config, operators, rng);
+            offspring.push(child_a);
+            offspring.push(child_b);
+        }
+        Routes::from(offspring)
+    }
+    /// Evolve the population by exactly one generation: the offspring is created with
+    /// `reproduce`, then the `config.elitism` fittest routes survive and the remaining routes of
+    /// the next generation are chosen from the current routes and their offspring with
+    /// `config.replacement`. Use this to drive your own evolution loop.
+    ///
+    /// With `config.adaptive_operators` the operators are chosen by the `operators` of the
+    /// population, and what they learn in this generation is carried over into the next one.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the fitness of the routes is computed on.
+    /// * `config` - The parameters of the genetic algorithm.
+    /// * `rng` - The random number generator used for selection, crossover and mutation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::config::GaConfig;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+    /// let config = GaConfig {
+    ///     population_size: 10,
+    ///     elitism: 1,
+    ///     ..GaConfig::default()
+    /// };
+    /// let mut routes = Routes::random(10, 4);
+    /// for generation in 0..5 {
+    ///     routes = routes.step(&distance_matrix, &config, &mut rand::thread_rng());
+    ///     println!("{}: {:?}", generation, routes.best(&distance_matrix));
+    /// }
+    /// ```
+    pub fn step(
+        &self,
+        distance_mat: &DistanceMat,
+        config: &GaConfig,
+        rng: &mut impl Rng,
+    ) -> Routes {
+        self.step_with_operators(distance_mat, config, &mut self.operators.clone(), rng)
+    }
+    /// Evolve the population by exactly one generation like `step`, but with the given
+    /// `operators` instead of the ones of the population. With `config.adaptive_operators` the
+    /// children are created and mutated with the operators chosen by `operators`, which
+    /// afterwards learns from the improvements of this generation and is carried over into the
+    /// next generation. Without `config.adaptive_operators` the `operators` are not used.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the fitness of the routes is computed on.
+    /// * `config` - The parameters of the genetic algorithm.
+    /// * `operators` - The adaptive selection of the mutation and crossover operators.
+    /// * `rng` - The random number generator used for selection, crossover and mutation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::adaptive::AdaptiveOperators;
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::config::GaConfig;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.5, 2.0)]);
+    /// let config = GaConfig {
+    ///     population_size: 10,
+    ///     adaptive_operators: true,
+    ///     ..GaConfig::default()
+    /// };
+    /// let mut operators = AdaptiveOperators::default();
+    /// let mut routes = Routes::random(10, 5);
+    /// for _ in 0..5 {
+    ///     routes = routes.step_with_operators(&distance_matrix, &config, &mut operators, &mut rand::thread_rng());
+    /// }
+    /// println!("{:?}", operators.probabilities());
+    /// ```
+    pub fn step_with_operators(
+        &self,
+        distance_mat: &DistanceMat,
+        config: &GaConfig,
+        operators: &mut AdaptiveOperators,
+        rng: &mut impl Rng,
+    ) -> Routes {
+        self.step_observing_offspring(distance_mat, config, operators, &mut |_| (), rng)
+    }
+    /// Evolve the population by one generation like `step_with_operators` and call
+    /// `on_offspring` with all children of this generation, also those that do not survive.
+    fn step_observing_offspring(
+        &self,
+        distance_mat: &DistanceMat,
+        config: &GaConfig,
+        operators: &mut AdaptiveOperators,
+        on_offspring: &mut impl FnMut(&Routes),
+        rng: &mut impl Rng,
+    ) -> Routes {
+        let next_generation = if config.replacement == ReplacementStrategy::DeterministicCrowding {
+            self.crowding_step(distance_mat, config, operators, on_offspring, rng)
+        } else {
+            self.replacement_step(distance_mat, config, operators, on_offspring, rng)
+        };
+        operators.end_generation();
         Routes {
-            routes: HashSet::from_iter(self.evolve_individuals(mutate_prob).into_iter()),
+            operators: operators.clone(),
+            ..next_generation
+        }
+    }
+    /// Evolve the population by one generation with the `config.replacement` of the offspring.
+    fn replacement_step(
+        &self,
+        distance_mat: &DistanceMat,
+        config: &GaConfig,
+        operators: &mut AdaptiveOperators,
+        on_offspring: &mut impl FnMut(&Routes),
+        rng: &mut impl Rng,
+    ) -> Routes {
+        let offspring = self.reproduce_with_operators(distance_mat, config, operators, rng);
+        on_offspring(&offspring);
+        let mut aged_routes = offspring
+            .routes
+            .into_iter()
+            .map(|route| (route, 0))
+            .collect::<HashMap<Route, usize>>();
+        // The current routes are carried over into the next generation and therefore age by one.
+        let ages_by_route = self.ages_by_route();
+        for (route, age) in ages_by_route.iter() {
+            aged_routes.insert((*route).clone(), age + 1);
+        }
+        let elites = self
+            .sorted_by_fitness(distance_mat)
+            .into_iter()
+            .take(config.elitism.min(config.population_size))
+            .map(|(route, _)| (route.clone(), ages_by_route[route] + 1))
+            .collect::<Vec<(Route, usize)>>();
+        for (elite, _) in elites.iter() {
+            aged_routes.remove(elite);
+        }
+        let mut survivors = Routes::from_aged_routes(aged_routes)
+            .select_survivors(
+                config.population_size - elites.len(),
+                distance_mat,
+                &config.replacement,
+            )
+            .ages_by_route()
+            .into_iter()
+            .map(|(route, age)| (route.clone(), age))
+            .collect::<HashMap<Route, usize>>();
+        survivors.extend(elites);
+        Routes::from_aged_routes(survivors)
+    }
+    /// Evolve the population by one generation with `ReplacementStrategy::DeterministicCrowding`.
+    fn crowding_step(
+        &self,
+        distance_mat: &DistanceMat,
+        config: &GaConfig,
+        operators: &mut AdaptiveOperators,
+        on_offspring: &mut impl FnMut(&Routes),
+        rng: &mut impl Rng,
+    ) -> Routes {
+        let ages_by_route = self.ages_by_route();
+        let competitions = self.crowding_competitions(distance_mat, config, operators, rng);
+        on_offspring(&Routes::from(
+            competitions
+                .iter()
+                .filter_map(|(_, child)| child.clone())
+                .collect::<Vec<Route>>(),
+        ));
+        let mut next_generation = HashMap::new();
+        for (parent, child) in competitions {
+            // A child that is already part of the population would not add a route, so the
+            // parent survives instead to keep the size of the population, even if the child is
+            // shorter.
+            match child.filter(|child| {
+                !self.routes.contains(child) && !next_generation.contains_key(child)
+            }) {
+                Some(child) if crowding_winner(parent, &child, distance_mat) == &child => {
+                    next_generation.insert(child, 0);
+                }
+                _ => {
+                    next_generation.insert(parent.clone(), ages_by_route[parent] + 1);
+                }
+            }
+        }
+        Routes::from_aged_routes(next_generation)
+    }
+    /// Pair the parents at random and let each of them compete with the child of its pair it
+    /// shares more edges with. A parent without a partner has no competitor.
+    fn crowding_competitions(
+        &self,
+        distance_mat: &DistanceMat,
+        config: &GaConfig,
+        operators: &mut AdaptiveOperators,
+        rng: &mut impl Rng,
+    ) -> Vec<(&Route, Option<Route>)> {
+        // Sort the parents before shuffling them, so that a seeded `rng` always leads to the same
+        // pairs independent of the order in which the routes are stored.
+        let mut parents = self.routes.iter().collect::<Vec<&Route>>();
+        parents.sort();
+        shuffle_with(&mut parents, rng);
+        let mut competitions = Vec::with_capacity(parents.len());
+        for pair in parents.chunks(2) {
+            match pair {
+                [parent_a, parent_b] => {
+                    let (child_a, child_b) =
+                        create_children(parent_a, parent_b, distance_mat, config, operators, rng);
+                    if shared_edges(parent_a, &child_a) + shared_edges(parent_b, &child_b)
+                        >= shared_edges(parent_a, &child_b) + shared_edges(parent_b, &child_a)
+                    {
+                        competitions
+                            .extend([(*parent_a, Some(child_a)), (*parent_b, Some(child_b))]);
+                    } else {
+                        competitions
+                            .extend([(*parent_a, Some(child_b)), (*parent_b, Some(child_a))]);
+                    }
+                }
+                // Without a partner the parent survives unchallenged.
+                _ => competitions.push((pair[0], None)),
+            }
+        }
+        competitions
+    }
+    /// Check that every route of the population can be used with `distance_mat`, see
+    /// `DistanceMat::is_compatible`. The evolution methods, `evolve_population`,
+    /// `evolve_population_with_replacement` and `IslandModel::evolve` check this once before the
+    /// first generation, so a population created for a matrix of another size is reported as an
+    /// error. Only `Population::evolve` knows no distance matrix and cannot check it.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the routes should be used with.
+    ///
+    /// # Errors
+    ///
+    /// `DistanceMatError::IncompatibleRoute` with the smallest route that is not compatible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert!(Routes::random(3, 3).check_compatible(&distance_matrix).is_ok());
+    /// assert!(Routes::random(3, 4).check_compatible(&distance_matrix).is_err());
+    /// ```
+    pub fn check_compatible(&self, distance_mat: &DistanceMat) -> Result<(), DistanceMatError> {
+        match self
+            .routes
+            .iter()
+            .filter(|route| !distance_mat.is_compatible(route))
+            .min()
+        {
+            Some(route) => Err(DistanceMatError::IncompatibleRoute {
+                route: route.indexes.clone(),
+                n_units: distance_mat.n_units(),
+            }),
+            None => Ok(()),
+        }
+    }
+    /// Evolve the population until its shortest route is at most `target` long, but for at most
+    /// `max_generations` generations. Returns the final population and the generation in which the
+    /// target was reached first, where generation 0 is the population itself. If the target was not
+    /// reached, `None` is returned instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the fitness of the routes is computed on.
+    /// * `config` - The parameters of the genetic algorithm.
+    /// * `target` - The round-trip length at which the evolution can stop.
+    /// * `max_generations` - After how many generations should the evolution stop at the latest?
+    /// * `rng` - The random number generator used for crossover and mutation.
+    ///
+    /// # Errors
+    ///
+    /// If a route is not compatible with `distance_mat`, see `Routes::check_compatible`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::config::GaConfig;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let (evolved_routes, reached_in) = Routes::random(3, 3).evolve_to_target(
+    ///     &distance_matrix,
+    ///     &GaConfig::default(),
+    ///     6.0,
+    ///     10,
+    ///     &mut rand::thread_rng(),
+    /// ).unwrap();
+    /// ```
+    pub fn evolve_to_target(
+        &self,
+        distance_mat: &DistanceMat,
+        config: &GaConfig,
+        target: f64,
+        max_generations: usize,
+        rng: &mut impl Rng,
+    ) -> Result<(Routes, Option<usize>), DistanceMatError> {
+        self.check_compatible(distance_mat)?;
+        let reached_target = |routes: &Routes| {
+            routes
+                .routes
+                .iter()
+                .any(|route| distance_mat.get_distance(&route.indexes) <= target)
+        };
+        let mut routes = self.clone();
+        let mut operators = self.operators.clone();
+        if reached_target(&routes) {
+            return Ok((routes, Some(0)));
+        }
+        for generation in 1..=max_generations {
+            routes = routes.step_with_operators(distance_mat, config, &mut operators, rng);
+            if reached_target(&routes) {
+                return Ok((routes, Some(generation)));
+            }
+        }
+        Ok((routes, None))
+    }
+    /// Evolve the population for `generations` generations with a random number generator that is
+    /// implemented in this crate and always seeded the same. The generator and the way the
+    /// selection, crossover and mutation draw from it never change with new versions of `rand`,
+    /// unlike `StdRng`, so this is meant for reproducible runs like golden tests in CI. The
+    /// generator has no cryptographic quality.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the fitness of the routes is computed on.
+    /// * `config` - The parameters of the genetic algorithm.
+    /// * `generations` - For how many generations should the population be evolved?
+    ///
+    /// # Errors
+    ///
+    /// If a route is not compatible with `distance_mat`, see `Routes::check_compatible`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::config::GaConfig;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::random(3, 3);
+    /// assert_eq!(
+    ///     routes.evolve_deterministic(&distance_matrix, &GaConfig::default(), 5).unwrap(),
+    ///     routes.evolve_deterministic(&distance_matrix, &GaConfig::default(), 5).unwrap()
+    /// );
+    /// ```
+    pub fn evolve_deterministic(
+        &self,
+        distance_mat: &DistanceMat,
+        config: &GaConfig,
+        generations: usize,
+    ) -> Result<Routes, DistanceMatError> {
+        self.check_compatible(distance_mat)?;
+        let mut rng = XorShiftRng::new(DETERMINISTIC_SEED);
+        let mut routes = self.clone();
+        let mut operators = self.operators.clone();
+        for _ in 0..generations {
+            routes = routes.step_with_operators(distance_mat, config, &mut operators, &mut rng);
+        }
+        Ok(routes)
+    }
+    /// Evolve the population for `generations` generations and call `callback` with the statistics
+    /// of every generation, e.g. to log the progress or to display the current best route.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the fitness of the routes is computed on.
+    /// * `config` - The parameters of the genetic algorithm.
+    /// * `generations` - For how many generations should the population be evolved?
+    /// * `rng` - The random number generator used for crossover and mutation.
+    /// * `callback` - Is called after every generation with the statistics of that generation.
+    ///
+    /// # Errors
+    ///
+    /// If a route is not compatible with `distance_mat`, see `Routes::check_compatible`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::config::GaConfig;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let evolved_routes = Routes::random(3, 3).evolve_with_callback(
+    ///     &distance_matrix,
+    ///     &GaConfig::default(),
+    ///     10,
+    ///     &mut rand::thread_rng(),
+    ///     |stats| println!("{}: {} {:?}", stats.generation, stats.best_distance, stats.best_route),
+    /// ).unwrap();
+    /// ```
+    pub fn evolve_with_callback(
+        &self,
+        distance_mat: &DistanceMat,
+        config: &GaConfig,
+        generations: usize,
+        rng: &mut impl Rng,
+        mut callback: impl FnMut(&GenerationStats),
+    ) -> Result<Routes, DistanceMatError> {
+        self.check_compatible(distance_mat)?;
+        let mut shortest_distance = self.best(distance_mat).map_or(f64::INFINITY, |route| {
+            distance_mat.get_distance(&route.indexes)
+        });
+        let mut generations_since_improvement = 0;
+        let mut routes = self.clone();
+        let mut operators = self.operators.clone();
+        for generation in 1..=generations {
+            routes = routes.step_with_operators(distance_mat, config, &mut operators, rng);
+            if let Some(best_route) = routes.best(distance_mat) {
+                let best_distance = distance_mat.get_distance(&best_route.indexes);
+                if best_distance < shortest_distance {
+                    shortest_distance = best_distance;
+                    generations_since_improvement = 0;
+                } else {
+                    generations_since_improvement += 1;
+                }
+                callback(&GenerationStats {
+                    generation,
+                    best_distance,
+                    best_route,
+                    generations_since_improvement,
+                });
+            }
+        }
+        Ok(routes)
+    }
+    /// Evolve the population for `generations` generations with parameters that move linearly
+    /// from `start_config` in the first generation to `end_config` in the last one, see
+    /// `Schedule`. For example, a high mutation rate and small tournaments at the start explore
+    /// the search space, while a low mutation rate and large tournaments at the end exploit the
+    /// best routes found.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the fitness of the routes is computed on.
+    /// * `start_config` - The parameters of the first generation.
+    /// * `end_config` - The parameters of the last generation.
+    /// * `generations` - For how many generations should the population be evolved?
+    /// * `rng` - The random number generator used for crossover and mutation.
+    ///
+    /// # Errors
+    ///
+    /// If a route is not compatible with `distance_mat`, see `Routes::check_compatible`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::config::{GaConfig, Selection};
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let explore = GaConfig { mutation_rate: 0.9, selection: Selection::Tournament { size: 2 }, ..GaConfig::default() };
+    /// let exploit = GaConfig { mutation_rate: 0.1, selection: Selection::Tournament { size: 5 }, ..GaConfig::default() };
+    /// let evolved_routes = Routes::random(3, 3)
+    ///     .evolve_scheduled(&distance_matrix, &explore, &exploit, 10, &mut rand::thread_rng())
+    ///     .unwrap();
+    /// ```
+    pub fn evolve_scheduled(
+        &self,
+        distance_mat: &DistanceMat,
+        start_config: &GaConfig,
+        end_config: &GaConfig,
+        generations: usize,
+        rng: &mut impl Rng,
+    ) -> Result<Routes, DistanceMatError> {
+        self.check_compatible(distance_mat)?;
+        let schedule = Schedule {
+            start: start_config.clone(),
+            end: end_config.clone(),
+            interpolation: Interpolation::Linear,
+        };
+        let mut routes = self.clone();
+        let mut operators = self.operators.clone();
+        for generation in 0..generations {
+            routes = routes.step_with_operators(
+                distance_mat,
+                &schedule.config_at(generation, generations),
+                &mut operators,
+                rng,
+            );
+        }
+        Ok(routes)
+    }
+    /// Evolve the population for `generations` generations and keep the `capacity` shortest
+    /// distinct routes of all generations, including the population itself, in a `HallOfFame`.
+    /// All children are considered, also those that are discarded by `config.replacement`.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the fitness of the routes is computed on.
+    /// * `config` - The parameters of the genetic algorithm.
+    /// * `generations` - For how many generations should the population be evolved?
+    /// * `capacity` - How many routes the hall of fame keeps.
+    /// * `rng` - The random number generator used for crossover and mutation.
+    ///
+    /// # Errors
+    ///
+    /// If a route is not compatible with `distance_mat`, see `Routes::check_compatible`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::config::GaConfig;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let (evolved_routes, hall_of_fame) = Routes::random(3, 3)
+    ///     .evolve_with_hall_of_fame(&distance_matrix, &GaConfig::default(), 10, 2, &mut rand::thread_rng())
+    ///     .unwrap();
+    /// assert_eq!(hall_of_fame.entries().len(), 2);
+    /// ```
+    pub fn evolve_with_hall_of_fame(
+        &self,
+        distance_mat: &DistanceMat,
+        config: &GaConfig,
+        generations: usize,
+        capacity: usize,
+        rng: &mut impl Rng,
+    ) -> Result<(Routes, HallOfFame), DistanceMatError> {
+        self.check_compatible(distance_mat)?;
+        let mut hall_of_fame = HallOfFame::new(capacity);
+        hall_of_fame.update(self, distance_mat);
+        let mut routes = self.clone();
+        let mut operators = self.operators.clone();
+        // Every survivor is either a parent or a child, so it was already considered.
+        for _ in 0..generations {
+            routes = routes.step_observing_offspring(
+                distance_mat,
+                config,
+                &mut operators,
+                &mut |offspring| hall_of_fame.update(offspring, distance_mat),
+                rng,
+            );
+        }
+        Ok((routes, hall_of_fame))
+    }
+    /// Evolve the population for `generations` generations and write the statistics of every
+    /// generation as CSV to `writer`, e.g. to plot the progress of an experiment. The first row is
+    /// the header `generation,best,worst,mean,std,diversity`, followed by one row per generation
+    /// with its `FitnessStats` and `Routes::edge_diversity`.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the fitness of the routes is computed on.
+    /// * `config` - The parameters of the genetic algorithm.
+    /// * `generations` - For how many generations should the population be evolved?
+    /// * `rng` - The random number generator used for crossover and mutation.
+    /// * `writer` - Where the CSV is written to, e.g. a file.
+    ///
+    /// # Errors
+    ///
+    /// If writing to `writer` fails, or with `std::io::ErrorKind::InvalidInput` if a route is not
+    /// compatible with `distance_mat`, see `Routes::check_compatible`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::config::GaConfig;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let mut csv = Vec::new();
+    /// Routes::random(3, 3)
+    ///     .evolve_logging_csv(&distance_matrix, &GaConfig::default(), 5, &mut rand::thread_rng(), &mut csv)
+    ///     .unwrap();
+    /// assert_eq!(String::from_utf8(csv).unwrap().lines().count(), 6);
+    /// ```
+    pub fn evolve_logging_csv<W: Write>(
+        &self,
+        distance_mat: &DistanceMat,
+        config: &GaConfig,
+        generations: usize,
+        rng: &mut impl Rng,
+        mut writer: W,
+    ) -> std::io::Result<Routes> {
+        self.check_compatible(distance_mat)
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidInput, error))?;
+        writeln!(writer, "generation,best,worst,mean,std,diversity")?;
+        let mut routes = self.clone();
+        let mut operators = self.operators.clone();
+        for generation in 1..=generations {
+            routes = routes.step_with_operators(distance_mat, config, &mut operators, rng);
+            if let Some(stats) = routes.fitness_stats(distance_mat) {
+                writeln!(
+                    writer,
+                    "{},{},{},{},{},{}",
+                    generation,
+                    stats.best,
+                    stats.worst,
+                    stats.mean,
+                    stats.std,
+                    routes.edge_diversity()
+                )?;
+            }
+        }
+        writer.flush()?;
+        Ok(routes)
+    }
+    /// Inject a route, e.g. found by another heuristic, into the population. It replaces the
+    /// currently longest route, so the size of the population stays the same. If the route is
+    /// already part of the population nothing changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route that should be added to the population.
+    /// * `distance_mat` - The distance matrix the length of the routes is computed on.
+    ///
+    /// # Panics
+    ///
+    /// If `route` does not visit all nodes of `distance_mat` exactly once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let mut routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
+    /// routes.inject(Route::new(vec![2,1,0]), &distance_matrix);
+    /// ```
+    pub fn inject(&mut self, route: Route, distance_mat: &DistanceMat) {
+        assert!(
+            distance_mat.route_matches(&route.indexes),
+            "The injected route {:?} is not a permutation of the {} nodes of the distance matrix.",
+            route.indexes,
+            distance_mat.n_units()
+        );
+        if self.routes.contains(&route) {
+            return;
+        }
+        let mut aged_routes = self
+            .ages_by_route()
+            .into_iter()
+            .map(|(route, age)| (route.clone(), age))
+            .collect::<HashMap<Route, usize>>();
+        if let Some((worst_route, _)) = self.sorted_by_fitness(distance_mat).pop() {
+            aged_routes.remove(worst_route);
+        }
+        aged_routes.insert(route, 0);
+        *self = Routes::from_aged_routes(aged_routes);
+    }
+    /// Combine two populations into one, e.g. to pool the routes of independent runs. A route that
+    /// is part of both populations is only kept once, with the larger of its two ages, so the
+    /// merged population only has the combined size if the populations do not share routes.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The population that is merged into this one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::route::Route;
+    ///
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
+    /// let other = Routes::from(vec![Route::new(vec![2,1,0])]);
+    /// assert_eq!(routes.merge(other).ages().len(), 3);
+    /// ```
+    pub fn merge(self, other: Routes) -> Routes {
+        let mut aged_routes = self
+            .routes
+            .into_iter()
+            .zip(self.ages)
+            .collect::<HashMap<Route, usize>>();
+        for (route, age) in other.routes.into_iter().zip(other.ages) {
+            let merged_age = aged_routes.entry(route).or_insert(age);
+            *merged_age = (*merged_age).max(age);
+        }
+        Routes::from_aged_routes(aged_routes)
+    }
+    /// Combine two populations like `merge`, but only keep the `k` shortest routes of both. If
+    /// several routes are as short as the `k`-th shortest route, the first ones in lexicographic
+    /// order are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The population that is merged into this one.
+    /// * `distance_mat` - The distance matrix the length of the routes is computed on.
+    /// * `k` - How many routes are kept at most.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    /// use genetic_algo::gen_traits::Population;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2,3]), Route::new(vec![0,2,1,3])]);
+    /// let other = Routes::from(vec![Route::new(vec![0,1,3,2])]);
+    /// let best = routes.merge_top_k(other, &distance_matrix, 1);
+    /// assert_eq!(best.iter().collect::<Vec<&Route>>(), vec![&Route::new(vec![0,1,2,3])]);
+    /// ```
+    pub fn merge_top_k(self, other: Routes, distance_mat: &DistanceMat, k: usize) -> Routes {
+        let merged = self.merge(other);
+        let ages_by_route = merged.ages_by_route();
+        Routes::from_aged_routes(
+            merged
+                .sorted_by_fitness(distance_mat)
+                .into_iter()
+                .take(k)
+                .map(|(route, _)| (route.clone(), ages_by_route[route]))
+                .collect(),
+        )
+    }
+}
+/// Create the two children of a pair of parents: with a probability of `config.crossover_rate`
+/// they are crossed over, otherwise they are copied. With `config.adaptive_operators` and
+/// crossover operators in `operators`, the crossover is chosen by `operators` and the
+/// improvements of both children over the shorter parent are recorded, otherwise the parents are
+/// crossed over with `config.crossover`. Afterwards both children are mutated, see
+/// `mutate_child`, and refined with `config.local_search`.
+fn create_children(
+    parent_a: &Route,
+    parent_b: &Route,
+    distance_mat: &DistanceMat,
+    config: &GaConfig,
+    operators: &mut AdaptiveOperators,
+    rng: &mut impl Rng,
+) -> (Route, Route) {
+    let (child_a, child_b) = if random_unit_with(rng) < f64::from(config.crossover_rate) {
+        let adaptive_crossover = if config.adaptive_operators {
+            operators.choose_crossover(rng)
+        } else {
+            None
+        };
+        match (adaptive_crossover, &config.crossover) {
+            (Some(crossover_idx), _) => {
+                let crossover = Arc::clone(&operators.crossovers()[crossover_idx]);
+                let children = (
+                    crossover.apply(parent_a, parent_b, rng),
+                    crossover.apply(parent_b, parent_a, rng),
+                );
+                let parent_length = parent_a
+                    .distance(distance_mat)
+                    .min(parent_b.distance(distance_mat));
+                for child in [&children.0, &children.1] {
+                    operators.record_crossover(
+                        crossover_idx,
+                        parent_length,
+                        child.distance(distance_mat),
+                    );
+                }
+                children
+            }
+            (None, Some(crossover)) => (
+                crossover.apply(parent_a, parent_b, rng),
+                crossover.apply(parent_b, parent_a, rng),
+            ),
+            (None, None) => parent_a.crossover_pair(parent_b, rng),
+        }
+    } else {
+        (parent_a.clone(), parent_b.clone())
+    };
+    let refine = |child: Route| match config.local_search {
+        Some(LocalSearchKind::TwoOpt) => child.two_opt(distance_mat),
+        Some(LocalSearchKind::OrOpt) => child.or_opt(distance_mat),
+        None => child,
+    };
+    let child_a = mutate_child(child_a, distance_mat, config, operators, rng);
+    let child_b = mutate_child(child_b, distance_mat, config, operators, rng);
+    (refine(child_a), refine(child_b))
+}
+/// Mutate a child with a probability of `config.mutation_rate`. With `config.adaptive_operators`
+/// the mutation is chosen by `operators` and its improvement is recorded, otherwise the child is
+/// mutated with `Route::mutate_with`.
+fn mutate_child(
+    child: Route,
+    distance_mat: &DistanceMat,
+    config: &GaConfig,
+    operators: &mut AdaptiveOperators,
+    rng: &mut impl Rng,
+) -> Route {
+    if !config.adaptive_operators {
+        return child.mutate_with(config.mutation_rate, rng);
+    }
+    if random_unit_with(rng) >= f64::from(config.mutation_rate) {
+        return child;
+    }
+    let operator = operators.choose(rng);
+    let mutated = child.maybe_mutate(1.0, operators.operators()[operator], rng);
+    operators.record(
+        operator,
+        child.distance(distance_mat),
+        mutated.distance(distance_mat),
+    );
+    mutated
+}
+/// Draw a tour that starts at a random node and moves on to the next unvisited node with a
+/// probability inversely proportional to the bias of the edge, see
+/// `Routes::seeded_from_edge_weights`.
+fn biased_tour(edge_bias: &DistanceMat, rng: &mut impl Rng) -> Route {
+    let n_nodes = edge_bias.n_units();
+    if n_nodes == 0 {
+        return Route::new(Vec::new());
+    }
+    let mut current = random_index_with(0..n_nodes, rng);
+    let mut unvisited = (0..n_nodes)
+        .filter(|node| *node != current)
+        .collect::<Vec<usize>>();
+    let mut indexes = vec![current];
+    while !unvisited.is_empty() {
+        let biases = unvisited
+            .iter()
+            .map(|node| edge_bias.distance_between(current, *node))
+            .collect::<Vec<f64>>();
+        let preferred = (0..unvisited.len())
+            .filter(|idx| biases[*idx] <= 0.0)
+            .collect::<Vec<usize>>();
+        let next_idx = if !preferred.is_empty() {
+            preferred[random_index_with(0..preferred.len(), rng)]
+        } else {
+            weighted_index_with(
+                &biases.iter().map(|bias| 1.0 / bias).collect::<Vec<f64>>(),
+                rng,
+            )
+            .expect("The biases of the edges have to be finite.")
+        };
+        current = unvisited.swap_remove(next_idx);
+        indexes.push(current);
+    }
+    Route::new(indexes)
+}
+/// Count the undirected edges two routes have in common.
+fn shared_edges(route_a: &Route, route_b: &Route) -> usize {
+    let undirected_edges = |route: &Route| {
+        route
+            .indexes
+            .iter()
+            .zip(route.indexes.iter().cycle().skip(1))
+            .map(|(from, to)| (*from.min(to), *from.max(to)))
+            .collect::<HashSet<(usize, usize)>>()
+    };
+    undirected_edges(route_a)
+        .intersection(&undirected_edges(route_b))
+        .count()
+}
+/// Decide the competition between a parent and its child in deterministic crowding: the child
+/// only wins if it is strictly shorter.
+fn crowding_winner<'r>(
+    parent: &'r Route,
+    child: &'r Route,
+    distance_mat: &DistanceMat,
+) -> &'r Route {
+    if child.distance(distance_mat) < parent.distance(distance_mat) {
+        child
+    } else {
+        parent
+    }
+}
+
+impl<'a> Population<'a> for Routes {
+    type Individual = Route;
+    type IndividualCollection = std::collections::hash_set::Iter<'a, Route>;
+
+    /// Given your pool of current routes, compute the fitness of your individuals to solve the
+    /// problem at hand.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distances between nodes that is neccessary to computes how well the route
+    ///   work in terms of the TSP
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    /// use crate::genetic_algo::gen_traits::Population;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
+    /// println!("Your routes's fitnesses: {:?}", routes.fitnesses(&distance_matrix));
+    /// ```
+    // fn fitnesses(&self, distance_mat: &DistanceMat) -> Vec<(f64, &Route)> {
+    //     self.iter()
+    //         .map(|route| (route.fitness(distance_mat), route))
+    //         .collect()
+    // }
+    /// Get the n fittest routes in your population. Routes of the same fitness are returned in
+    /// lexicographic order.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of routes you would like to get.
+    /// * `distance_mat` - The distance matrix the fitness should be evaluated on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    /// use crate::genetic_algo::gen_traits::Population;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
+    /// let my_fittest_routes = routes.get_n_fittest(1, &distance_matrix);
+    /// ```
+    fn get_n_fittest(&self, n: usize, distance_mat: &DistanceMat) -> Vec<Route> {
+        // Use the ordering of `sorted_by_fitness`, which breaks ties deterministically.
+        self.sorted_by_fitness(distance_mat)
+            .into_iter()
+            .take(n)
+            .map(|(route, _)| route.clone())
+            .collect()
+    }
+    /// Get the n fittest individuals in your routes as new routes object. This is typically used
+    /// to select the top n inidividuals, before continuing to evolve the routes further.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of individuals you would like to have.
+    /// * `distance_mat` - The distance matrix the fitness should be evaluated on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::route::Route;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    /// use crate::genetic_algo::gen_traits::Population;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
+    /// let my_fittest_routes = routes.get_fittest_population(2, &distance_matrix);
+    /// ```
+    fn get_fittest_population(&self, n: usize, distance_mat: &DistanceMat) -> Routes {
+        let ages = self.ages_by_route();
+        Routes::from_aged_routes(
+            self.get_n_fittest(n, distance_mat)
+                .into_iter()
+                .map(|route| {
+                    let age = ages[&route];
+                    (route, age)
+                })
+                .collect(),
+        )
+    }
+    /// Evolve your population.
+    ///
+    /// The evolution consists of the following stages:
+    /// 1) `crossover` between all 1,...,n routes excluding the route itself.
+    /// 2) `mutate` is applied to all individuals.
+    ///
+    /// The current routes are kept in the evolved population and their age increases by one, while
+    /// the new routes start with an age of 0.
+    ///
+    /// The `Population` trait knows no distance matrix, so neither the parents nor the survivors
+    /// are chosen by their fitness here. Use `Routes::step` to evolve the routes by one
+    /// generation of a `GaConfig`.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutate_prob` - The probabilty of an inviduals beeing mutated. Is applied via `individuals.mutate`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::route::Route;
+    /// use crate::genetic_algo::gen_traits::Population;
+    /// use genetic_algo::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
+    /// let evolved_routes = routes.evolve(0.5);
+    /// ```
+    fn evolve(&self, mutate_prob: f32) -> Routes {
+        let mut aged_routes = self
+            .evolve_individuals(mutate_prob)
+            .into_iter()
+            .map(|route| (route, 0))
+            .collect::<HashMap<Route, usize>>();
+        // The current routes are carried over into the next generation and therefore age by one.
+        for (route, age) in self.ages_by_route() {
+            aged_routes.insert(route.clone(), age + 1);
+        }
+        Routes::from_aged_routes(aged_routes)
+    }
+    /// Iterate over the individuals of your population.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algo::routes::Routes;
+    /// use genetic_algo::route::Route;
+    /// use crate::genetic_algo::gen_traits::Population;
+    ///
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
+    /// for route in routes.iter(){
+    ///     println!("{:?}", route);
+    /// }
+    /// ```
+    fn iter(&'a self) -> std::collections::hash_set::Iter<'a, Route> {
+        self.routes.iter()
+    }
+}
+
+/// Given an initial population evolve it for `n_generations` while keeping `size_generation`
+/// individuals. The final population will be returned. Every generation is a `Routes::step` in
+/// which all pairs of routes are crossed over, the children are mutated with a probability of 0.5
+/// and the fittest routes survive.
+///
+/// # Arguments
+///
+/// * `initial_population` - Your initial population that should be evolved.
+/// * `n_generations` - How many times should your population be evolved?
+/// * `size_generation` - How many individuals should be kept after evolving it.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+///
+/// # Errors
+///
+/// If a route is not compatible with `distance_matrix`, see `Routes::check_compatible`.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algo::routes::{Routes, evolve_population};
+/// use genetic_algo::route::Route;
+/// use genetic_algo::distance_mat::DistanceMat;
+///
+/// let evolved_population = evolve_population(
+///     Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]),
+///     10,
+///     10,
+///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]),
+///     0
+/// ).unwrap();
+/// ```
+pub fn evolve_population(
+    initial_population: Routes,
+    n_generations: usize,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+    n_jobs: usize,
+) -> Result<Routes, DistanceMatError> {
+    initial_population.check_compatible(distance_matrix)?;
+    let config = GaConfig {
+        population_size: size_generation,
+        crossover_rate: 1.0,
+        ..GaConfig::default()
+    };
+    if n_jobs == 0 {
+        // single-thread
+        let mut rng = rand::thread_rng();
+        Ok((0..n_generations).fold(initial_population, |pop, _| {
+            pop.step(distance_matrix, &config, &mut rng)
+        }))
+    } else {
+        // Multi-threaded execution
+        let config = &config;
+        Ok(thread::scope(|s| {
+            let mut result = Vec::new();
+            for _ in 0..n_jobs {
+                let this_population = initial_population.clone();
+                result.push(s.spawn(move |_| -> Vec<Route> {
+                    let mut rng = rand::thread_rng();
+                    (0..((n_generations / n_jobs) + 1))
+                        .fold(this_population, |pop, _| {
+                            pop.step(distance_matrix, config, &mut rng)
+                        })
+                        .get_n_fittest(size_generation, distance_matrix)
+                }))
+            }
+            Routes::from(
+                result
+                    .into_iter()
+                    .flat_map(|thread| thread.join().unwrap())
+                    .collect::<Vec<Route>>(),
+            )
+        })
+        .unwrap())
+    }
+}
+/// Given an initial population evolve it for `n_generations` and choose the `size_generation`
+/// routes that survive each generation with `replacement`. As strategies like
+/// `ReplacementStrategy::AgeBased` can discard the best route, the shortest route seen over all
+/// generations is returned together with the final population. The best route is `None` if the
+/// initial population is empty.
+///
+/// # Arguments
+///
+/// * `initial_population` - Your initial population that should be evolved.
+/// * `n_generations` - How many times should your population be evolved?
+/// * `size_generation` - How many individuals should be kept after evolving it.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `replacement` - How the individuals that are kept are chosen.
+/// * `mutation_probability` - The probability with which each offspring is mutated.
+///
+/// # Errors
+///
+/// If a route is not compatible with `distance_matrix`, see `Routes::check_compatible`.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algo::routes::{Routes, ReplacementStrategy, evolve_population_with_replacement};
+/// use genetic_algo::route::Route;
+/// use genetic_algo::distance_mat::DistanceMat;
+///
+/// let (evolved_population, best_route) = evolve_population_with_replacement(
+///     Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]),
+///     10,
+///     10,
+///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]),
+///     &ReplacementStrategy::AgeBased { max_age: 3 },
+///     0.5,
+/// ).unwrap();
+/// assert!(best_route.is_some());
+/// ```
+pub fn evolve_population_with_replacement(
+    initial_population: Routes,
+    n_generations: usize,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+    replacement: &ReplacementStrategy,
+    mutation_probability: f32,
+) -> Result<(Routes, Option<Route>), DistanceMatError> {
+    initial_population.check_compatible(distance_matrix)?;
+    let mut best_route = initial_population.get_n_fittest(1, distance_matrix).pop();
+    let final_population = (0..n_generations).fold(initial_population, |pop, _| {
+        let pop = pop.evolve(mutation_probability).select_survivors(
+            size_generation,
+            distance_matrix,
+            replacement,
+        );
+        if let Some(fittest_route) = pop.get_n_fittest(1, distance_matrix).pop() {
+            if best_route.as_ref().is_none_or(|best_route| {
+                fittest_route.fitness(distance_matrix) > best_route.fitness(distance_matrix)
+            }) {
+                best_route = Some(fittest_route);
+            }
+        }
+        pop
+    });
+    Ok((final_population, best_route))
+}
+/// Compute the time in milliseconds that it takes for a genetic algorithm to run.
+///
+/// # Arguments
+///
+/// * `n_generations` - How many generations should the algorithm evolve?
+/// * `size_generation` - How many individuals should be selected at the end of each
+///   evolution step.
+/// * `dist_mat` - What is the distance matrix for your TSP.
+///
+/// ```
+pub fn benchmark_population(
+    n_generations: usize,
+    size_generation: usize,
+    dist_mat: &DistanceMat,
+    n_jobs: usize,
+) -> (u64, f64) {
+    // End-to-end test: does the error of the route get down?
+    let before = Instant::now();
+    let final_population = evolve_population(
+        Routes::random(size_generation, dist_mat.n_units()),
+        n_generations,
+        size_generation,
+        dist_mat,
+        n_jobs,
+    )
+    .expect("The random routes are created for `dist_mat`.");
+    let duration = before.elapsed();
+    let nanos = duration.subsec_nanos() as u64;
+    (
+        (1000 * 1000 * 1000 * duration.as_secs() + nanos) / (1000 * 1000),
+        final_population.get_n_fittest(1, dist_mat)[0].fitness(dist_mat),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{test_dist_mat, valid_permutation};
+
+    #[test]
+    fn from_routes_vector() {
+        assert_eq!(
+            Routes::from(vec![
+                Route {
+                    indexes: vec![0, 1, 2]
+                },
+                Route {
+                    indexes: vec![0, 2, 1]
+                }
+            ])
+            .routes,
+            HashSet::from([
+                Route {
+                    indexes: vec![0, 1, 2]
+                },
+                Route {
+                    indexes: vec![0, 2, 1]
+                }
+            ])
+        )
+    }
+    #[test]
+    fn random_constructor() {
+        let n_objects = 3;
+        let population = Routes::random(3, n_objects);
+        assert_eq!(population.routes.len(), 3);
+        for route in population.routes {
+            valid_permutation(&route.indexes, &(0..n_objects).collect::<Vec<usize>>());
+        }
+    }
+    #[test]
+    fn test_fitness() {
+        let distance_mat = test_dist_mat();
+        let population = Routes::from(vec![Route::new(vec![1, 2, 0]), Route::new(vec![1, 0])]);
+        let fitnesses = population.fitnesses(&distance_mat);
+        assert_eq!(fitnesses.len(), 2);
+
+        for element in [
+            (-6.0, &Route::new(vec![1, 2, 0])),
+            (-2.0, &Route::new(vec![1, 0])),
+        ] {
+            assert!(fitnesses.contains(&element))
+        }
+    }
+    #[test]
+    fn sorted_by_fitness() {
+        let distance_mat = test_dist_mat();
+        let routes = Routes::from(vec![
+            Route::new(vec![1, 2, 0]),
+            Route::new(vec![1, 0]),
+            Route::new(vec![2, 0]),
+        ]);
+        assert_eq!(
+            routes.sorted_by_fitness(&distance_mat),
+            vec![
+                (&Route::new(vec![1, 0]), 2.0),
+                (&Route::new(vec![2, 0]), 4.0),
+                (&Route::new(vec![1, 2, 0]), 6.0),
+            ]
+        );
+    }
+    mod test_fitness_stats {
+        use super::*;
+        #[test]
+        fn best_worst_mean_std() {
+            let routes = Routes::from(vec![
+                Route::new(vec![0, 1]),
+                Route::new(vec![0, 2]),
+                Route::new(vec![1, 2]),
+            ]);
+            let stats = routes.fitness_stats(&test_dist_mat()).unwrap();
+            assert_eq!(stats.best, 2.0);
+            assert_eq!(stats.worst, 6.0);
+            assert_eq!(stats.mean, 4.0);
+            assert!((stats.std - (8.0_f64 / 3.0).sqrt()).abs() < 1e-12);
+        }
+        #[test]
+        fn empty_population() {
+            assert_eq!(
+                Routes::from(Vec::new()).fitness_stats(&test_dist_mat()),
+                None
+            );
+        }
+    }
+    mod test_custom_crossover {
+        use super::*;
+        use crate::route::CrossoverOp;
+        use rand::RngCore;
+        use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+        use std::sync::Arc;
+        #[derive(Debug, Default)]
+        struct FirstParent {
+            calls: AtomicUsize,
+        }
+        impl CrossoverOp for FirstParent {
+            fn apply(&self, a: &Route, _b: &Route, _rng: &mut dyn RngCore) -> Route {
+                self.calls.fetch_add(1, AtomicOrdering::SeqCst);
+                a.clone()
+            }
+        }
+        #[test]
+        fn wired_through_evolution() {
+            let operator = Arc::new(FirstParent::default());
+            let config = GaConfig {
+                population_size: 4,
+                mutation_rate: 0.0,
+                crossover_rate: 1.0,
+                crossover: Some(operator.clone()),
+                ..GaConfig::default()
+            };
+            let routes = Routes::from(vec![
+                Route::new(vec![0, 1, 2, 3]),
+                Route::new(vec![1, 3, 0, 2]),
+                Route::new(vec![2, 0, 3, 1]),
+                Route::new(vec![3, 2, 1, 0]),
+            ]);
+            let evolved = routes
+                .evolve_with_callback(
+                    &DistanceMat::from_coordinates(&[
+                        (0.0, 0.0),
+                        (1.0, 0.0),
+                        (1.0, 1.0),
+                        (0.0, 1.0),
+                    ]),
+                    &config,
+                    3,
+                    &mut rand::thread_rng(),
+                    |_| (),
+                )
+                .unwrap();
+            // Copying the parents never creates new routes.
+            assert!(evolved
+                .iter()
+                .all(|route| routes.iter().any(|parent| parent == route)));
+            // Every generation creates both children of all six pairs.
+            assert_eq!(operator.calls.load(AtomicOrdering::SeqCst), 3 * 12);
+        }
+    }
+    mod test_evolve_scheduled {
+        use super::*;
+        use crate::test_utils::circle_dist_mat;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn steps_with_scheduled_configs() {
+            let distance_mat = circle_dist_mat(8);
+            let start_config = GaConfig {
+                population_size: 8,
+                mutation_rate: 0.9,
+                selection: Selection::Tournament { size: 2 },
+                ..GaConfig::default()
+            };
+            let end_config = GaConfig {
+                mutation_rate: 0.1,
+                selection: Selection::Tournament { size: 6 },
+                ..start_config.clone()
+            };
+            let routes = Routes::random(8, 8);
+            let scheduled = routes
+                .evolve_scheduled(
+                    &distance_mat,
+                    &start_config,
+                    &end_config,
+                    5,
+                    &mut StdRng::seed_from_u64(3),
+                )
+                .unwrap();
+            let mut rng = StdRng::seed_from_u64(3);
+            let mut stepped = routes;
+            for (generation, size) in [2, 3, 4, 5, 6].into_iter().enumerate() {
+                let config = GaConfig {
+                    mutation_rate: 0.9 - 0.2 * generation as f32,
+                    selection: Selection::Tournament { size },
+                    ..start_config.clone()
+                };
+                stepped = stepped.step(&distance_mat, &config, &mut rng);
+            }
+            assert_eq!(scheduled, stepped);
+        }
+    }
+    mod test_sample {
+        use super::*;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn approximately_uniform() {
+            let routes = Routes::random(10, 6);
+            let mut rng = StdRng::seed_from_u64(0);
+            let mut inclusions = HashMap::new();
+            let n_draws = 20_000;
+            for _ in 0..n_draws {
+                let sample = routes.sample(3, &mut rng);
+                assert_eq!(sample.iter().collect::<HashSet<_>>().len(), 3);
+                for route in sample {
+                    *inclusions.entry(route.clone()).or_insert(0) += 1;
+                }
+            }
+            assert_eq!(inclusions.len(), 10);
+            for count in inclusions.values() {
+                let probability = *count as f64 / n_draws as f64;
+                assert!(
+                    (probability - 0.3).abs() < 0.02,
+                    "Inclusion probability {} instead of 0.3",
+                    probability
+                );
+            }
+        }
+        #[test]
+        fn k_larger_than_population() {
+            let routes = Routes::random(4, 5);
+            let mut sample = routes.sample(10, &mut rand::thread_rng());
+            sample.sort();
+            let mut all_routes = routes.iter().collect::<Vec<&Route>>();
+            all_routes.sort();
+            assert_eq!(sample, all_routes);
+            assert!(routes.sample(0, &mut rand::thread_rng()).is_empty());
+        }
+        #[test]
+        fn same_seed_same_sample() {
+            let routes = Routes::random(10, 6);
+            assert_eq!(
+                routes.sample(4, &mut StdRng::seed_from_u64(9)),
+                routes.clone().sample(4, &mut StdRng::seed_from_u64(9))
+            );
+        }
+    }
+    mod test_seeded_from_edge_weights {
+        use super::*;
+        use crate::test_utils::{circle_dist_mat, valid_permutation};
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        // Count how often the routes use an edge between neighbors on the circle.
+        fn n_circle_edges(routes: &Routes, n_nodes: usize) -> usize {
+            routes
+                .iter()
+                .map(|route| {
+                    route
+                        .indexes
+                        .iter()
+                        .zip(route.indexes.iter().cycle().skip(1))
+                        .filter(|(from, to)| {
+                            (n_nodes + *from - *to) % n_nodes == 1
+                                || (n_nodes + *to - *from) % n_nodes == 1
+                        })
+                        .count()
+                })
+                .sum()
+        }
+        #[test]
+        fn preferred_edges_more_frequent() {
+            let edge_bias = circle_dist_mat(12);
+            let mut rng = StdRng::seed_from_u64(4);
+            let seeded = Routes::seeded_from_edge_weights(12, 50, &edge_bias, &mut rng);
+            let random = Routes::random_with(50, 12, &mut rng);
+            assert_eq!(seeded.ages().len(), 50);
+            for route in seeded.iter() {
+                valid_permutation(&route.indexes, &(0..12).collect::<Vec<usize>>());
+            }
+            // A random route uses an edge of the circle with a probability of 2 / 11, so about 109
+            // of the 600 edges are on the circle. The seeded routes use about 200 of them.
+            assert!(2 * n_circle_edges(&seeded, 12) > 3 * n_circle_edges(&random, 12));
+        }
+        #[test]
+        fn zero_bias_fills_up() {
+            // Every edge is preferred alike, so the population holds all 24 tours on four nodes.
+            let edge_bias = DistanceMat::new(vec![vec![0.0; 4]; 4]);
+            let routes =
+                Routes::seeded_from_edge_weights(4, 24, &edge_bias, &mut StdRng::seed_from_u64(1));
+            assert_eq!(routes.ages().len(), 24);
+        }
+        #[test]
+        #[should_panic]
+        fn wrong_number_of_nodes() {
+            Routes::seeded_from_edge_weights(5, 2, &circle_dist_mat(4), &mut rand::thread_rng());
+        }
+    }
+    mod test_deterministic_crowding {
+        use super::*;
+        use crate::test_utils::circle_dist_mat;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn shorter_child_wins() {
+            let distance_mat = circle_dist_mat(6);
+            let (short, long) = (
+                Route::new(vec![0, 1, 2, 3, 4, 5]),
+                Route::new(vec![0, 3, 1, 4, 2, 5]),
+            );
+            assert_eq!(crowding_winner(&long, &short, &distance_mat), &short);
+            assert_eq!(crowding_winner(&short, &long, &distance_mat), &short);
+            assert_eq!(
+                crowding_winner(&short, &short.clone(), &distance_mat),
+                &short
+            );
+        }
+        #[test]
+        fn shared_edges_are_undirected() {
+            let route = Route::new(vec![0, 1, 2, 3]);
+            assert_eq!(shared_edges(&route, &Route::new(vec![3, 2, 1, 0])), 4);
+            assert_eq!(shared_edges(&route, &Route::new(vec![0, 2, 1, 3])), 2);
+        }
+        #[test]
+        fn size_constant_and_best_kept() {
+            let distance_mat = circle_dist_mat(12);
+            let config = GaConfig {
+                population_size: 3,
+                replacement: ReplacementStrategy::DeterministicCrowding,
+                ..GaConfig::default()
+            };
+            let mut rng = StdRng::seed_from_u64(5);
+            for n_routes in [7, 10] {
+                let mut routes = Routes::random(n_routes, 12);
+                for _ in 0..30 {
+                    let best_distance = routes.best(&distance_mat).unwrap().distance(&distance_mat);
+                    // The step draws the same competitions from a copy of the generator.
+                    let competitions = routes.crowding_competitions(
+                        &distance_mat,
+                        &config,
+                        &mut AdaptiveOperators::default(),
+                        &mut rng.clone(),
+                    );
+                    let next_generation = routes.step(&distance_mat, &config, &mut rng);
+                    assert_eq!(next_generation.ages().len(), n_routes);
+                    // A strictly shorter child survives, unless it is already part of the
+                    // population. Otherwise the parent survives.
+                    for (parent, child) in competitions {
+                        match child {
+                            Some(child)
+                                if child.distance(&distance_mat)
+                                    < parent.distance(&distance_mat)
+                                    && !routes.routes.contains(&child) =>
+                            {
+                                assert!(next_generation.routes.contains(&child));
+                            }
+                            _ => assert!(next_generation.routes.contains(parent)),
+                        }
+                    }
+                    // Every route is the survivor of a competition, so it is either a child of age
+                    // 0 that beat its parent or a parent that aged by one.
+                    let ages_by_route = routes.ages_by_route();
+                    for (route, age) in next_generation.ages_by_route() {
+                        if age > 0 {
+                            assert_eq!(ages_by_route[route] + 1, age);
+                        }
+                    }
+                    assert!(
+                        next_generation
+                            .best(&distance_mat)
+                            .unwrap()
+                            .distance(&distance_mat)
+                            <= best_distance
+                    );
+                    routes = next_generation;
+                }
+            }
+        }
+        #[test]
+        fn reproducible() {
+            let distance_mat = circle_dist_mat(8);
+            let config = GaConfig {
+                replacement: ReplacementStrategy::DeterministicCrowding,
+                ..GaConfig::default()
+            };
+            let routes = Routes::random(6, 8);
+            assert_eq!(
+                routes.step(&distance_mat, &config, &mut StdRng::seed_from_u64(1)),
+                routes.step(&distance_mat, &config, &mut StdRng::seed_from_u64(1))
+            );
+        }
+    }
+    mod test_routes_heap {
+        use super::*;
+        use crate::test_utils::circle_dist_mat;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        // Check that the heap is ordered and agrees with its set of routes.
+        fn assert_heap_invariant(heap: &RoutesHeap) {
+            let sorted = heap.entries.clone().into_sorted_vec();
+            assert!(sorted.windows(2).all(|pair| pair[0] <= pair[1]));
+            assert_eq!(
+                heap.peek_worst().map(|(route, _)| route),
+                sorted.last().map(|entry| &entry.route)
+            );
+            assert_eq!(
+                sorted
+                    .iter()
+                    .map(|entry| entry.route.clone())
+                    .collect::<HashSet<Route>>(),
+                heap.routes
+            );
+            assert_eq!(heap.routes.len(), heap.len());
+        }
+        #[test]
+        fn worst_stays_correct() {
+            let distance_mat = circle_dist_mat(9);
+            let mut rng = StdRng::seed_from_u64(6);
+            let mut heap = RoutesHeap::new(&Routes::random_with(10, 9, &mut rng), &distance_mat);
+            let mut reference = heap.routes.iter().cloned().collect::<Vec<Route>>();
+            assert_heap_invariant(&heap);
+            for step in 0..200 {
+                let route = Routes::random_with(1, 9, &mut rng)
+                    .routes
+                    .into_iter()
+                    .next()
+                    .unwrap();
+                if step % 3 == 0 {
+                    if heap.insert(route.clone(), &distance_mat) {
+                        reference.push(route);
+                    }
+                } else if let Some(replaced) = heap.replace_worst(route.clone(), &distance_mat) {
+                    let position = reference.iter().position(|r| *r == replaced).unwrap();
+                    reference[position] = route;
+                }
+                let worst_distance = reference
+                    .iter()
+                    .map(|route| route.distance(&distance_mat))
+                    .fold(f64::MIN, f64::max);
+                assert_eq!(heap.peek_worst().unwrap().1, worst_distance);
+                assert_eq!(heap.len(), reference.len());
+                assert_heap_invariant(&heap);
+            }
+        }
+        #[test]
+        fn replaced_route_was_worst() {
+            let distance_mat = circle_dist_mat(6);
+            let routes = Routes::from(vec![
+                Route::new(vec![0, 1, 2, 3, 4, 5]),
+                Route::new(vec![0, 3, 1, 4, 2, 5]),
+                Route::new(vec![0, 2, 1, 3, 4, 5]),
+            ]);
+            let mut heap = RoutesHeap::new(&routes, &distance_mat);
+            assert_eq!(
+                heap.replace_worst(Route::new(vec![0, 1, 2, 3, 5, 4]), &distance_mat),
+                Some(Route::new(vec![0, 3, 1, 4, 2, 5]))
+            );
+            // A route that is already part of the heap does not replace anything.
+            assert_eq!(
+                heap.replace_worst(Route::new(vec![0, 1, 2, 3, 4, 5]), &distance_mat),
+                None
+            );
+            assert_eq!(heap.len(), 3);
+        }
+        #[test]
+        fn keeps_ages() {
+            let distance_mat = circle_dist_mat(4);
+            let routes = Routes::from_aged_routes(HashMap::from([
+                (Route::new(vec![0, 1, 2, 3]), 4),
+                (Route::new(vec![0, 2, 1, 3]), 2),
+            ]));
+            let mut heap = RoutesHeap::new(&routes, &distance_mat);
+            heap.replace_worst(Route::new(vec![0, 1, 3, 2]), &distance_mat);
+            let ages_by_route = heap
+                .into_routes()
+                .ages_by_route()
+                .into_iter()
+                .map(|(route, age)| (route.clone(), age))
+                .collect::<HashMap<Route, usize>>();
+            assert_eq!(
+                ages_by_route,
+                HashMap::from([
+                    (Route::new(vec![0, 1, 2, 3]), 4),
+                    (Route::new(vec![0, 1, 3, 2]), 0)
+                ])
+            );
+        }
+        #[test]
+        fn empty_heap() {
+            let distance_mat = circle_dist_mat(4);
+            let mut heap = RoutesHeap::new(&Routes::from(vec![]), &distance_mat);
+            assert_eq!(heap.peek_worst(), None);
+            assert_eq!(
+                heap.replace_worst(Route::new(vec![0, 1, 2, 3]), &distance_mat),
+                None
+            );
+            assert!(heap.is_empty());
+        }
+    }
+    mod test_hall_of_fame {
+        use super::*;
+        use crate::test_utils::circle_dist_mat;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn keeps_shortest_distinct_routes() {
+            let mut hall_of_fame = HallOfFame::new(2);
+            hall_of_fame.update(
+                &Routes::from(vec![Route::new(vec![1, 2]), Route::new(vec![0, 2])]),
+                &test_dist_mat(),
+            );
+            hall_of_fame.update(
+                &Routes::from(vec![Route::new(vec![0, 2]), Route::new(vec![0, 1])]),
+                &test_dist_mat(),
+            );
+            assert_eq!(
+                hall_of_fame.entries(),
+                &[(Route::new(vec![0, 1]), 2.0), (Route::new(vec![0, 2]), 4.0)]
+            );
+        }
+        #[test]
+        fn not_worse_than_final_population() {
+            let distance_mat = circle_dist_mat(10);
+            let config = GaConfig {
+                population_size: 10,
+                ..GaConfig::default()
+            };
+            for seed in 0..5 {
+                let (routes, hall_of_fame) = Routes::random(10, 10)
+                    .evolve_with_hall_of_fame(
+                        &distance_mat,
+                        &config,
+                        10,
+                        3,
+                        &mut StdRng::seed_from_u64(seed),
+                    )
+                    .unwrap();
+                let final_best = routes.best(&distance_mat).unwrap();
+                assert!(
+                    hall_of_fame.entries()[0].1 <= distance_mat.get_distance(&final_best.indexes)
+                );
+                assert_eq!(hall_of_fame.entries().len(), 3);
+                assert!(hall_of_fame
+                    .entries()
+                    .windows(2)
+                    .all(|pair| pair[0].1 <= pair[1].1 && pair[0].0 != pair[1].0));
+            }
+        }
+        #[test]
+        fn includes_initial_population() {
+            let distance_mat = circle_dist_mat(6);
+            let optimal = Route::new(vec![0, 1, 2, 3, 4, 5]);
+            let routes = Routes::from(vec![optimal.clone(), Route::new(vec![0, 3, 1, 4, 2, 5])]);
+            let (_, hall_of_fame) = routes
+                .evolve_with_hall_of_fame(
+                    &distance_mat,
+                    &GaConfig::default(),
+                    0,
+                    1,
+                    &mut StdRng::seed_from_u64(0),
+                )
+                .unwrap();
+            assert_eq!(hall_of_fame.best(), Some(&optimal));
+        }
+        #[test]
+        fn includes_discarded_children() {
+            // With as many elites as routes, only the parents survive and all children are
+            // discarded. Both parents are the longest round-trip on the octagon.
+            let distance_mat = circle_dist_mat(8);
+            let routes = Routes::from(vec![
+                Route::new(vec![0, 3, 6, 1, 4, 7, 2, 5]),
+                Route::new(vec![1, 4, 7, 2, 5, 0, 3, 6]),
+            ]);
+            let config = GaConfig {
+                population_size: 2,
+                elitism: 2,
+                mutation_rate: 1.0,
+                ..GaConfig::default()
+            };
+            let (evolved_routes, hall_of_fame) = routes
+                .evolve_with_hall_of_fame(
+                    &distance_mat,
+                    &config,
+                    1,
+                    1,
+                    &mut StdRng::seed_from_u64(0),
+                )
+                .unwrap();
+            assert_eq!(evolved_routes.routes, routes.routes);
+            let (best_child, distance) = &hall_of_fame.entries()[0];
+            assert!(!routes.iter().any(|route| route == best_child));
+            assert!(
+                *distance < distance_mat.get_distance(&routes.best(&distance_mat).unwrap().indexes)
+            );
+        }
+    }
+    mod test_check_compatible {
+        use super::*;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn compatible_population() {
+            assert_eq!(
+                Routes::random(4, 3).check_compatible(&test_dist_mat()),
+                Ok(())
+            );
+        }
+        #[test]
+        fn population_of_other_matrix() {
+            let routes = Routes::from(vec![
+                Route::new(vec![0, 1, 2]),
+                Route::new(vec![3, 1, 0, 2]),
+                Route::new(vec![1, 0, 3, 2]),
+            ]);
+            let expected = DistanceMatError::IncompatibleRoute {
+                route: vec![1, 0, 3, 2],
+                n_units: 3,
+            };
+            assert_eq!(
+                routes.check_compatible(&test_dist_mat()),
+                Err(expected.clone())
+            );
+            assert_eq!(
+                routes.evolve_with_callback(
+                    &test_dist_mat(),
+                    &GaConfig::default(),
+                    3,
+                    &mut StdRng::seed_from_u64(0),
+                    |_| panic!("No generation should be evolved."),
+                ),
+                Err(expected.clone())
+            );
+            assert_eq!(
+                routes.evolve_to_target(
+                    &test_dist_mat(),
+                    &GaConfig::default(),
+                    0.0,
+                    3,
+                    &mut StdRng::seed_from_u64(0)
+                ),
+                Err(expected.clone())
+            );
+            assert_eq!(
+                routes.evolve_deterministic(&test_dist_mat(), &GaConfig::default(), 3),
+                Err(expected.clone())
+            );
+            assert_eq!(
+                evolve_population(routes.clone(), 3, 3, &test_dist_mat(), 0),
+                Err(expected.clone())
+            );
+            assert_eq!(
+                evolve_population_with_replacement(
+                    routes.clone(),
+                    3,
+                    3,
+                    &test_dist_mat(),
+                    &ReplacementStrategy::Fittest,
+                    0.5,
+                ),
+                Err(expected)
+            );
+            let error = routes
+                .evolve_logging_csv(
+                    &test_dist_mat(),
+                    &GaConfig::default(),
+                    3,
+                    &mut StdRng::seed_from_u64(0),
+                    std::io::sink(),
+                )
+                .unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        }
+    }
+    mod test_evolve_deterministic {
+        use super::*;
+        use crate::test_utils::circle_dist_mat;
+        #[test]
+        fn golden_tour_lengths() {
+            let distance_mat = circle_dist_mat(8);
+            let routes = Routes::from(vec![
+                Route::new(vec![0, 4, 1, 5, 2, 6, 3, 7]),
+                Route::new(vec![7, 6, 0, 2, 4, 1, 3, 5]),
+                Route::new(vec![3, 0, 6, 1, 7, 2, 5, 4]),
+                Route::new(vec![1, 6, 4, 3, 7, 0, 5, 2]),
+            ]);
+            let config = GaConfig {
+                population_size: 4,
+                ..GaConfig::default()
+            };
+            let evolved = routes
+                .evolve_deterministic(&distance_mat, &config, 5)
+                .unwrap();
+            let lengths = evolved
+                .sorted_by_fitness(&distance_mat)
+                .into_iter()
+                .map(|(_, distance)| format!("{:.6}", distance))
+                .collect::<Vec<String>>();
+            assert_eq!(
+                lengths,
+                vec!["9.088807", "9.304108", "9.304108", "9.304108"]
+            );
+        }
+    }
+    mod test_evolve_logging_csv {
+        use super::*;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn header_and_row_per_generation() {
+            let mut csv = Vec::new();
+            let routes = Routes::random(4, 3);
+            routes
+                .evolve_logging_csv(
+                    &test_dist_mat(),
+                    &GaConfig::default(),
+                    7,
+                    &mut StdRng::seed_from_u64(0),
+                    &mut csv,
+                )
+                .unwrap();
+            let csv = String::from_utf8(csv).unwrap();
+            let lines = csv.lines().collect::<Vec<&str>>();
+            assert_eq!(lines.len(), 8);
+            assert_eq!(lines[0], "generation,best,worst,mean,std,diversity");
+            for (idx, line) in lines[1..].iter().enumerate() {
+                let fields = line.split(',').collect::<Vec<&str>>();
+                assert_eq!(fields.len(), 6);
+                assert_eq!(fields[0], (idx + 1).to_string());
+                assert!(fields[1..].iter().all(|field| field.parse::<f64>().is_ok()));
+            }
+        }
+        #[test]
+        fn same_routes_as_step() {
+            let routes = Routes::random(4, 3);
+            let logged = routes
+                .evolve_logging_csv(
+                    &test_dist_mat(),
+                    &GaConfig::default(),
+                    3,
+                    &mut StdRng::seed_from_u64(4),
+                    std::io::sink(),
+                )
+                .unwrap();
+            let mut rng = StdRng::seed_from_u64(4);
+            let mut stepped = routes;
+            for _ in 0..3 {
+                stepped = stepped.step(&test_dist_mat(), &GaConfig::default(), &mut rng);
+            }
+            assert_eq!(logged, stepped);
+        }
+    }
+    mod test_from_tour_lines {
+        use super::*;
+        #[test]
+        fn two_tours() {
+            let tours = "1 2 3 4\n\n4, 2, 1, 3\n";
+            let routes = Routes::from_tour_lines(tours.as_bytes(), 4).unwrap();
+            assert_eq!(
+                routes,
+                Routes::from(vec![
+                    Route::new(vec![0, 1, 2, 3]),
+                    Route::new(vec![3, 1, 0, 2])
+                ])
+            );
+        }
+        #[test]
+        fn invalid_node() {
+            for (tours, token) in [
+                ("1 2 3\n1 x 3\n", "x"),
+                ("1 2 3\n0 1 2\n", "0"),
+                ("1 2 3\n1 2 4\n", "4"),
+            ] {
+                match Routes::from_tour_lines(tours.as_bytes(), 3) {
+                    Err(TourParseError::InvalidNode {
+                        line,
+                        token: invalid,
+                    }) => {
+                        assert_eq!(line, 2);
+                        assert_eq!(invalid, token);
+                    }
+                    other => panic!("Expected an invalid node, got {:?}", other),
+                }
+            }
+        }
+        #[test]
+        fn not_a_permutation() {
+            for tours in ["1 2 3\n\n1 2 2\n", "1 2 3\n\n1 2\n", "1 2 3\n\n1 2 3 1\n"] {
+                match Routes::from_tour_lines(tours.as_bytes(), 3) {
+                    Err(TourParseError::NotAPermutation { line }) => assert_eq!(line, 3),
+                    other => panic!("Expected an invalid tour, got {:?}", other),
+                }
+            }
+        }
+    }
+    mod test_ages {
+        use super::*;
+        use crate::test_utils::circle_dist_mat;
+        #[test]
+        fn new_routes_are_zero() {
+            let routes = Routes::random(4, 5);
+            assert_eq!(routes.ages(), &[0, 0, 0, 0]);
+        }
+        #[test]
+        fn evolve_ages_parents() {
+            let routes = Routes::from(vec![
+                Route::new(vec![0, 1, 2, 3, 4]),
+                Route::new(vec![4, 2, 0, 3, 1]),
+                Route::new(vec![1, 3, 0, 4, 2]),
+            ]);
+            let evolved = routes.evolve(0.5);
+            assert_eq!(evolved.ages().len(), evolved.routes.len());
+            for (route, age) in evolved.iter().zip(evolved.ages()) {
+                if routes.routes.contains(route) {
+                    assert_eq!(*age, 1);
+                } else {
+                    assert_eq!(*age, 0);
+                }
+            }
+        }
+        #[test]
+        fn surviving_elite_ages_each_generation() {
+            let distance_mat = circle_dist_mat(6);
+            let optimal_route = Route::new(vec![0, 1, 2, 3, 4, 5]);
+            let mut routes = Routes::from(vec![
+                optimal_route.clone(),
+                Route::new(vec![0, 3, 1, 4, 2, 5]),
+                Route::new(vec![0, 2, 4, 1, 3, 5]),
+            ]);
+            // The hexagon has 12 optimal routes (6 starting nodes, 2 directions), keeping 12 routes
+            // guarantees that the optimal route survives independent of how ties are broken.
+            for generation in 1..=3 {
+                routes = routes.evolve(0.5).get_fittest_population(12, &distance_mat);
+                assert_eq!(routes.ages_by_route()[&optimal_route], generation);
+            }
+        }
+        #[test]
+        fn equality_includes_ages() {
+            let routes = Routes::from(vec![Route::new(vec![0, 1, 2]), Route::new(vec![1, 0, 2])]);
+            let mut aged_routes = routes.clone();
+            aged_routes.ages = vec![1, 1];
+            assert_eq!(routes, routes.clone());
+            assert_ne!(aged_routes, routes);
+        }
+    }
+    mod test_replacement {
+        use super::*;
+        use crate::test_utils::circle_dist_mat;
+        #[test]
+        fn fittest_is_get_fittest_population() {
+            let distance_mat = circle_dist_mat(6);
+            let routes = Routes::random(10, 6).evolve(0.5);
+            let survivors =
+                routes.select_survivors(4, &distance_mat, &ReplacementStrategy::Fittest);
+            assert_eq!(survivors.routes.len(), 4);
+            assert_eq!(
+                survivors.get_n_fittest(1, &distance_mat)[0].fitness(&distance_mat),
+                routes.get_n_fittest(1, &distance_mat)[0].fitness(&distance_mat)
+            );
+        }
+        #[test]
+        fn age_based_retires_old_routes() {
+            let distance_mat = circle_dist_mat(8);
+            let replacement = ReplacementStrategy::AgeBased { max_age: 2 };
+            let mut routes = Routes::random(10, 8);
+            for _ in 0..10 {
+                routes = routes
+                    .evolve(0.5)
+                    .select_survivors(10, &distance_mat, &replacement);
+                assert!(routes.ages().iter().all(|age| *age <= 2));
+            }
+        }
+        #[test]
+        fn best_route_is_tracked() {
+            let distance_mat = circle_dist_mat(8);
+            let initial_population = Routes::random(10, 8);
+            let initial_fitness =
+                initial_population.get_n_fittest(1, &distance_mat)[0].fitness(&distance_mat);
+            let (final_population, best_route) = evolve_population_with_replacement(
+                initial_population,
+                10,
+                10,
+                &distance_mat,
+                &ReplacementStrategy::AgeBased { max_age: 1 },
+                0.5,
+            )
+            .unwrap();
+            let best_route = best_route.unwrap();
+            assert!(best_route.fitness(&distance_mat) >= initial_fitness);
+            for route in final_population.iter() {
+                assert!(best_route.fitness(&distance_mat) >= route.fitness(&distance_mat));
+            }
+        }
+        #[test]
+        fn empty_population_has_no_best_route() {
+            let (final_population, best_route) = evolve_population_with_replacement(
+                Routes::from(vec![]),
+                3,
+                10,
+                &circle_dist_mat(8),
+                &ReplacementStrategy::Fittest,
+                0.5,
+            )
+            .unwrap();
+            assert_eq!(final_population.iter().count(), 0);
+            assert_eq!(best_route, None);
+        }
+    }
+    mod test_reproduce {
+        use super::*;
+        use crate::test_utils::{circle_dist_mat, valid_permutation};
+        #[test]
+        fn no_crossover_copies_parents() {
+            let distance_mat = circle_dist_mat(8);
+            let routes = Routes::random(6, 8);
+            let config = GaConfig {
+                crossover_rate: 0.0,
+                mutation_rate: 0.0,
+                ..GaConfig::default()
+            };
+            let offspring = routes.reproduce(&distance_mat, &config, &mut rand::thread_rng());
+            assert_eq!(offspring.routes, routes.routes);
+        }
+        #[test]
+        fn crossover_creates_new_routes() {
+            let distance_mat = circle_dist_mat(8);
+            let routes = Routes::random(6, 8);
+            let config = GaConfig {
+                crossover_rate: 1.0,
+                mutation_rate: 0.0,
+                ..GaConfig::default()
+            };
+            let offspring = routes.reproduce(&distance_mat, &config, &mut rand::thread_rng());
+            assert!(!offspring.routes.is_subset(&routes.routes));
+            for route in offspring.iter() {
+                valid_permutation(&route.indexes, &(0..8).collect::<Vec<usize>>());
+            }
+        }
+    }
+    mod test_proportionate_selection {
+        use super::*;
+        use crate::config::FitnessScaling;
+        use crate::test_utils::circle_dist_mat;
+        #[test]
+        fn creates_population_size_children() {
+            let distance_mat = circle_dist_mat(8);
+            let config = GaConfig {
+                population_size: 9,
+                selection: Selection::Proportionate,
+                ..GaConfig::default()
+            };
+            let routes = Routes::random(4, 8);
+            let parents = routes.select_parents(&distance_mat, &config, &mut rand::thread_rng());
+            assert_eq!(parents.len(), 5);
+        }
+        #[test]
+        fn truncated_route_is_never_selected() {
+            // The optimal route and its reverse are much shorter than the third route, which is
+            // therefore clipped by sigma truncation.
+            let distance_mat = circle_dist_mat(6);
+            let worst_route = Route::new(vec![0, 3, 1, 4, 2, 5]);
+            let routes = Routes::from(vec![
+                Route::new(vec![0, 1, 2, 3, 4, 5]),
+                Route::new(vec![5, 4, 3, 2, 1, 0]),
+                worst_route.clone(),
+            ]);
+            let config = GaConfig {
+                population_size: 50,
+                crossover_rate: 0.0,
+                mutation_rate: 0.0,
+                selection: Selection::Proportionate,
+                fitness_scaling: FitnessScaling::SigmaTruncation { c: 0.5 },
+                ..GaConfig::default()
+            };
+            let offspring = routes.reproduce(&distance_mat, &config, &mut rand::thread_rng());
+            assert!(!offspring.routes.contains(&worst_route));
+            assert!(!offspring.routes.is_empty());
+        }
+    }
+    mod test_fitness_percentiles {
+        use super::*;
+        use crate::test_utils::test_dist_mat;
+        // The routes are 2, 4 and 6 long.
+        fn routes() -> Routes {
+            Routes::from(vec![
+                Route::new(vec![1, 2]),
+                Route::new(vec![0, 1]),
+                Route::new(vec![0, 2]),
+            ])
+        }
+        #[test]
+        fn hand_computed() {
+            assert_eq!(
+                routes().fitness_percentiles(&test_dist_mat(), &[0.0, 0.25, 0.5, 0.9, 1.0]),
+                vec![2.0, 3.0, 4.0, 5.6, 6.0]
+            );
+        }
+        #[test]
+        fn single_route() {
+            assert_eq!(
+                Routes::from(vec![Route::new(vec![0, 1, 2])])
+                    .fitness_percentiles(&test_dist_mat(), &[0.0, 0.3, 1.0]),
+                vec![6.0, 6.0, 6.0]
+            );
+        }
+        #[test]
+        #[should_panic]
+        fn invalid_percentile() {
+            routes().fitness_percentiles(&test_dist_mat(), &[1.5]);
+        }
+        #[test]
+        #[should_panic]
+        fn empty_population() {
+            Routes::from(Vec::new()).fitness_percentiles(&test_dist_mat(), &[0.5]);
+        }
+    }
+    mod test_local_search {
+        use super::*;
+        use crate::test_utils::circle_dist_mat;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn two_opt_finds_better_routes() {
+            // On points in convex position every route without crossing edges is optimal, so all
+            // children refined with 2-opt are optimal.
+            let distance_mat = circle_dist_mat(12);
+            let routes = Routes::random_with(10, 12, &mut StdRng::seed_from_u64(0));
+            let best_distance = |local_search: Option<LocalSearchKind>| {
+                let config = GaConfig {
+                    population_size: 10,
+                    local_search,
+                    ..GaConfig::default()
+                };
+                let next_generation =
+                    routes.step(&distance_mat, &config, &mut StdRng::seed_from_u64(1));
+                distance_mat.get_distance(&next_generation.best(&distance_mat).unwrap().indexes)
+            };
+            let optimum = distance_mat.get_distance(&(0..12).collect::<Vec<usize>>());
+            assert!((best_distance(Some(LocalSearchKind::TwoOpt)) - optimum).abs() < 1e-9);
+            assert!(best_distance(Some(LocalSearchKind::TwoOpt)) < best_distance(None));
+        }
+        #[test]
+        fn or_opt_never_worse() {
+            // The refinement does not draw random numbers, so both runs create the same children
+            // before they are refined.
+            let distance_mat = circle_dist_mat(12);
+            let routes = Routes::random_with(10, 12, &mut StdRng::seed_from_u64(2));
+            let best_distance = |local_search: Option<LocalSearchKind>| {
+                let config = GaConfig {
+                    population_size: 10,
+                    local_search,
+                    ..GaConfig::default()
+                };
+                let offspring =
+                    routes.reproduce(&distance_mat, &config, &mut StdRng::seed_from_u64(3));
+                distance_mat.get_distance(&offspring.best(&distance_mat).unwrap().indexes)
+            };
+            assert!(best_distance(Some(LocalSearchKind::OrOpt)) <= best_distance(None));
+        }
+    }
+    mod test_crowding_distances {
+        use super::*;
+        // Objectives that are looked up per route, to construct fronts with a known geometry.
+        struct FixedObjectives(HashMap<Route, Vec<f64>>);
+        impl MultiObjective for FixedObjectives {
+            type Individual = Route;
+            fn objectives(&self, individual: &Route) -> Vec<f64> {
+                self.0[individual].clone()
+            }
+        }
+        fn front_of(points: &[(f64, f64)]) -> (Routes, FixedObjectives, Vec<Route>) {
+            let front = (0..points.len())
+                .map(|idx| Route::new(vec![idx]))
+                .collect::<Vec<Route>>();
+            let objectives = FixedObjectives(
+                front
+                    .iter()
+                    .zip(points)
+                    .map(|(route, (a, b))| (route.clone(), vec![*a, *b]))
+                    .collect(),
+            );
+            (Routes::from(front.clone()), objectives, front)
+        }
+        #[test]
+        fn middle_point_is_finite_and_smaller() {
+            let (routes, objectives, front) = front_of(&[(0.0, 4.0), (1.0, 1.0), (4.0, 0.0)]);
+            let distances =
+                routes.crowding_distances(&objectives, &front.iter().collect::<Vec<&Route>>());
+            assert!(distances[0].is_infinite());
+            // Both neighbours span the whole range of both objectives.
+            assert_eq!(distances[1], 2.0);
+            assert!(distances[2].is_infinite());
+        }
+        #[test]
+        fn crowded_point_is_smaller() {
+            let (routes, objectives, front) =
+                front_of(&[(0.0, 4.0), (1.0, 3.0), (1.5, 2.5), (4.0, 0.0)]);
+            let distances =
+                routes.crowding_distances(&objectives, &front.iter().collect::<Vec<&Route>>());
+            assert!(distances[1] < distances[2]);
+            assert!(distances[2].is_finite());
+        }
+        #[test]
+        fn in_order_of_the_front() {
+            let (routes, objectives, front) = front_of(&[(0.0, 4.0), (1.0, 1.0), (4.0, 0.0)]);
+            let distances =
+                routes.crowding_distances(&objectives, &[&front[1], &front[2], &front[0]]);
+            assert_eq!(distances[0], 2.0);
+            assert!(distances[1].is_infinite());
+            assert!(distances[2].is_infinite());
+        }
+        #[test]
+        #[should_panic]
+        fn route_not_in_population() {
+            let (routes, objectives, front) = front_of(&[(0.0, 4.0), (1.0, 1.0)]);
+            let outsider = Route::new(vec![5]);
+            routes.crowding_distances(&objectives, &[&front[0], &outsider]);
+        }
+        #[test]
+        fn empty_front() {
+            let (routes, objectives, _) = front_of(&[(0.0, 1.0)]);
+            assert_eq!(
+                routes.crowding_distances(&objectives, &[]),
+                Vec::<f64>::new()
+            );
+        }
+    }
+    mod test_tournament_selection {
+        use super::*;
+        use crate::test_utils::circle_dist_mat;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn large_tournament_picks_best() {
+            // With a huge tournament every route competes, so the best route always wins.
+            let distance_mat = circle_dist_mat(6);
+            let best_route = Route::new(vec![0, 1, 2, 3, 4, 5]);
+            let routes = Routes::from(vec![
+                best_route.clone(),
+                Route::new(vec![0, 3, 1, 4, 2, 5]),
+                Route::new(vec![0, 2, 1, 3, 5, 4]),
+            ]);
+            let config = GaConfig {
+                population_size: 6,
+                selection: Selection::Tournament { size: 200 },
+                ..GaConfig::default()
+            };
+            let parents =
+                routes.select_parents(&distance_mat, &config, &mut StdRng::seed_from_u64(0));
+            assert_eq!(parents.len(), 3);
+            assert!(parents
+                .iter()
+                .all(|(parent_a, parent_b)| **parent_a == best_route && **parent_b == best_route));
+        }
+    }
+    mod test_adaptive_selection {
+        use super::*;
+        use crate::config::AdaptiveSelectionParams;
+        #[test]
+        fn edge_diversity() {
+            // Rotations and reversals of the same round-trip use the same edges.
+            let collapsed = Routes::from(vec![
+                Route::new(vec![0, 1, 2, 3, 4, 5]),
+                Route::new(vec![2, 3, 4, 5, 0, 1]),
+                Route::new(vec![5, 4, 3, 2, 1, 0]),
+            ]);
+            assert_eq!(collapsed.edge_diversity(), 0.0);
+            // Both routes share no edge.
+            let disjoint = Routes::from(vec![
+                Route::new(vec![0, 1, 2, 3, 4]),
+                Route::new(vec![0, 2, 4, 1, 3]),
+            ]);
+            assert_eq!(disjoint.edge_diversity(), 1.0);
+            assert_eq!(Routes::from(Vec::new()).edge_diversity(), 0.0);
+        }
+        #[test]
+        fn tournament_shrinks_when_diversity_collapses() {
+            use rand::rngs::StdRng;
+            use rand::SeedableRng;
+            // On a pentagon only the rotations and reversals of the outer round-trip are
+            // shortest, so only keeping the fittest routes collapses the population onto them.
+            let distance_mat = crate::test_utils::circle_dist_mat(5);
+            let config = GaConfig {
+                population_size: 2,
+                adaptive_selection: Some(AdaptiveSelectionParams {
+                    min_size: 2,
+                    max_size: 10,
+                }),
+                ..GaConfig::default()
+            };
+            let mut routes = Routes::from(vec![
+                Route::new(vec![0, 1, 2, 3, 4]),
+                Route::new(vec![0, 2, 4, 1, 3]),
+            ]);
+            assert_eq!(routes.effective_tournament_size(&config), Some(10));
+            let mut rng = StdRng::seed_from_u64(5);
+            for _ in 0..50 {
+                routes = routes.step(&distance_mat, &config, &mut rng);
+            }
+            assert_eq!(routes.edge_diversity(), 0.0);
+            assert_eq!(routes.effective_tournament_size(&config), Some(2));
+        }
+        #[test]
+        fn without_adaptation() {
+            let routes = Routes::random(5, 6);
+            assert_eq!(routes.effective_tournament_size(&GaConfig::default()), None);
+            let config = GaConfig {
+                selection: Selection::Tournament { size: 4 },
+                ..GaConfig::default()
+            };
+            assert_eq!(routes.effective_tournament_size(&config), Some(4));
+        }
+        #[test]
+        fn adaptive_parents() {
+            // With adaptive selection as many pairs are drawn as for any tournament.
+            let distance_mat = crate::test_utils::circle_dist_mat(8);
+            let config = GaConfig {
+                population_size: 10,
+                adaptive_selection: Some(AdaptiveSelectionParams {
+                    min_size: 1,
+                    max_size: 4,
+                }),
+                ..GaConfig::default()
+            };
+            let routes = Routes::random(10, 8);
+            let parents = routes.select_parents(&distance_mat, &config, &mut rand::thread_rng());
+            assert_eq!(parents.len(), 5);
         }
     }
-    /// Iterate over the individuals of your population.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use genetic_algo::routes::Routes;
-    /// use genetic_algo::route::Route;
-    /// use crate::genetic_algo::gen_traits::Population;
-    ///
-    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
-    /// for route in routes.iter(){
-    ///     println!("{:?}", route);
-    /// }
-    /// ```
-    fn iter(&'a self) -> std::collections::hash_set::Iter<Route> {
-        self.routes.iter()
+    mod test_step_with_operators {
+        use super::*;
+        use crate::route::MutationKind;
+        use crate::test_utils::{circle_dist_mat, valid_permutation};
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn operators_learn() {
+            let distance_mat = circle_dist_mat(12);
+            let config = GaConfig {
+                population_size: 10,
+                mutation_rate: 1.0,
+                adaptive_operators: true,
+                ..GaConfig::default()
+            };
+            let mut operators = AdaptiveOperators::default();
+            let mut rng = StdRng::seed_from_u64(9);
+            let mut routes = Routes::random(10, 12);
+            for _ in 0..10 {
+                routes =
+                    routes.step_with_operators(&distance_mat, &config, &mut operators, &mut rng);
+                assert_eq!(routes.ages().len(), 10);
+                for route in routes.iter() {
+                    valid_permutation(&route.indexes, &(0..12).collect::<Vec<usize>>());
+                }
+            }
+            assert_ne!(operators.probabilities(), vec![0.25; 4]);
+        }
+        #[test]
+        fn operators_unused_without_adaptation() {
+            let distance_mat = circle_dist_mat(8);
+            let mut operators =
+                AdaptiveOperators::new(vec![MutationKind::Swap, MutationKind::Inversion]);
+            let routes = Routes::random(6, 8);
+            let next_generation = routes.step_with_operators(
+                &distance_mat,
+                &GaConfig::default(),
+                &mut operators,
+                &mut StdRng::seed_from_u64(2),
+            );
+            assert_eq!(operators.probabilities(), vec![0.5, 0.5]);
+            assert_eq!(
+                next_generation,
+                routes.step(
+                    &distance_mat,
+                    &GaConfig::default(),
+                    &mut StdRng::seed_from_u64(2)
+                )
+            );
+        }
+        #[test]
+        fn two_nodes() {
+            let distance_mat = DistanceMat::new(vec![vec![0.0, 1.0], vec![2.0, 0.0]]);
+            let config = GaConfig {
+                population_size: 2,
+                mutation_rate: 1.0,
+                adaptive_operators: true,
+                ..GaConfig::default()
+            };
+            let mut rng = StdRng::seed_from_u64(4);
+            let mut routes = Routes::from(vec![Route::new(vec![0, 1]), Route::new(vec![1, 0])]);
+            for _ in 0..20 {
+                routes = routes.step(&distance_mat, &config, &mut rng);
+                for route in routes.iter() {
+                    valid_permutation(&route.indexes, &[0, 1]);
+                }
+            }
+        }
+        // A crossover whose child is a copy of the first parent, so it never improves a route.
+        #[derive(Debug)]
+        struct CopyFirstParent;
+        impl crate::route::CrossoverOp for CopyFirstParent {
+            fn apply(&self, a: &Route, _b: &Route, _rng: &mut dyn rand::RngCore) -> Route {
+                a.clone()
+            }
+        }
+        #[test]
+        fn never_improving_crossover_decays() {
+            let distance_mat = circle_dist_mat(12);
+            let config = GaConfig {
+                population_size: 10,
+                crossover_rate: 1.0,
+                mutation_rate: 0.0,
+                adaptive_operators: true,
+                ..GaConfig::default()
+            };
+            let mut routes = Routes::random_with(10, 12, &mut StdRng::seed_from_u64(1))
+                .with_operators(AdaptiveOperators::default().with_crossovers(vec![
+                    Arc::new(CopyFirstParent),
+                    Arc::new(crate::route::OrderedCrossover),
+                ]));
+            let mut rng = StdRng::seed_from_u64(5);
+            let mut probability = routes.operators().crossover_probabilities()[0];
+            assert_eq!(probability, 0.5);
+            for _ in 0..20 {
+                routes = routes.step(&distance_mat, &config, &mut rng);
+                let next_probability = routes.operators().crossover_probabilities()[0];
+                assert!(next_probability <= probability);
+                probability = next_probability;
+            }
+            assert!(probability < 0.5);
+        }
+        #[test]
+        fn step_keeps_learning() {
+            let distance_mat = circle_dist_mat(8);
+            let config = GaConfig {
+                mutation_rate: 1.0,
+                adaptive_operators: true,
+                ..GaConfig::default()
+            };
+            let mut by_step = Routes::random_with(6, 8, &mut StdRng::seed_from_u64(2));
+            let mut by_operators = by_step.clone();
+            let mut operators = AdaptiveOperators::default();
+            let (mut rng_step, mut rng_operators) =
+                (StdRng::seed_from_u64(4), StdRng::seed_from_u64(4));
+            for _ in 0..5 {
+                by_step = by_step.step(&distance_mat, &config, &mut rng_step);
+                by_operators = by_operators.step_with_operators(
+                    &distance_mat,
+                    &config,
+                    &mut operators,
+                    &mut rng_operators,
+                );
+            }
+            assert_eq!(by_step, by_operators);
+            assert_eq!(by_step.operators(), &operators);
+            assert_ne!(operators.probabilities(), vec![0.25; 4]);
+        }
     }
-}
-
-/// Given an initial population evolve it for `n_generations` while keeping `size_generation`
-/// individuals. The final population will be returned.
-///
-/// # Arguments
-///
-/// * `initial_population` - Your initial population that should be evolved.
-/// * `n_generations` - How many times should your population be evolved?
-/// * `size_generation` - How many individuals should be kept after evolving it.
-/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
-///
-/// # Examples
-///
-/// ```
-/// use genetic_algo::routes::{Routes, evolve_population};
-/// use genetic_algo::route::Route;
-/// use genetic_algo::distance_mat::DistanceMat;
-///
-/// let evolved_population = evolve_population(
-///     Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]),
-///     10,
-///     10,
-///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]),
-///     0
-/// );
-/// ```
-pub fn evolve_population(
-    initial_population: Routes,
-    n_generations: usize,
-    size_generation: usize,
-    distance_matrix: &DistanceMat,
-    n_jobs: usize,
-) -> Routes {
-    if n_jobs == 0 {
-        // single-thread
-        (0..n_generations).fold(initial_population, |pop, _| {
-            pop.evolve(0.5)
-                .get_fittest_population(size_generation, distance_matrix)
-        })
-    } else {
-        // Multi-threaded execution
-        thread::scope(|s| {
-            let mut result = Vec::new();
-            for _ in 0..n_jobs {
-                let this_population = initial_population.clone();
-                result.push(s.spawn(move |_| -> Vec<Route> {
-                    (0..((n_generations / n_jobs) + 1))
-                        .fold(this_population, |pop, _| {
-                            pop.evolve(0.5)
-                                .get_fittest_population(size_generation, distance_matrix)
-                        })
-                        .get_n_fittest(size_generation, distance_matrix)
-                }))
+    mod test_step {
+        use super::*;
+        use crate::test_utils::circle_dist_mat;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn preserves_population_size() {
+            let distance_mat = circle_dist_mat(8);
+            for selection in [
+                Selection::AllPairs,
+                Selection::Proportionate,
+                Selection::Tournament { size: 3 },
+            ] {
+                let config = GaConfig {
+                    population_size: 10,
+                    selection,
+                    elitism: 2,
+                    ..GaConfig::default()
+                };
+                let next_generation = Routes::random(10, 8).step(
+                    &distance_mat,
+                    &config,
+                    &mut StdRng::seed_from_u64(3),
+                );
+                assert_eq!(next_generation.routes.len(), 10);
+                assert_eq!(next_generation.ages().len(), 10);
             }
-            Routes::from(
-                result
-                    .into_iter()
-                    .map(|thread| thread.join().unwrap())
-                    .flatten()
-                    .collect::<Vec<Route>>(),
-            )
-        })
-        .unwrap()
+        }
+        #[test]
+        fn keeps_elites() {
+            // All parents are too old to survive, only the elites are kept regardless of their age.
+            let distance_mat = circle_dist_mat(8);
+            let routes = Routes::random(10, 8);
+            let elites = routes
+                .sorted_by_fitness(&distance_mat)
+                .into_iter()
+                .take(3)
+                .map(|(route, _)| route.clone())
+                .collect::<Vec<Route>>();
+            let config = GaConfig {
+                population_size: 10,
+                replacement: ReplacementStrategy::AgeBased { max_age: 0 },
+                elitism: 3,
+                ..GaConfig::default()
+            };
+            let next_generation =
+                routes.step(&distance_mat, &config, &mut StdRng::seed_from_u64(5));
+            let ages = next_generation.ages_by_route();
+            for elite in elites.iter() {
+                assert_eq!(ages[elite], 1);
+            }
+            assert_eq!(ages.values().filter(|age| **age == 1).count(), 3);
+        }
+        #[test]
+        fn without_elitism_old_routes_retire() {
+            let distance_mat = circle_dist_mat(8);
+            let config = GaConfig {
+                population_size: 10,
+                replacement: ReplacementStrategy::AgeBased { max_age: 0 },
+                ..GaConfig::default()
+            };
+            let next_generation =
+                Routes::random(10, 8).step(&distance_mat, &config, &mut StdRng::seed_from_u64(5));
+            assert!(next_generation.ages().iter().all(|age| *age == 0));
+        }
     }
-}
-/// Compute the time in milliseconds that it takes for a genetic algorithm to run.
-///
-/// # Arguments
-///
-/// * `n_generations` - How many generations should the algorithm evolve?
-/// * `size_generation` - How many individuals should be selected at the end of each
-/// evolution step.
-/// * `dist_mat` - What is the distance matrix for your TSP.
-///
-/// ```
-pub fn benchmark_population(
-    n_generations: usize,
-    size_generation: usize,
-    dist_mat: &DistanceMat,
-    n_jobs: usize,
-) -> (u64, f64) {
-    // End-to-end test: does the error of the route get down?
-    let before = Instant::now();
-    let final_population = evolve_population(
-        Routes::random(size_generation, dist_mat.n_units()),
-        n_generations,
-        size_generation,
-        dist_mat,
-        n_jobs,
-    );
-    let duration = before.elapsed();
-    let nanos = duration.subsec_nanos() as u64;
-    (
-        (1000 * 1000 * 1000 * duration.as_secs() + nanos) / (1000 * 1000),
-        final_population.get_n_fittest(1, dist_mat)[0].fitness(dist_mat),
-    )
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::test_utils::{test_dist_mat, valid_permutation};
-
-    #[test]
-    fn from_routes_vector() {
-        assert_eq!(
+    mod test_evolve_to_target {
+        use super::*;
+        use crate::test_utils::circle_dist_mat;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn reaches_optimum() {
+            // The optimal route on a hexagon with side length 1 is 6 long.
+            let distance_mat = circle_dist_mat(6);
+            let config = GaConfig {
+                population_size: 10,
+                ..GaConfig::default()
+            };
+            let (routes, reached_in) = Routes::random(10, 6)
+                .evolve_to_target(
+                    &distance_mat,
+                    &config,
+                    6.0 + 1e-9,
+                    100,
+                    &mut StdRng::seed_from_u64(0),
+                )
+                .unwrap();
+            assert!(reached_in.is_some());
+            assert!(reached_in.unwrap() <= 100);
+            assert!(
+                distance_mat.get_distance(&routes.get_n_fittest(1, &distance_mat)[0].indexes)
+                    <= 6.0 + 1e-9
+            );
+        }
+        #[test]
+        fn already_reached() {
+            let distance_mat = circle_dist_mat(6);
+            let routes = Routes::from(vec![Route::new(vec![0, 1, 2, 3, 4, 5])]);
+            let (evolved_routes, reached_in) = routes
+                .evolve_to_target(
+                    &distance_mat,
+                    &GaConfig::default(),
+                    6.0 + 1e-9,
+                    10,
+                    &mut StdRng::seed_from_u64(0),
+                )
+                .unwrap();
+            assert_eq!(reached_in, Some(0));
+            assert_eq!(evolved_routes, routes);
+        }
+        #[test]
+        fn unreachable_target() {
+            let distance_mat = circle_dist_mat(6);
+            let config = GaConfig {
+                population_size: 5,
+                ..GaConfig::default()
+            };
+            let (routes, reached_in) = Routes::random(5, 6)
+                .evolve_to_target(
+                    &distance_mat,
+                    &config,
+                    1.0,
+                    5,
+                    &mut StdRng::seed_from_u64(0),
+                )
+                .unwrap();
+            assert_eq!(reached_in, None);
+            assert_eq!(routes.routes.len(), 5);
+        }
+    }
+    mod test_evolve_with_callback {
+        use super::*;
+        use crate::test_utils::circle_dist_mat;
+        #[test]
+        fn called_every_generation() {
+            let distance_mat = circle_dist_mat(8);
+            let mut generations = Vec::new();
+            Routes::random(10, 8)
+                .evolve_with_callback(
+                    &distance_mat,
+                    &GaConfig::default(),
+                    5,
+                    &mut rand::thread_rng(),
+                    |stats| generations.push(stats.generation),
+                )
+                .unwrap();
+            assert_eq!(generations, vec![1, 2, 3, 4, 5]);
+        }
+        #[test]
+        fn best_route_matches_population() {
+            let distance_mat = circle_dist_mat(8);
+            let mut best_routes = Vec::new();
+            let routes = Routes::random(10, 8)
+                .evolve_with_callback(
+                    &distance_mat,
+                    &GaConfig::default(),
+                    5,
+                    &mut rand::thread_rng(),
+                    |stats| {
+                        assert_eq!(
+                            stats.best_distance,
+                            distance_mat.get_distance(&stats.best_route.indexes)
+                        );
+                        best_routes.push(stats.best_route.clone())
+                    },
+                )
+                .unwrap();
+            assert_eq!(best_routes.last(), routes.best(&distance_mat));
+        }
+        #[test]
+        fn plateau_counter_increments() {
+            // Every route through three nodes is equally long, so the best never improves.
+            let mut counters = Vec::new();
+            Routes::random(3, 3)
+                .evolve_with_callback(
+                    &test_dist_mat(),
+                    &GaConfig::default(),
+                    5,
+                    &mut rand::thread_rng(),
+                    |stats| counters.push(stats.generations_since_improvement),
+                )
+                .unwrap();
+            assert_eq!(counters, vec![1, 2, 3, 4, 5]);
+        }
+        #[test]
+        fn plateau_counter_resets_on_improvement() {
+            // 2-opt untangles the crossing routes in the first generation, afterwards they are
+            // optimal. All optimal routes around the square are exactly 4 long.
+            let distance_mat =
+                DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+            let config = GaConfig {
+                population_size: 2,
+                local_search: Some(LocalSearchKind::TwoOpt),
+                ..GaConfig::default()
+            };
+            let mut counters = Vec::new();
             Routes::from(vec![
-                Route {
-                    indexes: vec![0, 1, 2]
-                },
-                Route {
-                    indexes: vec![0, 2, 1]
-                }
-            ])
-            .routes,
-            HashSet::from([
-                Route {
-                    indexes: vec![0, 1, 2]
-                },
-                Route {
-                    indexes: vec![0, 2, 1]
-                }
+                Route::new(vec![0, 2, 1, 3]),
+                Route::new(vec![1, 3, 0, 2]),
             ])
-        )
+            .evolve_with_callback(
+                &distance_mat,
+                &config,
+                3,
+                &mut rand::thread_rng(),
+                |stats| counters.push(stats.generations_since_improvement),
+            )
+            .unwrap();
+            assert_eq!(counters, vec![0, 1, 2]);
+        }
+        #[test]
+        fn plateau_counter_follows_shortest_distance() {
+            let distance_mat = circle_dist_mat(10);
+            let routes = Routes::random(10, 10);
+            let mut shortest_distance =
+                distance_mat.get_distance(&routes.best(&distance_mat).unwrap().indexes);
+            let mut last_counter = 0;
+            routes
+                .evolve_with_callback(
+                    &distance_mat,
+                    &GaConfig::default(),
+                    20,
+                    &mut rand::thread_rng(),
+                    |stats| {
+                        if stats.best_distance < shortest_distance {
+                            shortest_distance = stats.best_distance;
+                            assert_eq!(stats.generations_since_improvement, 0);
+                        } else {
+                            assert_eq!(stats.generations_since_improvement, last_counter + 1);
+                        }
+                        last_counter = stats.generations_since_improvement;
+                    },
+                )
+                .unwrap();
+        }
     }
-    #[test]
-    fn random_constructor() {
-        let n_objects = 3;
-        let population = Routes::random(3, n_objects);
-        assert_eq!(population.routes.len(), 3);
-        for route in population.routes {
-            valid_permutation(&route.indexes, &(0..n_objects).collect::<Vec<usize>>());
+    mod test_tie_breaking {
+        use super::*;
+        #[test]
+        fn equal_length_lexicographic_winner() {
+            // All round-trips through the three nodes are 6 long.
+            let distance_mat = test_dist_mat();
+            let routes = Routes::from(vec![
+                Route::new(vec![2, 1, 0]),
+                Route::new(vec![1, 2, 0]),
+                Route::new(vec![0, 2, 1]),
+                Route::new(vec![0, 1, 2]),
+            ]);
+            assert_eq!(routes.best(&distance_mat), Some(&Route::new(vec![0, 1, 2])));
+            assert_eq!(
+                routes.get_n_fittest(2, &distance_mat),
+                vec![Route::new(vec![0, 1, 2]), Route::new(vec![0, 2, 1])]
+            );
+            assert_eq!(
+                routes
+                    .sorted_by_fitness(&distance_mat)
+                    .into_iter()
+                    .map(|(route, _)| route.clone())
+                    .collect::<Vec<Route>>(),
+                vec![
+                    Route::new(vec![0, 1, 2]),
+                    Route::new(vec![0, 2, 1]),
+                    Route::new(vec![1, 2, 0]),
+                    Route::new(vec![2, 1, 0]),
+                ]
+            );
+        }
+        #[test]
+        fn shorter_route_beats_ordering() {
+            let routes = Routes::from(vec![Route::new(vec![0, 1, 2]), Route::new(vec![1, 0])]);
+            assert_eq!(routes.best(&test_dist_mat()), Some(&Route::new(vec![1, 0])));
+        }
+        #[test]
+        fn empty_population() {
+            assert_eq!(Routes::from(vec![]).best(&test_dist_mat()), None);
+        }
+        #[test]
+        fn same_seed_same_offspring() {
+            use rand::rngs::StdRng;
+            use rand::SeedableRng;
+            // Every `Routes` hashes with its own random state, so the same routes are stored in a
+            // different order by every population.
+            let routes = vec![
+                Route::new(vec![0, 1, 2, 3]),
+                Route::new(vec![1, 3, 0, 2]),
+                Route::new(vec![2, 0, 3, 1]),
+                Route::new(vec![3, 2, 1, 0]),
+            ];
+            let distance_mat = crate::test_utils::circle_dist_mat(4);
+            let offspring = |routes: Vec<Route>| {
+                Routes::from(routes).reproduce(
+                    &distance_mat,
+                    &GaConfig::default(),
+                    &mut StdRng::seed_from_u64(7),
+                )
+            };
+            let expected = offspring(routes.clone());
+            for _ in 0..10 {
+                let mut reversed = routes.clone();
+                reversed.reverse();
+                assert_eq!(offspring(reversed), expected);
+            }
         }
     }
-    #[test]
-    fn test_fitness() {
-        let distance_mat = test_dist_mat();
-        let population = Routes::from(vec![Route::new(vec![1, 2, 0]), Route::new(vec![1, 0])]);
-        let fitnesses = population.fitnesses(&distance_mat);
-        assert_eq!(fitnesses.len(), 2);
-
-        for element in vec![
-            (-6.0, &Route::new(vec![1, 2, 0])),
-            (-2.0, &Route::new(vec![1, 0])),
-        ] {
-            assert!(fitnesses.contains(&element))
+    mod test_merge {
+        use super::*;
+        use crate::test_utils::circle_dist_mat;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn combined_size() {
+            let mut rng = StdRng::seed_from_u64(8);
+            let routes = Routes::random_with(10, 9, &mut rng);
+            let other = Routes::random_with(7, 9, &mut rng);
+            let n_shared = routes
+                .iter()
+                .filter(|route| other.routes.contains(route))
+                .count();
+            let merged = routes.clone().merge(other.clone());
+            assert_eq!(merged.ages().len(), 17 - n_shared);
+            assert!(routes
+                .iter()
+                .chain(other.iter())
+                .all(|route| merged.routes.contains(route)));
+        }
+        #[test]
+        fn shared_routes_keep_older_age() {
+            let route = Route::new(vec![0, 1, 2]);
+            let routes = Routes::from_aged_routes(HashMap::from([(route.clone(), 3)]));
+            let other = Routes::from_aged_routes(HashMap::from([
+                (route.clone(), 5),
+                (Route::new(vec![1, 0, 2]), 1),
+            ]));
+            let merged = routes.merge(other);
+            assert_eq!(merged.ages_by_route()[&route], 5);
+            assert_eq!(merged.ages().len(), 2);
+        }
+        #[test]
+        fn top_k_keeps_best() {
+            let distance_mat = circle_dist_mat(8);
+            let mut rng = StdRng::seed_from_u64(2);
+            let routes = Routes::random_with(12, 8, &mut rng);
+            let other = Routes::random_with(12, 8, &mut rng);
+            let expected = routes
+                .clone()
+                .merge(other.clone())
+                .sorted_by_fitness(&distance_mat)
+                .into_iter()
+                .take(5)
+                .map(|(route, _)| route.clone())
+                .collect::<HashSet<Route>>();
+            let best = routes.merge_top_k(other, &distance_mat, 5);
+            assert_eq!(best.ages().len(), 5);
+            assert_eq!(best.routes, expected);
+        }
+        #[test]
+        fn top_k_larger_than_population() {
+            let distance_mat = circle_dist_mat(4);
+            let merged = Routes::random(3, 4).merge_top_k(Routes::from(vec![]), &distance_mat, 10);
+            assert_eq!(merged.ages().len(), 3);
+        }
+    }
+    mod test_inject {
+        use super::*;
+        use crate::test_utils::circle_dist_mat;
+        #[test]
+        fn replaces_worst() {
+            // On a hexagon of side length 1 the three routes have length 7.46, 9.93 and 10.46.
+            let distance_mat = circle_dist_mat(6);
+            let mut routes = Routes::from(vec![
+                Route::new(vec![0, 1, 2, 3, 5, 4]),
+                Route::new(vec![0, 2, 4, 1, 3, 5]),
+                Route::new(vec![0, 3, 1, 4, 2, 5]),
+            ]);
+            routes.inject(Route::new(vec![5, 4, 3, 2, 1, 0]), &distance_mat);
+            assert_eq!(
+                routes,
+                Routes::from(vec![
+                    Route::new(vec![0, 1, 2, 3, 5, 4]),
+                    Route::new(vec![0, 2, 4, 1, 3, 5]),
+                    Route::new(vec![5, 4, 3, 2, 1, 0]),
+                ])
+            );
+        }
+        #[test]
+        fn improves_best() {
+            let distance_mat = circle_dist_mat(4);
+            let mut routes = Routes::from(vec![
+                Route::new(vec![0, 2, 1, 3]),
+                Route::new(vec![0, 1, 3, 2]),
+            ]);
+            routes.inject(Route::new(vec![3, 2, 1, 0]), &distance_mat);
+            assert_eq!(
+                routes.get_n_fittest(1, &distance_mat),
+                vec![Route::new(vec![3, 2, 1, 0])]
+            );
+            assert_eq!(routes.routes.len(), 2);
+        }
+        #[test]
+        fn already_contained() {
+            let distance_mat = circle_dist_mat(4);
+            let mut routes = Routes::from(vec![
+                Route::new(vec![0, 2, 1, 3]),
+                Route::new(vec![0, 1, 3, 2]),
+            ]);
+            routes.inject(Route::new(vec![0, 1, 3, 2]), &distance_mat);
+            assert_eq!(routes.routes.len(), 2);
+        }
+        #[test]
+        #[should_panic]
+        fn invalid_route() {
+            let mut routes = Routes::from(vec![Route::new(vec![0, 1, 2])]);
+            routes.inject(Route::new(vec![0, 1]), &test_dist_mat());
         }
     }
     mod test_get_n_fittest {
@@ -380,9 +4205,7 @@ mod tests {
             ]);
             assert_eq!(
                 routes.get_fittest_population(0, &distance_mat),
-                Routes {
-                    routes: HashSet::new()
-                },
+                Routes::from(vec![]),
             )
         }
         #[test]
@@ -395,9 +4218,7 @@ mod tests {
             ]);
             assert_eq!(
                 routes.get_fittest_population(1, &distance_mat),
-                Routes {
-                    routes: HashSet::from([Route::new(vec![1, 0]),]),
-                },
+                Routes::from(vec![Route::new(vec![1, 0]),]),
             )
         }
         #[test]
@@ -410,9 +4231,7 @@ mod tests {
             ]);
             assert_eq!(
                 routes.get_fittest_population(2, &distance_mat),
-                Routes {
-                    routes: HashSet::from([Route::new(vec![1, 0]), Route::new(vec![2, 0])]),
-                },
+                Routes::from(vec![Route::new(vec![1, 0]), Route::new(vec![2, 0])]),
             )
         }
         #[test]
@@ -425,13 +4244,11 @@ mod tests {
             ]);
             assert_eq!(
                 routes.get_fittest_population(3, &distance_mat),
-                Routes {
-                    routes: HashSet::from([
-                        Route::new(vec![1, 0]),
-                        Route::new(vec![2, 0]),
-                        Route::new(vec![1, 2, 0]),
-                    ]),
-                },
+                Routes::from(vec![
+                    Route::new(vec![1, 0]),
+                    Route::new(vec![2, 0]),
+                    Route::new(vec![1, 2, 0]),
+                ]),
             )
         }
     }
@@ -458,7 +4275,32 @@ mod tests {
             );
             assert!(new_routes.routes.len() >= 3);
             for route in new_routes.routes {
-                valid_permutation(&vec![0, 1, 2], &route.indexes);
+                valid_permutation(&[0, 1, 2], &route.indexes);
+            }
+        }
+    }
+    mod test_evolve_population {
+        use super::*;
+        use crate::test_utils::{circle_dist_mat, valid_permutation};
+        #[test]
+        fn keeps_size_and_best_route() {
+            let distance_mat = circle_dist_mat(8);
+            let initial_population = Routes::random(10, 8);
+            let initial_fitness =
+                initial_population.get_n_fittest(1, &distance_mat)[0].fitness(&distance_mat);
+            for n_jobs in [0, 2] {
+                let evolved_population =
+                    evolve_population(initial_population.clone(), 5, 10, &distance_mat, n_jobs)
+                        .unwrap();
+                let n_routes = evolved_population.iter().count();
+                assert!(n_routes >= 10 && n_routes <= 10 * n_jobs.max(1));
+                for route in evolved_population.iter() {
+                    valid_permutation(&(0..8).collect::<Vec<usize>>(), &route.indexes);
+                }
+                assert!(
+                    evolved_population.get_n_fittest(1, &distance_mat)[0].fitness(&distance_mat)
+                        >= initial_fitness
+                );
             }
         }
     }
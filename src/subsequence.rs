@@ -1,8 +1,9 @@
-use crate::utils::get_random_elem_from_range;
+use crate::utils::{get_random_elem_from_range, random_index_with};
+use rand::Rng;
 
 /// The `Subsequence`-object only stores the indexes of a potential subsequences. Then based on a sequence, operations
 /// on that subsequence can be applied.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Subsequence {
     /// Where does the subsequence start?
     pub start_index: usize,
@@ -35,6 +36,19 @@ impl Subsequence {
             length: get_random_elem_from_range(1..(len_sequence - start_index - 1)),
         }
     }
+    /// Create a new, random subsequence by sampling from the random number generator provided.
+    ///
+    /// # Arguments
+    ///
+    /// * `len_sequence` - What is the len of the actual sequence that should be subsequenced?
+    /// * `rng` - The random number generator used to sample the subsequence.
+    pub fn random_subsequence_with(len_sequence: usize, rng: &mut impl Rng) -> Self {
+        let start_index = random_index_with(0..(len_sequence - 2), rng);
+        Subsequence::new(
+            start_index,
+            random_index_with(1..(len_sequence - start_index - 1), rng),
+        )
+    }
     /// Based on an actual sequence, get all elements that are in the subsequence
     ///
     /// # Arguments
@@ -82,26 +96,37 @@ mod tests {
     mod test_random_subsequence {
         use super::*;
         #[test]
-        #[test]
-        #[test]
-        #[test]
         fn test_max_10() {
             let max_value = 10;
-            let random_subsequence = Subsequence::random_subsequence(max_value);
-            assert!(random_subsequence.start_index < max_value);
-            assert!(random_subsequence.length < max_value - random_subsequence.start_index);
-            assert!(random_subsequence.start_index + random_subsequence.length < max_value);
+            for _ in 0..4 {
+                let random_subsequence = Subsequence::random_subsequence(max_value);
+                assert!(random_subsequence.start_index < max_value);
+                assert!(random_subsequence.length < max_value - random_subsequence.start_index);
+                assert!(random_subsequence.start_index + random_subsequence.length < max_value);
+            }
         }
         #[test]
-        #[test]
-        #[test]
-        #[test]
         fn test_max_2() {
             let max_value = 2;
-            let random_subsequence = Subsequence::random_subsequence(max_value);
-            assert!(random_subsequence.start_index < max_value);
-            assert!(random_subsequence.length < max_value - random_subsequence.start_index);
-            assert!(random_subsequence.start_index + random_subsequence.length < max_value);
+            for _ in 0..4 {
+                let random_subsequence = Subsequence::random_subsequence(max_value);
+                assert!(random_subsequence.start_index < max_value);
+                assert!(random_subsequence.length < max_value - random_subsequence.start_index);
+                assert!(random_subsequence.start_index + random_subsequence.length < max_value);
+            }
+        }
+    }
+    mod test_random_subsequence_with {
+        use super::*;
+        #[test]
+        fn test_max_10() {
+            let max_value = 10;
+            let mut rng = rand::thread_rng();
+            for _ in 0..100 {
+                let random_subsequence = Subsequence::random_subsequence_with(max_value, &mut rng);
+                assert!(random_subsequence.length < max_value - random_subsequence.start_index);
+                assert!(random_subsequence.start_index + random_subsequence.length < max_value);
+            }
         }
     }
     mod test_get_values_in_subsequence {
@@ -0,0 +1,113 @@
+use crate::route::Route;
+use crate::utils::random_permutation_with;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Assert that `route` visits each of the nodes `0..n_nodes` exactly once, e.g. that an operator
+/// returned a valid route.
+///
+/// # Arguments
+///
+/// * `route` - The route to check.
+/// * `n_nodes` - How many nodes the route should visit.
+///
+/// # Panics
+///
+/// If the route has not `n_nodes` nodes, visits a node outside of `0..n_nodes` or visits a node
+/// more than once.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algo::route::Route;
+/// use genetic_algo::test_support::assert_valid_permutation;
+///
+/// assert_valid_permutation(&Route::new(vec![2, 0, 1]), 3);
+/// ```
+pub fn assert_valid_permutation(route: &Route, n_nodes: usize) {
+    assert_eq!(
+        route.nodes().len(),
+        n_nodes,
+        "The route {:?} does not visit {} nodes.",
+        route.nodes(),
+        n_nodes
+    );
+    let mut visited = vec![false; n_nodes];
+    for node in route.nodes() {
+        assert!(
+            *node < n_nodes,
+            "The route {:?} visits the node {}, which is not one of the {} nodes.",
+            route.nodes(),
+            node,
+            n_nodes
+        );
+        assert!(
+            !visited[*node],
+            "The route {:?} visits the node {} more than once.",
+            route.nodes(),
+            node
+        );
+        visited[*node] = true;
+    }
+}
+
+/// Create a random route over the nodes `0..n_nodes` from a `seed`. As the route only depends on
+/// the seed, it can be plugged into property testing frameworks that generate integers, e.g. with
+/// `any::<u64>().prop_map(|seed| arbitrary_route(10, seed))` in `proptest` or from an `u64`
+/// generated by `quickcheck`, and failing cases shrink to a reproducible seed.
+///
+/// # Arguments
+///
+/// * `n_nodes` - How many nodes the route visits.
+/// * `seed` - The seed of the random number generator the route is created with.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algo::test_support::{arbitrary_route, assert_valid_permutation};
+///
+/// let route = arbitrary_route(10, 42);
+/// assert_valid_permutation(&route, 10);
+/// assert_eq!(route, arbitrary_route(10, 42));
+/// ```
+pub fn arbitrary_route(n_nodes: usize, seed: u64) -> Route {
+    Route::new(random_permutation_with(
+        &(0..n_nodes).collect::<Vec<usize>>(),
+        &mut StdRng::seed_from_u64(seed),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    mod test_assert_valid_permutation {
+        use super::*;
+        #[test]
+        fn valid_routes() {
+            assert_valid_permutation(&Route::new(vec![]), 0);
+            assert_valid_permutation(&Route::new(vec![3, 1, 0, 2]), 4);
+        }
+        #[test]
+        #[should_panic]
+        fn wrong_length() {
+            assert_valid_permutation(&Route::new(vec![1, 0]), 3);
+        }
+        #[test]
+        #[should_panic]
+        fn unknown_node() {
+            assert_valid_permutation(&Route::new(vec![0, 1, 3]), 3);
+        }
+        #[test]
+        #[should_panic]
+        fn duplicate_node() {
+            assert_valid_permutation(&Route::new(vec![0, 1, 1]), 3);
+        }
+    }
+    mod test_arbitrary_route {
+        use super::*;
+        #[test]
+        fn seeds_differ() {
+            assert_ne!(arbitrary_route(20, 1), arbitrary_route(20, 2));
+        }
+    }
+}
@@ -12,6 +12,23 @@ pub fn test_dist_mat() -> DistanceMat {
         vec![2.0, 3.0, 0.0],
     ])
 }
+/// Get a distance matrix of `n_nodes` points that are evenly spaced on the unit circle. The
+/// optimal route visits the nodes in order and has a length of roughly `2 * pi`.
+///
+/// # Arguments
+///
+/// * `n_nodes` - The number of points on the circle.
+///
+pub fn circle_dist_mat(n_nodes: usize) -> DistanceMat {
+    DistanceMat::from_coordinates(
+        &(0..n_nodes)
+            .map(|node| {
+                let angle = 2.0 * std::f64::consts::PI * node as f64 / n_nodes as f64;
+                (angle.cos(), angle.sin())
+            })
+            .collect::<Vec<(f64, f64)>>(),
+    )
+}
 /// Test if a sequence is a valid permutation for another sequence.
 ///
 /// Permuation means that it consists exactly all elements from the
@@ -49,26 +66,26 @@ mod tests {
         use super::*;
         #[test]
         fn same_subsequence() {
-            valid_permutation(&vec![1, 2, 3], &vec![1, 2, 3]);
+            valid_permutation(&[1, 2, 3], &[1, 2, 3]);
         }
         #[test]
         fn actual_permuation() {
-            valid_permutation(&vec![1, 2, 3], &vec![3, 1, 2]);
+            valid_permutation(&[1, 2, 3], &[3, 1, 2]);
         }
         #[test]
         #[should_panic]
         fn invalid_permuation_too_many() {
-            valid_permutation(&vec![1, 2, 3], &vec![3, 1, 2, 3]);
+            valid_permutation(&[1, 2, 3], &[3, 1, 2, 3]);
         }
         #[test]
         #[should_panic]
         fn invalid_permuation_too_few() {
-            valid_permutation(&vec![1, 2, 3], &vec![3, 1]);
+            valid_permutation(&[1, 2, 3], &[3, 1]);
         }
         #[test]
         #[should_panic]
         fn invalid_permuation_wrong_elem() {
-            valid_permutation(&vec![1, 2, 3], &vec![3, 1, 4]);
+            valid_permutation(&[1, 2, 3], &[3, 1, 4]);
         }
     }
 }
@@ -1,8 +1,6 @@
 use crate::route::Route;
 use crate::subsequence::Subsequence;
-use rand::seq::SliceRandom;
-use rand::thread_rng;
-use rand::Rng;
+use rand::{Rng, RngCore};
 use std::cmp::max;
 use std::cmp::Ordering;
 use std::ops::Range;
@@ -16,20 +14,146 @@ use std::ops::Range;
 pub fn get_random_elem_from_range<T>(range: Range<T>) -> T
 where
     T: std::cmp::PartialOrd + rand::distributions::uniform::SampleUniform,
+{
+    get_random_elem_from_range_with(range, &mut rand::thread_rng())
+}
+/// Get a random alement from a range using the random number generator provided.
+///
+/// # Arguments
+///
+/// * `range` - The range that should be sampled.
+/// * `rng` - The random number generator used for sampling.
+///
+pub fn get_random_elem_from_range_with<T, R>(range: Range<T>, rng: &mut R) -> T
+where
+    T: std::cmp::PartialOrd + rand::distributions::uniform::SampleUniform,
+    R: Rng + ?Sized,
 {
     if !range.is_empty() {
-        rand::thread_rng().gen_range::<T, Range<T>>(range)
+        rng.gen_range::<T, Range<T>>(range)
     } else {
         range.start
     }
 }
+/// Draw a random number from a range of indexes. Unlike `get_random_elem_from_range_with` the
+/// number is computed from a single `u64` of the generator in this crate, so a generator that
+/// produces the same raw numbers always leads to the same draws, whatever the version of `rand`.
+/// An empty range gives its start.
+///
+/// # Arguments
+///
+/// * `range` - The range that should be sampled.
+/// * `rng` - The random number generator used for sampling.
+///
+pub fn random_index_with<R>(range: Range<usize>, rng: &mut R) -> usize
+where
+    R: RngCore + ?Sized,
+{
+    if range.is_empty() {
+        return range.start;
+    }
+    // Scale the raw number to the length of the range, which is unbiased up to a negligible
+    // `range.len() / 2^64`.
+    range.start + ((u128::from(rng.next_u64()) * range.len() as u128) >> 64) as usize
+}
+/// Draw a random number between 0 (inclusive) and 1 (exclusive) from the 53 upper bits of a
+/// single `u64` of the generator, so the draw never changes with the version of `rand`.
+///
+/// # Arguments
+///
+/// * `rng` - The random number generator used for sampling.
+///
+pub fn random_unit_with<R>(rng: &mut R) -> f64
+where
+    R: RngCore + ?Sized,
+{
+    (rng.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+}
+/// Shuffle a slice in place with the Fisher–Yates shuffle, drawing every position with
+/// `random_index_with`.
+///
+/// # Arguments
+///
+/// * `data` - The slice that should be shuffled.
+/// * `rng` - The random number generator used for shuffling.
+///
+pub fn shuffle_with<T, R>(data: &mut [T], rng: &mut R)
+where
+    R: RngCore + ?Sized,
+{
+    for idx in (1..data.len()).rev() {
+        data.swap(idx, random_index_with(0..(idx + 1), rng));
+    }
+}
+/// Draw `amount` distinct indexes below `length` in random order, with the first `amount` steps
+/// of a Fisher–Yates shuffle.
+///
+/// # Arguments
+///
+/// * `length` - All indexes are smaller than `length`.
+/// * `amount` - How many indexes are drawn.
+/// * `rng` - The random number generator used for sampling.
+///
+/// # Panics
+///
+/// If `amount` is larger than `length`.
+///
+pub fn sample_indexes_with<R>(length: usize, amount: usize, rng: &mut R) -> Vec<usize>
+where
+    R: RngCore + ?Sized,
+{
+    assert!(
+        amount <= length,
+        "Cannot sample {} distinct indexes below {}.",
+        amount,
+        length
+    );
+    let mut indexes = (0..length).collect::<Vec<usize>>();
+    for idx in 0..amount {
+        indexes.swap(idx, random_index_with(idx..length, rng));
+    }
+    indexes.truncate(amount);
+    indexes
+}
+/// Draw the position of a weight, with a probability proportional to the weight. Returns `None`
+/// if a weight is negative or not finite, or if all weights are 0.
+///
+/// # Arguments
+///
+/// * `weights` - The weights of all positions.
+/// * `rng` - The random number generator used for sampling.
+///
+pub fn weighted_index_with<R>(weights: &[f64], rng: &mut R) -> Option<usize>
+where
+    R: RngCore + ?Sized,
+{
+    if weights
+        .iter()
+        .any(|weight| !weight.is_finite() || *weight < 0.0)
+    {
+        return None;
+    }
+    let total = weights.iter().sum::<f64>();
+    if !(total > 0.0 && total.is_finite()) {
+        return None;
+    }
+    let mut remaining = random_unit_with(rng) * total;
+    for (idx, weight) in weights.iter().enumerate() {
+        if remaining < *weight {
+            return Some(idx);
+        }
+        remaining -= weight;
+    }
+    // Rounding errors can leave a tiny remainder, which belongs to the last positive weight.
+    weights.iter().rposition(|weight| *weight > 0.0)
+}
 /// Generate a re-ordered vector.
 ///
 /// # Arguments
 ///
 /// * `data` - The original slice that should be re-ordered.
 /// * `put_before_index` - The element as position `move_idx` should be positioned before
-/// the element at `put_before_index`.
+///   the element at `put_before_index`.
 /// * `move_idx` - The position of the element that should be moved.
 ///
 pub fn change_order(data: &[usize], put_before_idx: usize, move_idx: usize) -> Vec<usize> {
@@ -38,10 +162,7 @@ pub fn change_order(data: &[usize], put_before_idx: usize, move_idx: usize) -> V
         let move_item = data[move_idx];
         new_data.remove(move_idx);
         let reset_index = (move_idx < put_before_idx) as usize;
-        new_data.insert(
-            max(put_before_idx, reset_index as usize) - reset_index as usize,
-            move_item,
-        );
+        new_data.insert(max(put_before_idx, reset_index) - reset_index, move_item);
     }
     new_data
 }
@@ -106,16 +227,20 @@ pub fn is_in(value: usize, elements: &[usize]) -> bool {
     }
     false
 }
-/// Give a random permutation of a slice. No guarantee that
-/// the vector is actually changed.
+/// Give a random permutation of a slice, drawn with the given random number generator. No
+/// guarantee that the vector is actually changed.
 ///
 /// # Arguments
 ///
 /// * `vec` - The slice that should be permutated.
+/// * `rng` - The random number generator the permutation is drawn with.
 ///
-pub fn random_permutation(vec: &[usize]) -> Vec<usize> {
+pub fn random_permutation_with<R>(vec: &[usize], rng: &mut R) -> Vec<usize>
+where
+    R: RngCore + ?Sized,
+{
     let mut this_vec: Vec<usize> = vec.to_vec();
-    this_vec.shuffle(&mut thread_rng());
+    shuffle_with(&mut this_vec, rng);
     this_vec
 }
 
@@ -149,6 +274,54 @@ fn reverse_ordering(ordering: Ordering) -> Ordering {
         Ordering::Equal => Ordering::Equal,
     }
 }
+/// A small xorshift64* random number generator. Unlike `StdRng`, whose algorithm may change with
+/// new versions of `rand`, it produces the same numbers forever, which makes it suitable for
+/// reproducible runs together with the samplers in this module, e.g. `random_index_with`. It is
+/// not suitable for anything that needs cryptographic quality.
+#[derive(Debug, Clone)]
+pub struct XorShiftRng {
+    state: u64,
+}
+impl XorShiftRng {
+    /// Create a new generator from a seed. A seed of 0 is replaced by a fixed non-zero value, as
+    /// the state of a xorshift generator must never be zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed that determines all numbers the generator produces.
+    ///
+    pub fn new(seed: u64) -> Self {
+        XorShiftRng {
+            state: if seed == 0 {
+                0x9E37_79B9_7F4A_7C15
+            } else {
+                seed
+            },
+        }
+    }
+}
+// Provide the raw random numbers, so the generator can be used wherever an `Rng` is expected.
+impl rand::RngCore for XorShiftRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -167,6 +340,13 @@ mod tests {
         fn sample_empty_range() {
             assert_eq!(get_random_elem_from_range(0..0), 0);
         }
+        #[test]
+        fn sample_empty_range_with_rng() {
+            assert_eq!(
+                get_random_elem_from_range_with(5..5, &mut rand::thread_rng()),
+                5
+            );
+        }
     }
     mod test_remove_elem {
         use super::*;
@@ -199,56 +379,56 @@ mod tests {
         use super::*;
         #[test]
         fn put_before_first() {
-            assert_eq!(change_order(&vec![1, 2, 3, 4], 0, 1), vec![2, 1, 3, 4]);
+            assert_eq!(change_order(&[1, 2, 3, 4], 0, 1), vec![2, 1, 3, 4]);
         }
         #[test]
         fn put_last_before_first() {
-            assert_eq!(change_order(&vec![1, 2, 3, 4], 0, 3), vec![4, 1, 2, 3]);
+            assert_eq!(change_order(&[1, 2, 3, 4], 0, 3), vec![4, 1, 2, 3]);
         }
         #[test]
         fn put_first_before_second() {
-            assert_eq!(change_order(&vec![1, 2, 3, 4], 1, 0), vec![1, 2, 3, 4]);
+            assert_eq!(change_order(&[1, 2, 3, 4], 1, 0), vec![1, 2, 3, 4]);
         }
         #[test]
         fn put_before_second() {
-            assert_eq!(change_order(&vec![1, 2, 3, 4], 1, 2), vec![1, 3, 2, 4]);
+            assert_eq!(change_order(&[1, 2, 3, 4], 1, 2), vec![1, 3, 2, 4]);
         }
         #[test]
         fn put_last_before_second() {
-            assert_eq!(change_order(&vec![1, 2, 3, 4], 1, 3), vec![1, 4, 2, 3]);
+            assert_eq!(change_order(&[1, 2, 3, 4], 1, 3), vec![1, 4, 2, 3]);
         }
         #[test]
         fn put_first_before_last() {
-            assert_eq!(change_order(&vec![1, 2, 3, 4], 3, 0), vec![2, 3, 1, 4]);
+            assert_eq!(change_order(&[1, 2, 3, 4], 3, 0), vec![2, 3, 1, 4]);
         }
         #[test]
         fn put_fourth_before_fourth() {
-            assert_eq!(change_order(&vec![1, 2, 3, 4], 3, 3), vec![1, 2, 3, 4]);
+            assert_eq!(change_order(&[1, 2, 3, 4], 3, 3), vec![1, 2, 3, 4]);
         }
         #[test]
         fn put_first_before_first() {
-            assert_eq!(change_order(&vec![1, 2, 3, 4], 3, 3), vec![1, 2, 3, 4]);
+            assert_eq!(change_order(&[1, 2, 3, 4], 3, 3), vec![1, 2, 3, 4]);
         }
         #[test]
         fn test_change_order_move_first() {
-            assert_eq!(change_order(&vec![1, 2, 3], 1, 0), vec![1, 2, 3])
+            assert_eq!(change_order(&[1, 2, 3], 1, 0), vec![1, 2, 3])
         }
         #[test]
         fn test_change_order_move_middle() {
-            assert_eq!(change_order(&vec![1, 2, 3], 0, 1), vec![2, 1, 3])
+            assert_eq!(change_order(&[1, 2, 3], 0, 1), vec![2, 1, 3])
         }
 
         #[test]
         fn test_change_order_move_last() {
-            assert_eq!(change_order(&vec![1, 2, 3], 0, 2), vec![3, 1, 2])
+            assert_eq!(change_order(&[1, 2, 3], 0, 2), vec![3, 1, 2])
         }
         #[test]
         fn test_change_order_move_first_before_last() {
-            assert_eq!(change_order(&vec![1, 2, 3], 2, 0), vec![2, 1, 3])
+            assert_eq!(change_order(&[1, 2, 3], 2, 0), vec![2, 1, 3])
         }
         #[test]
         fn test_change_order_move_middle_before_last() {
-            assert_eq!(change_order(&vec![1, 2, 3], 2, 1), vec![1, 2, 3])
+            assert_eq!(change_order(&[1, 2, 3], 2, 1), vec![1, 2, 3])
         }
     }
     mod test_ordered_crossover {
@@ -375,43 +555,45 @@ mod tests {
         use super::*;
         #[test]
         fn not_in() {
-            assert_eq!(is_in(0, &[1, 2, 3]), false)
+            assert!(!is_in(0, &[1, 2, 3]))
         }
         #[test]
         fn not_in_empty_sequence() {
-            assert_eq!(is_in(0, &Vec::<usize>::new()), false)
+            assert!(!is_in(0, &Vec::<usize>::new()))
         }
         #[test]
         fn value_is_in() {
-            assert_eq!(is_in(0, &[1, 0, 3]), true)
+            assert!(is_in(0, &[1, 0, 3]))
         }
         #[test]
         fn value_is_in_duplicated() {
-            assert_eq!(is_in(0, &[0, 1, 0, 3]), true)
+            assert!(is_in(0, &[0, 1, 0, 3]))
         }
     }
     mod test_random_permutation {
         use super::*;
         use crate::test_utils::valid_permutation;
         #[test]
-        #[test]
-        #[test]
-        #[test]
         fn simple_test() {
             let main_vec = (0..10).collect::<Vec<usize>>();
-            valid_permutation(&main_vec, &random_permutation(&main_vec));
+            for _ in 0..4 {
+                valid_permutation(
+                    &main_vec,
+                    &random_permutation_with(&main_vec, &mut rand::thread_rng()),
+                );
+            }
         }
     }
     mod test_argsort {
         use super::*;
         #[test]
         fn four_floats() {
-            assert_eq!(argsort(&vec![1.0, 5.0, 3.0, 6.0]), vec![3, 1, 2, 0]);
+            assert_eq!(argsort(&[1.0, 5.0, 3.0, 6.0]), vec![3, 1, 2, 0]);
         }
         #[test]
         fn thirteen_floats() {
             assert_eq!(
-                argsort(&vec![
+                argsort(&[
                     13.0, 14.0, 12.0, 10.0, 22.0, 6.0, 16.0, 24.0, 18.0, 23.0, 15.0, 11.0, 17.0
                 ]),
                 vec![7, 9, 4, 8, 12, 6, 10, 1, 0, 2, 11, 3, 5],
@@ -420,7 +602,7 @@ mod tests {
 
         #[test]
         fn five_isize() {
-            assert_eq!(argsort(&vec![2, 5, 3, 4, 1, 6]), vec![5, 1, 3, 2, 0, 4]);
+            assert_eq!(argsort(&[2, 5, 3, 4, 1, 6]), vec![5, 1, 3, 2, 0, 4]);
         }
     }
     mod test_reverse_ordering {
@@ -438,4 +620,170 @@ mod tests {
             assert_eq!(reverse_ordering(Ordering::Equal), Ordering::Equal)
         }
     }
+    mod test_random_index_with {
+        use super::*;
+        #[test]
+        fn known_draws() {
+            let mut rng = XorShiftRng::new(1);
+            assert_eq!(
+                (0..5)
+                    .map(|_| random_index_with(0..10, &mut rng))
+                    .collect::<Vec<usize>>(),
+                vec![2, 6, 7, 3, 0]
+            );
+        }
+        #[test]
+        fn within_range() {
+            let mut rng = XorShiftRng::new(2);
+            for _ in 0..1000 {
+                assert!((3..7).contains(&random_index_with(3..7, &mut rng)));
+            }
+        }
+        #[test]
+        fn empty_range() {
+            assert_eq!(random_index_with(5..5, &mut XorShiftRng::new(3)), 5);
+        }
+    }
+    mod test_random_unit_with {
+        use super::*;
+        #[test]
+        fn known_draws() {
+            let mut rng = XorShiftRng::new(1);
+            assert_eq!(
+                (0..3)
+                    .map(|_| format!("{:.6}", random_unit_with(&mut rng)))
+                    .collect::<Vec<String>>(),
+                vec!["0.280835", "0.671137", "0.725846"]
+            );
+        }
+        #[test]
+        fn between_zero_and_one() {
+            let mut rng = XorShiftRng::new(2);
+            for _ in 0..1000 {
+                assert!((0.0..1.0).contains(&random_unit_with(&mut rng)));
+            }
+        }
+    }
+    mod test_shuffle_with {
+        use super::*;
+        use crate::test_utils::valid_permutation;
+        #[test]
+        fn known_shuffle() {
+            let mut data = (0..6).collect::<Vec<usize>>();
+            shuffle_with(&mut data, &mut XorShiftRng::new(1));
+            assert_eq!(data, vec![5, 4, 0, 2, 3, 1]);
+        }
+        #[test]
+        fn is_permutation() {
+            let mut rng = XorShiftRng::new(2);
+            let original = (0..10).collect::<Vec<usize>>();
+            for _ in 0..100 {
+                let mut data = original.clone();
+                shuffle_with(&mut data, &mut rng);
+                valid_permutation(&original, &data);
+            }
+        }
+        #[test]
+        fn empty_slice() {
+            let mut data: Vec<usize> = Vec::new();
+            shuffle_with(&mut data, &mut XorShiftRng::new(3));
+            assert!(data.is_empty());
+        }
+    }
+    mod test_sample_indexes_with {
+        use super::*;
+        #[test]
+        fn known_sample() {
+            assert_eq!(
+                sample_indexes_with(10, 3, &mut XorShiftRng::new(1)),
+                vec![2, 7, 1]
+            );
+        }
+        #[test]
+        fn distinct_and_in_range() {
+            let mut rng = XorShiftRng::new(2);
+            for _ in 0..100 {
+                let mut sample = sample_indexes_with(8, 5, &mut rng);
+                assert!(sample.iter().all(|idx| *idx < 8));
+                sample.sort_unstable();
+                sample.dedup();
+                assert_eq!(sample.len(), 5);
+            }
+        }
+        #[test]
+        fn all_indexes() {
+            let mut sample = sample_indexes_with(4, 4, &mut XorShiftRng::new(3));
+            sample.sort_unstable();
+            assert_eq!(sample, vec![0, 1, 2, 3]);
+        }
+        #[test]
+        #[should_panic]
+        fn too_many_indexes() {
+            sample_indexes_with(3, 4, &mut XorShiftRng::new(4));
+        }
+    }
+    mod test_weighted_index_with {
+        use super::*;
+        #[test]
+        fn known_picks() {
+            let mut rng = XorShiftRng::new(1);
+            assert_eq!(
+                (0..5)
+                    .map(|_| weighted_index_with(&[1.0, 2.0, 3.0], &mut rng).unwrap())
+                    .collect::<Vec<usize>>(),
+                vec![1, 2, 2, 1, 0]
+            );
+        }
+        #[test]
+        fn proportional_to_weights() {
+            let mut rng = XorShiftRng::new(2);
+            let mut counts = [0; 3];
+            for _ in 0..6000 {
+                counts[weighted_index_with(&[1.0, 0.0, 2.0], &mut rng).unwrap()] += 1;
+            }
+            assert_eq!(counts[1], 0);
+            assert!((1700..2300).contains(&counts[0]));
+            assert!((3700..4300).contains(&counts[2]));
+        }
+        #[test]
+        fn invalid_weights() {
+            let mut rng = XorShiftRng::new(3);
+            assert_eq!(weighted_index_with(&[], &mut rng), None);
+            assert_eq!(weighted_index_with(&[0.0, 0.0], &mut rng), None);
+            assert_eq!(weighted_index_with(&[1.0, -1.0], &mut rng), None);
+            assert_eq!(weighted_index_with(&[1.0, f64::INFINITY], &mut rng), None);
+            assert_eq!(weighted_index_with(&[1.0, f64::NAN], &mut rng), None);
+        }
+    }
+    mod test_xor_shift_rng {
+        use super::*;
+        use rand::RngCore;
+        #[test]
+        fn known_sequence() {
+            let mut rng = XorShiftRng::new(1);
+            assert_eq!(
+                [rng.next_u64(), rng.next_u64(), rng.next_u64()],
+                [
+                    5180492295206395165,
+                    12380297144915551517,
+                    13389498078930870103
+                ]
+            );
+        }
+        #[test]
+        fn zero_seed_is_usable() {
+            let mut rng = XorShiftRng::new(0);
+            assert_ne!(rng.next_u64(), rng.next_u64());
+        }
+        #[test]
+        fn fill_bytes_uses_next_u64() {
+            let mut bytes = [0; 12];
+            XorShiftRng::new(7).fill_bytes(&mut bytes);
+            let mut rng = XorShiftRng::new(7);
+            let first = rng.next_u64().to_le_bytes();
+            let second = rng.next_u64().to_le_bytes();
+            assert_eq!(bytes[..8], first);
+            assert_eq!(bytes[8..], second[..4]);
+        }
+    }
 }
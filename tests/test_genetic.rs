@@ -24,7 +24,7 @@ fn run_evolution() {
     );
     let routes = Routes::random(size_generation, distances.n_units());
     let max_fit = routes.get_n_fittest(1, &distances)[0].fitness(&distances);
-    let routes = evolve_population(routes, n_generations, size_generation, &distances, 0);
+    let routes = evolve_population(routes, n_generations, size_generation, &distances, 0).unwrap();
     let max_fit_new = routes.get_n_fittest(1, &distances)[0].fitness(&distances);
     // Assert after optimizing, the routes is fitter then before.
     assert!(max_fit <= max_fit_new);
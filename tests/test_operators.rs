@@ -0,0 +1,31 @@
+use genetic_algo::route::{MutationKind, Route};
+use genetic_algo::test_support::{arbitrary_route, assert_valid_permutation};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+// Property test: every built-in mutation operator turns a valid route into a valid route, for
+// routes of every length and many seeds. `mutate_with` cannot mutate routes with two nodes yet, so
+// it is only applied to routes with at least three nodes.
+#[test]
+fn mutations_preserve_permutations() {
+    for n_nodes in 2..12 {
+        for seed in 0..100 {
+            let route = arbitrary_route(n_nodes, seed);
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut mutated_routes: Vec<Route> = vec![
+                route.mutate_each_gene(0.5, &mut rng),
+                route.double_bridge(&mut rng),
+                route.maybe_mutate(1.0, MutationKind::Insertion, &mut rng),
+                route.maybe_mutate(1.0, MutationKind::Swap, &mut rng),
+                route.maybe_mutate(1.0, MutationKind::Inversion, &mut rng),
+                route.maybe_mutate(1.0, MutationKind::DoubleBridge, &mut rng),
+            ];
+            if n_nodes > 2 {
+                mutated_routes.push(route.clone().mutate_with(1.0, &mut rng));
+            }
+            for mutated in mutated_routes.iter() {
+                assert_valid_permutation(mutated, n_nodes);
+            }
+        }
+    }
+}